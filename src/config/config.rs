@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use config::{Config, File};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::time::Duration;
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -14,6 +15,8 @@ pub struct AppCfg {
     pub strategy: StrategyCfg,
     #[serde(default)]
     pub llm: LlmCfg,
+    #[serde(default)]
+    pub prompts: PromptsCfg,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -26,6 +29,15 @@ pub struct LlmCfg {
     pub base_url: String,
     #[serde(default = "default_llm_rate_limit", rename = "rateLimitRpm")]
     pub rate_limit_rpm: u32,
+    /// Per-model token prices, keyed by model name (or model name prefix).
+    #[serde(default)]
+    pub pricing: HashMap<String, LlmPriceCfg>,
+    #[serde(default)]
+    pub budget: LlmBudgetCfg,
+    #[serde(default, rename = "responseFormat")]
+    pub response_format: LlmResponseFormat,
+    #[serde(default)]
+    pub retry: LlmRetryCfg,
 }
 
 impl Default for LlmCfg {
@@ -35,6 +47,10 @@ impl Default for LlmCfg {
             model: default_llm_model(),
             base_url: default_llm_base_url(),
             rate_limit_rpm: default_llm_rate_limit(),
+            pricing: HashMap::new(),
+            budget: LlmBudgetCfg::default(),
+            response_format: LlmResponseFormat::default(),
+            retry: LlmRetryCfg::default(),
         }
     }
 }
@@ -55,6 +71,163 @@ fn default_llm_rate_limit() -> u32 {
     500
 }
 
+/// Versioned prompt templates. Templates are text files with `{{news}}`,
+/// `{{market}}`, `{{outcomes}}`, `{{price}}` and `{{date}}` placeholders and are
+/// referenced as "name@version".
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct PromptsCfg {
+    #[serde(default)]
+    pub templates: Vec<PromptTemplateCfg>,
+    /// Template used when no category route matches; the built-in "analyst@v1" if unset.
+    #[serde(default)]
+    pub default: Option<String>,
+    /// Market category/tag (case-insensitive) -> template id.
+    #[serde(default, rename = "byCategory")]
+    pub by_category: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PromptTemplateCfg {
+    pub name: String,
+    pub version: String,
+    pub file: String,
+}
+
+/// USD price per one million tokens.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct LlmPriceCfg {
+    #[serde(default, rename = "inputPerMTok")]
+    pub input_per_mtok: f64,
+    #[serde(default, rename = "outputPerMTok")]
+    pub output_per_mtok: f64,
+}
+
+/// Spend caps in USD. A cap of 0 disables that window.
+#[derive(Debug, Deserialize, Clone)]
+pub struct LlmBudgetCfg {
+    #[serde(default, rename = "dailyUsd")]
+    pub daily_usd: f64,
+    #[serde(default, rename = "monthlyUsd")]
+    pub monthly_usd: f64,
+    /// Fraction of a cap after which the analyst degrades.
+    #[serde(default = "default_degrade_at_pct", rename = "degradeAtPct")]
+    pub degrade_at_pct: f64,
+    /// Cheaper model used while degraded. Keeps the primary model if unset.
+    #[serde(default, rename = "fallbackModel")]
+    pub fallback_model: Option<String>,
+    #[serde(
+        default = "default_degraded_top_candidates",
+        rename = "degradedTopCandidates"
+    )]
+    pub degraded_top_candidates: usize,
+}
+
+impl Default for LlmBudgetCfg {
+    fn default() -> Self {
+        Self {
+            daily_usd: 0.0,
+            monthly_usd: 0.0,
+            degrade_at_pct: default_degrade_at_pct(),
+            fallback_model: None,
+            degraded_top_candidates: default_degraded_top_candidates(),
+        }
+    }
+}
+
+fn default_degrade_at_pct() -> f64 {
+    0.8
+}
+
+fn default_degraded_top_candidates() -> usize {
+    1
+}
+
+/// How the model is asked to return JSON. `jsonSchema` (OpenAI structured outputs)
+/// is the strictest; use `jsonObject` or `text` for backends that don't support it.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LlmResponseFormat {
+    #[default]
+    JsonSchema,
+    JsonObject,
+    Text,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LlmRetryCfg {
+    /// Upper bound for a single attempt, including provider-side 429/5xx retries.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_llm_request_timeout",
+        rename = "requestTimeout"
+    )]
+    pub request_timeout: Duration,
+    #[serde(
+        with = "humantime_serde",
+        default = "default_llm_backoff_initial",
+        rename = "backoffInitial"
+    )]
+    pub backoff_initial: Duration,
+    /// Total time spent retrying 429/5xx before giving up.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_llm_backoff_max_elapsed",
+        rename = "backoffMaxElapsed"
+    )]
+    pub backoff_max_elapsed: Duration,
+    /// Re-asks with a repair prompt when the output is not valid JSON.
+    #[serde(default = "default_llm_parse_retries", rename = "maxParseRetries")]
+    pub max_parse_retries: u32,
+    #[serde(
+        default = "default_llm_breaker_threshold",
+        rename = "breakerFailureThreshold"
+    )]
+    pub breaker_failure_threshold: u32,
+    #[serde(
+        with = "humantime_serde",
+        default = "default_llm_breaker_cooldown",
+        rename = "breakerCooldown"
+    )]
+    pub breaker_cooldown: Duration,
+}
+
+impl Default for LlmRetryCfg {
+    fn default() -> Self {
+        Self {
+            request_timeout: default_llm_request_timeout(),
+            backoff_initial: default_llm_backoff_initial(),
+            backoff_max_elapsed: default_llm_backoff_max_elapsed(),
+            max_parse_retries: default_llm_parse_retries(),
+            breaker_failure_threshold: default_llm_breaker_threshold(),
+            breaker_cooldown: default_llm_breaker_cooldown(),
+        }
+    }
+}
+
+fn default_llm_request_timeout() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_llm_backoff_initial() -> Duration {
+    Duration::from_millis(500)
+}
+
+fn default_llm_backoff_max_elapsed() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_llm_parse_retries() -> u32 {
+    2
+}
+
+fn default_llm_breaker_threshold() -> u32 {
+    5
+}
+
+fn default_llm_breaker_cooldown() -> Duration {
+    Duration::from_secs(60)
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct HttpCfg {
     #[serde(rename = "userAgent", default = "default_ua")]
@@ -143,6 +316,33 @@ fn default_token_decimals() -> u32 {
 pub struct RssFeedCfg {
     pub id: String,
     pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Overrides `rss.refresh` for this feed.
+    #[serde(default, with = "humantime_serde")]
+    pub refresh: Option<Duration>,
+    /// Request timeout; the HTTP client's default if unset.
+    #[serde(default, with = "humantime_serde")]
+    pub timeout: Option<Duration>,
+    /// Extra request headers, e.g. a user agent or API key.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Higher priority feeds are polled and published first.
+    #[serde(default)]
+    pub priority: i32,
+    /// Labels added to every item from this feed.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Items must contain one of these (case-insensitive) or match one of
+    /// `includeRegex`, when either is set.
+    #[serde(default, rename = "includeKeywords")]
+    pub include_keywords: Vec<String>,
+    #[serde(default, rename = "excludeKeywords")]
+    pub exclude_keywords: Vec<String>,
+    #[serde(default, rename = "includeRegex")]
+    pub include_regex: Vec<String>,
+    #[serde(default, rename = "excludeRegex")]
+    pub exclude_regex: Vec<String>,
 }
 
 impl Default for RssFeedCfg {
@@ -150,16 +350,37 @@ impl Default for RssFeedCfg {
         Self {
             id: "default".to_string(),
             url: "http://localhost".to_string(),
+            enabled: true,
+            refresh: None,
+            timeout: None,
+            headers: HashMap::new(),
+            priority: 0,
+            labels: Vec::new(),
+            include_keywords: Vec::new(),
+            exclude_keywords: Vec::new(),
+            include_regex: Vec::new(),
+            exclude_regex: Vec::new(),
         }
     }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct RssCfg {
+    /// Default poll interval per feed.
     #[serde(with = "humantime_serde")]
     pub refresh: Duration,
     pub concurrency: usize,
+    /// Cap on the exponential backoff of a failing feed.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_rss_max_backoff",
+        rename = "maxBackoff"
+    )]
+    pub max_backoff: Duration,
     pub feeds: Vec<RssFeedCfg>,
+    /// Fetching of linked article bodies.
+    #[serde(default)]
+    pub articles: ArticleCfg,
 }
 
 impl Default for RssCfg {
@@ -167,11 +388,72 @@ impl Default for RssCfg {
         Self {
             refresh: Duration::from_secs(60),
             concurrency: 1,
+            max_backoff: default_rss_max_backoff(),
             feeds: vec![RssFeedCfg::default()],
+            articles: ArticleCfg::default(),
+        }
+    }
+}
+
+fn default_rss_max_backoff() -> Duration {
+    Duration::from_secs(600)
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ArticleCfg {
+    /// Fetch each item's link and attach the extracted article text.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(with = "humantime_serde", default = "default_article_timeout")]
+    pub timeout: Duration,
+    /// Pages larger than this are skipped.
+    #[serde(default = "default_article_max_bytes", rename = "maxBytes")]
+    pub max_bytes: usize,
+    /// Extracted text is truncated to this many characters.
+    #[serde(default = "default_article_max_chars", rename = "maxChars")]
+    pub max_chars: usize,
+    /// Minimum time between fetches from the same domain. Items wait for
+    /// their domain's next slot; those more than `timeout` away keep their
+    /// description only.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_article_domain_interval",
+        rename = "domainInterval"
+    )]
+    pub domain_interval: Duration,
+    #[serde(default = "default_article_concurrency")]
+    pub concurrency: usize,
+}
+
+impl Default for ArticleCfg {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout: default_article_timeout(),
+            max_bytes: default_article_max_bytes(),
+            max_chars: default_article_max_chars(),
+            domain_interval: default_article_domain_interval(),
+            concurrency: default_article_concurrency(),
         }
     }
 }
 
+fn default_article_timeout() -> Duration {
+    Duration::from_secs(5)
+}
+fn default_article_max_bytes() -> usize {
+    2 * 1024 * 1024
+}
+fn default_article_max_chars() -> usize {
+    5000
+}
+fn default_article_domain_interval() -> Duration {
+    Duration::from_secs(2)
+}
+fn default_article_concurrency() -> usize {
+    4
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct FinJuiceCfg {
     #[serde(rename = "baseUrl")]
@@ -181,6 +463,9 @@ pub struct FinJuiceCfg {
     #[serde(rename = "altUrl")]
     pub alt_url: String,
     pub info: String,
+    /// IANA timezone of the feed's `DatePublished` timestamps.
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
 }
 
 impl Default for FinJuiceCfg {
@@ -190,6 +475,7 @@ impl Default for FinJuiceCfg {
             refresh: Duration::from_secs(60),
             alt_url: "http://localhost".to_string(),
             info: "".to_string(),
+            timezone: default_timezone(),
         }
     }
 }
@@ -207,12 +493,581 @@ pub struct StrategyCfg {
         rename = "maxPositionDrawdownPct"
     )]
     pub max_position_drawdown_pct: f64,
+    #[serde(default, rename = "marketIndex")]
+    pub market_index: MarketIndexCfg,
+    #[serde(default)]
+    pub retrieval: RetrievalCfg,
+    #[serde(default)]
+    pub rerank: RerankCfg,
+    #[serde(default)]
+    pub entities: EntityDictCfg,
+    #[serde(default, rename = "timeExpressions")]
+    pub time_expressions: TimeExprCfg,
+    #[serde(default, rename = "numericCheck")]
+    pub numeric_check: NumericCheckCfg,
+    #[serde(default)]
+    pub stories: StoryCfg,
+    #[serde(default)]
+    pub sources: SourceStatsCfg,
+    #[serde(default)]
+    pub staleness: StalenessCfg,
+}
+
+/// Deterministic settlement of threshold markets ("will CPI exceed 3%?") from
+/// figures in the news. Settled markets are traded without an LLM call.
+#[derive(Debug, Deserialize, Clone)]
+pub struct NumericCheckCfg {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Probability assigned to the outcome the figures confirm.
+    #[serde(default = "default_numeric_check_probability")]
+    pub probability: f64,
+}
+
+impl Default for NumericCheckCfg {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            probability: default_numeric_check_probability(),
+        }
+    }
+}
+
+fn default_numeric_check_probability() -> f64 {
+    0.95
+}
+
+/// Story clustering: follow-ups join the story of the item they repeat,
+/// corroborate or update instead of being dropped as near-duplicates.
+#[derive(Debug, Deserialize, Clone)]
+pub struct StoryCfg {
+    /// SimHash distance at which an item joins a story without further checks.
+    #[serde(
+        default = "default_story_hamming_threshold",
+        rename = "hammingThreshold"
+    )]
+    pub hamming_threshold: u32,
+    #[serde(default = "default_story_simhash_weight", rename = "simhashWeight")]
+    pub simhash_weight: f32,
+    #[serde(default = "default_story_embedding_weight", rename = "embeddingWeight")]
+    pub embedding_weight: f32,
+    /// Minimum decayed similarity (0..1) to join an existing story.
+    #[serde(default = "default_story_min_similarity", rename = "minSimilarity")]
+    pub min_similarity: f32,
+    /// Share of new tokens that makes a follow-up a material update.
+    #[serde(default = "default_story_min_novelty", rename = "minNovelty")]
+    pub min_novelty: f32,
+    /// Similarity halves for every `halfLife` since the story was last reported.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_story_half_life",
+        rename = "halfLife"
+    )]
+    pub half_life: Duration,
+    /// Stories not reported for this long are closed.
+    #[serde(with = "humantime_serde", default = "default_story_ttl")]
+    pub ttl: Duration,
+    /// Max hashes kept in the SimHash index.
+    #[serde(default = "default_story_capacity")]
+    pub capacity: usize,
+    /// SimHash width (1..=64).
+    #[serde(default = "default_simhash_bits", rename = "simhashBits")]
+    pub simhash_bits: u32,
+    /// LSH bands the SimHash is split into; lookups are exact while
+    /// `simhashBands > hammingThreshold`, and faster with more bands.
+    #[serde(default = "default_simhash_bands", rename = "simhashBands")]
+    pub simhash_bands: u32,
+}
+
+impl Default for StoryCfg {
+    fn default() -> Self {
+        Self {
+            hamming_threshold: default_story_hamming_threshold(),
+            simhash_weight: default_story_simhash_weight(),
+            embedding_weight: default_story_embedding_weight(),
+            min_similarity: default_story_min_similarity(),
+            min_novelty: default_story_min_novelty(),
+            half_life: default_story_half_life(),
+            ttl: default_story_ttl(),
+            capacity: default_story_capacity(),
+            simhash_bits: default_simhash_bits(),
+            simhash_bands: default_simhash_bands(),
+        }
+    }
+}
+
+fn default_story_hamming_threshold() -> u32 {
+    3
+}
+
+fn default_story_simhash_weight() -> f32 {
+    0.3
+}
+
+fn default_story_embedding_weight() -> f32 {
+    0.7
+}
+
+fn default_story_min_similarity() -> f32 {
+    0.7
+}
+
+fn default_story_min_novelty() -> f32 {
+    0.3
+}
+
+fn default_story_half_life() -> Duration {
+    Duration::from_secs(24 * 3600)
+}
+
+fn default_story_ttl() -> Duration {
+    Duration::from_secs(48 * 3600)
+}
+
+fn default_story_capacity() -> usize {
+    10_000
+}
+
+fn default_simhash_bits() -> u32 {
+    64
+}
+
+fn default_simhash_bands() -> u32 {
+    4
+}
+
+/// Per-source reliability: how often a source breaks stories, how far behind
+/// the first report it usually is, and how its signals resolved.
+#[derive(Debug, Deserialize, Clone)]
+pub struct SourceStatsCfg {
+    /// History loaded at startup for the statistics.
+    #[serde(with = "humantime_serde", default = "default_source_window")]
+    pub window: Duration,
+    /// How often signal outcomes are reloaded as markets resolve.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_source_refresh_interval",
+        rename = "refreshInterval"
+    )]
+    pub refresh_interval: Duration,
+    /// Pseudo-observations pulling a thin history towards the neutral weight.
+    #[serde(default = "default_source_prior_count", rename = "priorCount")]
+    pub prior_count: f64,
+    /// Timeliness halves for every `lagHalfLife` behind the first report.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_source_lag_half_life",
+        rename = "lagHalfLife"
+    )]
+    pub lag_half_life: Duration,
+    /// Weight bounds; 1.0 is neutral.
+    #[serde(default = "default_source_min_weight", rename = "minWeight")]
+    pub min_weight: f64,
+    #[serde(default = "default_source_max_weight", rename = "maxWeight")]
+    pub max_weight: f64,
+}
+
+impl Default for SourceStatsCfg {
+    fn default() -> Self {
+        Self {
+            window: default_source_window(),
+            refresh_interval: default_source_refresh_interval(),
+            prior_count: default_source_prior_count(),
+            lag_half_life: default_source_lag_half_life(),
+            min_weight: default_source_min_weight(),
+            max_weight: default_source_max_weight(),
+        }
+    }
+}
+
+fn default_source_window() -> Duration {
+    Duration::from_secs(30 * 24 * 3600)
+}
+
+fn default_source_refresh_interval() -> Duration {
+    Duration::from_secs(3600)
+}
+
+fn default_source_prior_count() -> f64 {
+    20.0
+}
+
+fn default_source_lag_half_life() -> Duration {
+    Duration::from_secs(300)
+}
+
+fn default_source_min_weight() -> f64 {
+    0.5
+}
+
+fn default_source_max_weight() -> f64 {
+    1.5
+}
+
+/// Drops news too old to trade on before it reaches the LLM.
+#[derive(Debug, Deserialize, Clone)]
+pub struct StalenessCfg {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Oldest `published` time accepted, unless overridden in `bySource`.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_news_max_age",
+        rename = "maxAge"
+    )]
+    pub max_age: Duration,
+    /// Per-source overrides, keyed by `RawNews.feed`.
+    #[serde(default, rename = "bySource")]
+    pub by_source: HashMap<String, SourceStalenessCfg>,
+    /// Tolerated difference between the source's clock and ours; items
+    /// published further in the future are rejected.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_clock_skew",
+        rename = "clockSkew"
+    )]
+    pub clock_skew: Duration,
+    /// Items published this long before the newest item already seen from
+    /// their source are rejected; covers feeds listing items out of order.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_watermark_grace",
+        rename = "watermarkGrace"
+    )]
+    pub watermark_grace: Duration,
+    /// Reject items without a `published` time instead of passing them.
+    #[serde(default, rename = "requirePublished")]
+    pub require_published: bool,
+}
+
+impl Default for StalenessCfg {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_age: default_news_max_age(),
+            by_source: HashMap::new(),
+            clock_skew: default_clock_skew(),
+            watermark_grace: default_watermark_grace(),
+            require_published: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SourceStalenessCfg {
+    #[serde(with = "humantime_serde", rename = "maxAge")]
+    pub max_age: Duration,
+}
+
+fn default_news_max_age() -> Duration {
+    Duration::from_secs(15 * 60)
+}
+
+fn default_clock_skew() -> Duration {
+    Duration::from_secs(120)
+}
+
+fn default_watermark_grace() -> Duration {
+    Duration::from_secs(10 * 60)
+}
+
+/// How time expressions in news ("by friday", "FOMC meeting") become windows.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimeExprCfg {
+    /// IANA timezone in which days, weeks and months start, e.g. "America/New_York".
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// Read ambiguous numeric dates ("03/04/2026") as day/month rather than month/day.
+    #[serde(default, rename = "dayFirst")]
+    pub day_first: bool,
+    /// Markets resolving more than this after the end of the extracted window are
+    /// dropped by the hard filters; unbounded if unset.
+    #[serde(default, with = "humantime_serde", rename = "maxResolutionLag")]
+    pub max_resolution_lag: Option<Duration>,
+    #[serde(default, rename = "namedEvents")]
+    pub named_events: Vec<NamedEventCfg>,
+}
+
+impl Default for TimeExprCfg {
+    fn default() -> Self {
+        Self {
+            timezone: default_timezone(),
+            day_first: false,
+            max_resolution_lag: None,
+            named_events: Vec::new(),
+        }
+    }
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+/// A scheduled event that news refers to by name; resolves to its next date.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct NamedEventCfg {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub dates: Vec<chrono::NaiveDate>,
+}
+
+/// Entity dictionary files (YAML or JSON), merged in order and reloaded when
+/// they change. The built-in minimal dictionary is used when none are listed.
+#[derive(Debug, Deserialize, Clone)]
+pub struct EntityDictCfg {
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// How often the files are checked for changes.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_entity_reload_interval",
+        rename = "reloadInterval"
+    )]
+    pub reload_interval: Duration,
+}
+
+impl Default for EntityDictCfg {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            reload_interval: default_entity_reload_interval(),
+        }
+    }
+}
+
+fn default_entity_reload_interval() -> Duration {
+    Duration::from_secs(30)
 }
 
 fn default_top_candidates() -> usize {
     5
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct MarketIndexCfg {
+    /// Directory for the persistent tantivy index and embedding log; in-memory if unset.
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub ann: AnnCfg,
+    /// Max markets applied per background indexing batch (one commit each).
+    #[serde(default = "default_index_batch_size", rename = "batchSize")]
+    pub batch_size: usize,
+    /// How long the indexer waits to fill a batch before committing it.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_index_flush_interval",
+        rename = "flushInterval"
+    )]
+    pub flush_interval: Duration,
+    /// Also embed markets with a multilingual model, used for non-English news.
+    #[serde(default)]
+    pub multilingual: bool,
+}
+
+impl Default for MarketIndexCfg {
+    fn default() -> Self {
+        Self {
+            path: None,
+            ann: AnnCfg::default(),
+            batch_size: default_index_batch_size(),
+            flush_interval: default_index_flush_interval(),
+            multilingual: false,
+        }
+    }
+}
+
+fn default_index_batch_size() -> usize {
+    256
+}
+
+fn default_index_flush_interval() -> Duration {
+    Duration::from_millis(500)
+}
+
+/// HNSW parameters for semantic market search.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnnCfg {
+    /// Links per node (2x on the bottom layer).
+    #[serde(default = "default_ann_m")]
+    pub m: usize,
+    #[serde(default = "default_ann_ef_construction", rename = "efConstruction")]
+    pub ef_construction: usize,
+    /// Search beam width; higher is slower with better recall.
+    #[serde(default = "default_ann_ef_search", rename = "efSearch")]
+    pub ef_search: usize,
+}
+
+impl Default for AnnCfg {
+    fn default() -> Self {
+        Self {
+            m: default_ann_m(),
+            ef_construction: default_ann_ef_construction(),
+            ef_search: default_ann_ef_search(),
+        }
+    }
+}
+
+fn default_ann_m() -> usize {
+    16
+}
+
+fn default_ann_ef_construction() -> usize {
+    200
+}
+
+fn default_ann_ef_search() -> usize {
+    64
+}
+
+/// Hybrid BM25 + semantic candidate retrieval.
+#[derive(Debug, Deserialize, Clone)]
+pub struct RetrievalCfg {
+    /// Results requested from each of the lexical and semantic searches.
+    #[serde(default = "default_retrieval_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub fusion: FusionMethod,
+    #[serde(default = "default_fusion_weight", rename = "bm25Weight")]
+    pub bm25_weight: f32,
+    #[serde(default = "default_fusion_weight", rename = "semanticWeight")]
+    pub semantic_weight: f32,
+    /// RRF rank constant; larger values flatten the contribution of top ranks.
+    #[serde(default = "default_rrf_k", rename = "rrfK")]
+    pub rrf_k: f32,
+    #[serde(default)]
+    pub filters: RetrievalFilterCfg,
+}
+
+impl Default for RetrievalCfg {
+    fn default() -> Self {
+        Self {
+            limit: default_retrieval_limit(),
+            fusion: FusionMethod::default(),
+            bm25_weight: default_fusion_weight(),
+            semantic_weight: default_fusion_weight(),
+            rrf_k: default_rrf_k(),
+            filters: RetrievalFilterCfg::default(),
+        }
+    }
+}
+
+/// Structured filters applied inside both retrievers (not after them), so the
+/// `limit` window only holds tradeable markets. Categories match Gamma
+/// categories and tag labels, case-insensitively.
+#[derive(Debug, Deserialize, Clone)]
+pub struct RetrievalFilterCfg {
+    #[serde(default, rename = "includeCategories")]
+    pub include_categories: Vec<String>,
+    #[serde(default, rename = "excludeCategories")]
+    pub exclude_categories: Vec<String>,
+    #[serde(default, rename = "minLiquidity")]
+    pub min_liquidity: Option<f64>,
+    #[serde(default, rename = "minVolume")]
+    pub min_volume: Option<f64>,
+    /// Only markets resolving at least this far in the future.
+    #[serde(default, with = "humantime_serde", rename = "minTimeToResolution")]
+    pub min_time_to_resolution: Option<Duration>,
+    /// Only markets resolving within this horizon.
+    #[serde(default, with = "humantime_serde", rename = "maxTimeToResolution")]
+    pub max_time_to_resolution: Option<Duration>,
+    #[serde(default = "default_true", rename = "activeOnly")]
+    pub active_only: bool,
+    /// Skip markets we already hold a position in.
+    #[serde(default = "default_true", rename = "excludeHeld")]
+    pub exclude_held: bool,
+}
+
+impl Default for RetrievalFilterCfg {
+    fn default() -> Self {
+        Self {
+            include_categories: Vec::new(),
+            exclude_categories: Vec::new(),
+            min_liquidity: None,
+            min_volume: None,
+            min_time_to_resolution: None,
+            max_time_to_resolution: None,
+            active_only: default_true(),
+            exclude_held: default_true(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// `rrf`: weight / (k + rank) summed over both result lists.
+/// `weighted`: weighted sum of max-normalized BM25 and cosine similarity.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FusionMethod {
+    #[default]
+    Rrf,
+    Weighted,
+}
+
+fn default_retrieval_limit() -> usize {
+    50
+}
+
+fn default_fusion_weight() -> f32 {
+    1.0
+}
+
+fn default_rrf_k() -> f32 {
+    60.0
+}
+
+/// Cross-encoder reranking of (news, market) pairs between the hard filters and the LLM.
+#[derive(Debug, Deserialize, Clone)]
+pub struct RerankCfg {
+    #[serde(default = "default_rerank_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub model: RerankModel,
+    /// Candidates scoring below this relevance (0..1) are dropped before the LLM.
+    #[serde(default = "default_min_relevance", rename = "minRelevance")]
+    pub min_relevance: f32,
+    /// Only the first N filtered candidates are scored.
+    #[serde(default = "default_rerank_max_candidates", rename = "maxCandidates")]
+    pub max_candidates: usize,
+}
+
+impl Default for RerankCfg {
+    fn default() -> Self {
+        Self {
+            enabled: default_rerank_enabled(),
+            model: RerankModel::default(),
+            min_relevance: default_min_relevance(),
+            max_candidates: default_rerank_max_candidates(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RerankModel {
+    #[default]
+    BgeRerankerBase,
+    BgeRerankerV2M3,
+    JinaRerankerV1TurboEn,
+    JinaRerankerV2BaseMultilingual,
+}
+
+fn default_rerank_enabled() -> bool {
+    true
+}
+
+fn default_min_relevance() -> f32 {
+    0.3
+}
+
+fn default_rerank_max_candidates() -> usize {
+    30
+}
+
 fn default_max_pos_drawdown() -> f64 {
     0.20 // 20%
 }
@@ -260,12 +1115,102 @@ impl AppCfg {
             !self.polymarket.gamma_markets_url.is_empty(),
             "polymarket.gammaMarketsUrl missing"
         );
+        anyhow::ensure!(
+            self.strategy
+                .time_expressions
+                .timezone
+                .parse::<chrono_tz::Tz>()
+                .is_ok(),
+            "strategy.timeExpressions.timezone must be an IANA timezone"
+        );
+        anyhow::ensure!(
+            self.financial_juice
+                .timezone
+                .parse::<chrono_tz::Tz>()
+                .is_ok(),
+            "financialJuice.timezone must be an IANA timezone"
+        );
+        anyhow::ensure!(
+            (0.5..=1.0).contains(&self.strategy.numeric_check.probability),
+            "strategy.numericCheck.probability must be within [0.5, 1]"
+        );
+        let stories = &self.strategy.stories;
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&stories.min_similarity)
+                && (0.0..=1.0).contains(&stories.min_novelty),
+            "strategy.stories.minSimilarity and minNovelty must be within [0, 1]"
+        );
+        anyhow::ensure!(
+            stories.simhash_weight >= 0.0 && stories.embedding_weight >= 0.0,
+            "strategy.stories weights must be >= 0"
+        );
+        anyhow::ensure!(
+            (1..=64).contains(&stories.simhash_bits)
+                && (1..=stories.simhash_bits).contains(&stories.simhash_bands),
+            "strategy.stories.simhashBits must be within [1, 64] and simhashBands within [1, simhashBits]"
+        );
+        let sources = &self.strategy.sources;
+        anyhow::ensure!(
+            sources.prior_count >= 0.0
+                && sources.min_weight > 0.0
+                && sources.min_weight <= sources.max_weight,
+            "strategy.sources.priorCount must be >= 0 and 0 < minWeight <= maxWeight"
+        );
+        anyhow::ensure!(
+            !sources.refresh_interval.is_zero(),
+            "strategy.sources.refreshInterval must be > 0"
+        );
         anyhow::ensure!(self.rss.concurrency > 0, "rss.concurrency must be > 0");
-        anyhow::ensure!(!self.rss.feeds.is_empty(), "rss.feeds must not be empty");
+        anyhow::ensure!(
+            self.rss.articles.concurrency > 0,
+            "rss.articles.concurrency must be > 0"
+        );
+        anyhow::ensure!(
+            self.rss.articles.max_bytes > 0 && self.rss.articles.max_chars > 0,
+            "rss.articles.maxBytes and maxChars must be > 0"
+        );
+        anyhow::ensure!(
+            self.rss.feeds.iter().any(|f| f.enabled),
+            "rss.feeds must have an enabled feed"
+        );
+        let mut feed_ids = std::collections::HashSet::new();
+        for feed in &self.rss.feeds {
+            anyhow::ensure!(
+                feed_ids.insert(feed.id.as_str()),
+                "rss.feeds: duplicate id {}",
+                feed.id
+            );
+            for pattern in feed.include_regex.iter().chain(&feed.exclude_regex) {
+                regex::Regex::new(pattern).with_context(|| {
+                    format!("rss.feeds.{}: invalid regex {:?}", feed.id, pattern)
+                })?;
+            }
+        }
         anyhow::ensure!(
             !self.financial_juice.base_url.is_empty(),
             "financialJuice.baseUrl required in non-dev env"
         );
+        anyhow::ensure!(
+            self.llm.budget.daily_usd >= 0.0 && self.llm.budget.monthly_usd >= 0.0,
+            "llm.budget caps must be >= 0"
+        );
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&self.llm.budget.degrade_at_pct),
+            "llm.budget.degradeAtPct must be within [0, 1]"
+        );
+        anyhow::ensure!(
+            self.llm.retry.request_timeout > self.llm.retry.backoff_max_elapsed,
+            "llm.retry.requestTimeout must exceed llm.retry.backoffMaxElapsed"
+        );
+        anyhow::ensure!(
+            self.strategy.retrieval.bm25_weight >= 0.0
+                && self.strategy.retrieval.semantic_weight >= 0.0,
+            "strategy.retrieval weights must be >= 0"
+        );
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&self.strategy.rerank.min_relevance),
+            "strategy.rerank.minRelevance must be within [0, 1]"
+        );
         Ok(())
     }
 }
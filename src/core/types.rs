@@ -1,6 +1,6 @@
 use anyhow::Result;
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[async_trait::async_trait]
 pub trait Actor: Send + Sync + 'static {
@@ -26,6 +26,20 @@ pub struct RawNews {
     pub published: Option<chrono::DateTime<chrono::Utc>>,
     #[allow(dead_code)]
     pub labels: Vec<String>,
+    /// Feed entry id (RSS guid, Atom id, JSON Feed id)
+    #[serde(default)]
+    #[allow(dead_code)]
+    pub guid: Option<String>,
+    #[serde(default)]
+    #[allow(dead_code)]
+    pub author: Option<String>,
+    /// Last modification time, when the feed gives one
+    #[serde(default)]
+    #[allow(dead_code)]
+    pub updated: Option<chrono::DateTime<chrono::Utc>>,
+    /// Main text of the linked article, when article fetching is enabled
+    #[serde(default)]
+    pub body: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -95,7 +109,6 @@ pub struct Execution {
 
 #[derive(Clone, Debug, Deserialize, Hash, Eq, PartialEq)]
 pub struct PolyMarketEvent {
-    #[allow(dead_code)]
     pub id: String,
     #[serde(default)]
     pub title: Option<String>,
@@ -103,6 +116,39 @@ pub struct PolyMarketEvent {
     pub description: Option<String>,
     #[serde(default)]
     pub markets: Option<Vec<PolyMarketMarket>>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<PolyTag>>,
+}
+
+impl PolyMarketEvent {
+    /// Stamp each market with its parent event ID and inherit the event's
+    /// category and tags where Gamma leaves them off the market itself.
+    pub fn propagate_to_markets(&mut self) {
+        let Some(markets) = self.markets.as_mut() else {
+            return;
+        };
+        for market in markets {
+            if market.event_id.is_none() {
+                market.event_id = Some(self.id.clone());
+            }
+            if market.category.is_none() {
+                market.category = self.category.clone();
+            }
+            if market.tags.as_ref().is_none_or(|t| t.is_empty()) {
+                market.tags = self.tags.clone();
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct PolyTag {
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub slug: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Hash, Eq, PartialEq)]
@@ -128,9 +174,64 @@ pub struct PolyMarketMarket {
     pub outcomes: Option<String>,
     #[serde(default, rename = "outcomePrices")]
     pub outcome_prices: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<PolyTag>>,
+    #[serde(default, deserialize_with = "de_opt_decimal")]
+    pub volume: Option<Decimal>,
+    #[serde(default, deserialize_with = "de_opt_decimal")]
+    pub liquidity: Option<Decimal>,
+    /// Parent Gamma event, set by `PolyMarketEvent::propagate_to_markets`.
+    #[serde(default, rename = "eventId")]
+    pub event_id: Option<String>,
+}
+
+/// Gamma sends amounts as JSON numbers, numeric strings or "".
+fn de_opt_decimal<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Decimal>, D::Error> {
+    let raw = Option::<serde_json::Value>::deserialize(d)?;
+    let text = match raw {
+        Some(serde_json::Value::String(s)) => s,
+        Some(serde_json::Value::Number(n)) => n.to_string(),
+        _ => return Ok(None),
+    };
+    let text = text.trim();
+    Ok(text
+        .parse::<Decimal>()
+        .or_else(|_| Decimal::from_scientific(text))
+        .ok())
 }
 
 impl PolyMarketMarket {
+    /// Category plus tag labels, lowercased and deduplicated, for indexing and filters.
+    pub fn tag_labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = Vec::new();
+        let candidates = self
+            .category
+            .iter()
+            .map(|c| c.as_str())
+            .chain(self.tags.iter().flatten().map(|t| t.label.as_str()));
+        for label in candidates {
+            let label = label.trim().to_lowercase();
+            if !label.is_empty() && !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        labels
+    }
+
+    /// `endDate` as a unix timestamp (seconds). Gamma uses RFC 3339, occasionally a bare date.
+    pub fn resolution_ts(&self) -> Option<i64> {
+        let end = self.end_date.as_deref()?.trim();
+        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(end) {
+            return Some(dt.timestamp());
+        }
+        chrono::NaiveDate::parse_from_str(end, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(23, 59, 59))
+            .map(|dt| dt.and_utc().timestamp())
+    }
+
     pub fn get_tokens(&self) -> Vec<MarketToken> {
         let mut tokens_vec = Vec::new();
 
@@ -290,3 +391,52 @@ impl Portfolio {
         Some(position.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gamma_event_metadata() {
+        let json = r#"{
+            "id": "903",
+            "title": "Fed decision in March?",
+            "category": "Economics",
+            "tags": [{"id": "2", "label": "Fed", "slug": "fed"}, {"label": "Economics", "slug": "economics"}],
+            "markets": [
+                {
+                    "id": "m1",
+                    "question": "Fed cuts in March?",
+                    "endDate": "2026-03-18T18:00:00Z",
+                    "volume": "123456.78",
+                    "liquidity": 9876.5,
+                    "active": true
+                },
+                {
+                    "id": "m2",
+                    "question": "Fed hikes in March?",
+                    "endDate": "2026-03-18",
+                    "volume": "",
+                    "tags": [{"label": "Rates"}]
+                }
+            ]
+        }"#;
+
+        let mut event: PolyMarketEvent = serde_json::from_str(json).unwrap();
+        event.propagate_to_markets();
+        let markets = event.markets.unwrap();
+
+        let m1 = &markets[0];
+        assert_eq!(m1.event_id.as_deref(), Some("903"));
+        assert_eq!(m1.volume, Some(Decimal::new(12345678, 2)));
+        assert_eq!(m1.liquidity, Some(Decimal::new(98765, 1)));
+        assert_eq!(m1.tag_labels(), vec!["economics", "fed"]);
+        assert_eq!(m1.resolution_ts(), Some(1773856800));
+
+        // Own tags win over the event's; empty amounts are None
+        let m2 = &markets[1];
+        assert_eq!(m2.tag_labels(), vec!["economics", "rates"]);
+        assert_eq!(m2.volume, None);
+        assert!(m2.resolution_ts().is_some());
+    }
+}
@@ -62,10 +62,13 @@ impl MarketDiscoveryActor {
             );
         }
 
-        let events = res
+        let mut events = res
             .json::<Vec<PolyMarketEvent>>()
             .await
             .context("parsing polymarket events response")?;
+        for event in &mut events {
+            event.propagate_to_markets();
+        }
 
         metrics::counter!("discovery_fetch_events_total", "status" => "success").increment(1);
         metrics::histogram!("discovery_fetch_duration_seconds")
@@ -3,6 +3,7 @@ use crate::config::config::FinJuiceCfg;
 use crate::core::types::{Actor, RawNews};
 use anyhow::{Context, Result};
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use reqwest::Client;
 use serde_json::Value;
 use std::time::Duration;
@@ -15,14 +16,27 @@ pub struct FinJuiceActor {
     pub client: Client,
     pub cfg: FinJuiceCfg,
     pub shutdown: CancellationToken,
+    /// Timezone of `DatePublished`
+    tz: Tz,
+    /// Highest `NewsID` published; sent as `oldID` so the service only
+    /// returns newer items.
+    last_id: u64,
 }
 
-fn parse_date_published_to_utc(s: &str) -> Option<DateTime<Utc>> {
-    // Example: "2025-11-14T16:51:20.647"
-    // Treat as UTC (good enough for MVP; if it’s server-local you’re off by at most a few hours)
-    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
-        .ok()
-        .map(|naive| Utc.from_utc_datetime(&naive))
+/// A news item with its FinancialJuice id.
+#[derive(Debug)]
+pub struct FjNews {
+    pub id: u64,
+    pub news: RawNews,
+}
+
+fn parse_date_published(s: &str, tz: Tz) -> Option<DateTime<Utc>> {
+    // Example: "2025-11-14T16:51:20.647", local time in the source timezone
+    let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+    // Ambiguous around DST changes: take the earlier instant
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
 }
 
 fn extract_inner_json(xml: &str) -> Result<String> {
@@ -43,10 +57,47 @@ fn extract_inner_json(xml: &str) -> Result<String> {
         .map(|i| after_open_gt + i)
         .ok_or_else(|| anyhow::anyhow!("no </string> closing tag found"))?;
 
-    Ok(xml[after_open_gt..close_tag_start].trim().to_string())
+    // 4) The JSON is XML text content, so `&`, `<` and `>` arrive escaped
+    unescape_xml(xml[after_open_gt..close_tag_start].trim())
 }
 
-pub fn parse_fj_response_to_raw(xml: &str) -> Result<Vec<RawNews>> {
+/// Resolve the predefined XML entities and character references.
+fn unescape_xml(text: &str) -> Result<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let end = rest[amp..]
+            .find(';')
+            .map(|i| amp + i)
+            .ok_or_else(|| anyhow::anyhow!("unterminated XML entity at {:?}", &rest[amp..]))?;
+        let entity = &rest[amp + 1..end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| anyhow::anyhow!("unknown XML entity &{};", entity))?
+            }
+        };
+        out.push(c);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+pub fn parse_fj_response(xml: &str, tz: Tz) -> Result<Vec<FjNews>> {
     let json_str = extract_inner_json(xml)?;
     let v: Value = serde_json::from_str(&json_str).context("parsing FJ JSON")?;
 
@@ -63,9 +114,14 @@ pub fn parse_fj_response_to_raw(xml: &str) -> Result<Vec<RawNews>> {
             .and_then(Value::as_str)
             .unwrap_or("")
             .trim();
-        if title.is_empty() {
+        // Numeric, though older responses quote it
+        let id = item.get("NewsID").and_then(|v| {
+            v.as_u64()
+                .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+        });
+        let Some(id) = id.filter(|_| !title.is_empty()) else {
             continue; // skip broken entries
-        }
+        };
 
         let description = item
             .get("Description")
@@ -95,18 +151,22 @@ pub fn parse_fj_response_to_raw(xml: &str) -> Result<Vec<RawNews>> {
             .and_then(Value::as_str)
             .unwrap_or("");
 
-        let published = parse_date_published_to_utc(date_published_str);
+        let published = parse_date_published(date_published_str, tz);
 
         let rn = RawNews {
             feed: "FinancialJuice".to_string(),
             title: title.to_string(),
             url: url.to_string(),
-            labels: labels,
-            published: published,
+            labels,
+            guid: Some(id.to_string()),
+            author: None,
+            updated: None,
+            body: None,
+            published,
             description: description.to_string(),
         };
 
-        out.push(rn);
+        out.push(FjNews { id, news: rn });
     }
 
     Ok(out)
@@ -119,25 +179,31 @@ impl FinJuiceActor {
         cfg: FinJuiceCfg,
         shutdown: CancellationToken,
     ) -> FinJuiceActor {
+        let tz = cfg
+            .timezone
+            .parse()
+            .expect("Invalid financialJuice.timezone");
         Self {
             bus,
             client,
             cfg,
             shutdown,
+            tz,
+            last_id: 0,
         }
     }
 
     fn get_api_url(&self) -> String {
         if !self.cfg.alt_url.is_empty() {
             return format!(
-                "{}?info={}&TimeOffSet=1&tabID=0&oldID=0&TickerID=0&FeedCompanyID=0&strSearch=&extraNID=0",
-                self.cfg.alt_url, self.cfg.info,
+                "{}?info={}&TimeOffSet=1&tabID=0&oldID={}&TickerID=0&FeedCompanyID=0&strSearch=&extraNID=0",
+                self.cfg.alt_url, self.cfg.info, self.last_id,
             );
         }
         format!("{}/news", self.cfg.base_url)
     }
 
-    async fn fetch_data_from_api(&self) -> Result<Vec<RawNews>> {
+    async fn fetch_data_from_api(&self) -> Result<Vec<FjNews>> {
         let start = std::time::Instant::now();
         let url = self.get_api_url();
 
@@ -151,11 +217,21 @@ impl FinJuiceActor {
             .text()
             .await?;
 
-        parse_fj_response_to_raw(&xml).map(|events| {
-            metrics::histogram!("finjuice_fetch_duration_seconds")
-                .record(start.elapsed().as_secs_f64());
-            events
-        })
+        let events = parse_fj_response(&xml, self.tz)?;
+        metrics::histogram!("finjuice_fetch_duration_seconds")
+            .record(start.elapsed().as_secs_f64());
+        Ok(events)
+    }
+
+    /// Items newer than the last published one, oldest first. The service
+    /// may ignore `oldID`, so the filter is applied here as well.
+    fn take_new(&mut self, mut events: Vec<FjNews>) -> Vec<RawNews> {
+        events.retain(|e| e.id > self.last_id);
+        events.sort_by_key(|e| e.id);
+        if let Some(last) = events.last() {
+            self.last_id = last.id;
+        }
+        events.into_iter().map(|e| e.news).collect()
     }
 }
 #[async_trait::async_trait]
@@ -177,6 +253,10 @@ impl Actor for FinJuiceActor {
                     match self.fetch_data_from_api().await {
                         Ok(events) => {
                             metrics::counter!("finjuice_fetches_total", "status" => "success").increment(1);
+                            let listed = events.len();
+                            let events = self.take_new(events);
+                            metrics::counter!("finjuice_items_skipped_total")
+                                .increment((listed - events.len()) as u64);
                             metrics::counter!("finjuice_items_fetched_total").increment(events.len() as u64);
                             for n in events {
                                 if let Err(e) = self.bus.raw_news.publish(n).await {
@@ -199,3 +279,97 @@ impl Actor for FinJuiceActor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_startup_response() {
+        let items = parse_fj_response(
+            include_str!("../../testdata/finjuice/startup.xml"),
+            chrono_tz::Europe::London,
+        )
+        .unwrap();
+        // The entry without a NewsID is skipped
+        assert_eq!(items.len(), 3);
+
+        let first = &items[0];
+        assert_eq!(first.id, 8912347);
+        assert_eq!(
+            first.news.title,
+            "S&P 500 futures <1% lower after \"hot\" CPI"
+        );
+        assert_eq!(first.news.guid.as_deref(), Some("8912347"));
+        assert_eq!(first.news.labels, vec!["US", "Indexes"]);
+        // British Summer Time: 15:30 local is 14:30 UTC
+        assert_eq!(
+            first.news.published.map(|p| p.to_rfc3339()).as_deref(),
+            Some("2026-06-10T14:30:05.120+00:00")
+        );
+        // Quoted id, apostrophe character reference
+        assert_eq!(items[1].id, 8912346);
+        assert_eq!(
+            items[1].news.title,
+            "ECB's Lagarde: rates are in a good place"
+        );
+        assert_eq!(
+            items[1].news.description,
+            "Comments at the \"Sintra\" forum"
+        );
+        // Winter: London is UTC
+        assert_eq!(
+            items[2].news.published.map(|p| p.to_rfc3339()).as_deref(),
+            Some("2026-01-15T09:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn test_incremental_poll() {
+        let client = Client::new();
+        let mut actor = FinJuiceActor::new(
+            Bus::new(),
+            client,
+            FinJuiceCfg {
+                alt_url: "https://live.example.com/Startup".to_string(),
+                ..Default::default()
+            },
+            CancellationToken::new(),
+        );
+        assert!(actor.get_api_url().contains("&oldID=0&"));
+
+        let first = parse_fj_response(include_str!("../../testdata/finjuice/startup.xml"), Tz::UTC)
+            .unwrap();
+        let published = actor.take_new(first);
+        assert_eq!(published.len(), 3);
+        // Oldest first
+        assert_eq!(published[0].guid.as_deref(), Some("8912301"));
+        assert!(actor.get_api_url().contains("&oldID=8912347&"));
+
+        // A later poll repeats one known item alongside a new one
+        let next =
+            parse_fj_response(include_str!("../../testdata/finjuice/update.xml"), Tz::UTC).unwrap();
+        let published = actor.take_new(next);
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].title, "Oil jumps 3% on OPEC+ supply cut");
+        assert!(actor.get_api_url().contains("&oldID=8912352&"));
+    }
+
+    #[test]
+    fn test_malformed_responses() {
+        assert!(parse_fj_response("<html>maintenance</html>", Tz::UTC).is_err());
+        assert!(
+            parse_fj_response(
+                r#"<string xmlns="http://tempuri.org/">{"News": []} &bogus;</string>"#,
+                Tz::UTC
+            )
+            .is_err()
+        );
+        let empty = parse_fj_response(
+            r#"<string xmlns="http://tempuri.org/">{"News":[]}</string>"#,
+            Tz::UTC,
+        )
+        .unwrap();
+        assert!(empty.is_empty());
+    }
+}
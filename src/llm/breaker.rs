@@ -0,0 +1,105 @@
+use std::time::{Duration, Instant};
+
+/// Consecutive-failure circuit breaker for the LLM provider.
+///
+/// Closed: calls go through. After `threshold` consecutive failures the breaker
+/// opens for `cooldown`; once it elapses a single trial call is allowed
+/// (half-open). A success closes the breaker, a failure re-opens it.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// A half-open trial call has been admitted and has not completed yet.
+    trial_in_flight: bool,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            consecutive_failures: 0,
+            open_until: None,
+            trial_in_flight: false,
+        }
+    }
+
+    /// Admit a call. After the cooldown only the first caller gets through,
+    /// as the trial, until its outcome is recorded.
+    pub fn allow(&mut self, now: Instant) -> bool {
+        match self.open_until {
+            None => true,
+            Some(until) if now < until => false,
+            Some(_) if self.trial_in_flight => false,
+            Some(_) => {
+                self.trial_in_flight = true;
+                true
+            }
+        }
+    }
+
+    /// Whether calls are refused at `now`, without claiming the trial.
+    pub fn is_open(&self, now: Instant) -> bool {
+        match self.open_until {
+            None => false,
+            Some(until) => now < until || self.trial_in_flight,
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.open_until = None;
+        self.trial_in_flight = false;
+    }
+
+    /// Returns true if this failure tripped the breaker.
+    pub fn record_failure(&mut self, now: Instant) -> bool {
+        self.trial_in_flight = false;
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= self.threshold {
+            self.open_until = Some(now + self.cooldown);
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_and_recovers() {
+        let cooldown = Duration::from_secs(60);
+        let mut breaker = CircuitBreaker::new(3, cooldown);
+        let t0 = Instant::now();
+
+        assert!(!breaker.record_failure(t0));
+        assert!(!breaker.record_failure(t0));
+        assert!(breaker.allow(t0));
+        assert!(breaker.record_failure(t0)); // 3rd failure trips
+        assert!(breaker.is_open(t0));
+
+        // Half-open after cooldown: a single trial call
+        let t1 = t0 + cooldown;
+        assert!(!breaker.is_open(t1));
+        assert!(breaker.allow(t1));
+        assert!(breaker.is_open(t1));
+        assert!(!breaker.allow(t1));
+
+        // Trial failure re-opens immediately
+        assert!(breaker.record_failure(t1));
+        assert!(breaker.is_open(t1));
+        assert!(!breaker.allow(t1));
+
+        // Trial success closes it
+        let t2 = t1 + cooldown;
+        assert!(breaker.allow(t2));
+        breaker.record_success();
+        assert!(breaker.allow(t2));
+        assert!(breaker.allow(t2));
+        assert!(!breaker.record_failure(t2));
+    }
+}
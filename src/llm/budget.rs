@@ -0,0 +1,240 @@
+use crate::config::config::{LlmBudgetCfg, LlmPriceCfg};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use std::collections::HashMap;
+
+/// Token counts reported by the provider for a single completion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u32 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// One billed provider call, whether or not its output was usable.
+#[derive(Debug, Clone)]
+pub struct UsageRecord {
+    pub model: String,
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+    pub at: DateTime<Utc>,
+}
+
+/// Price table lookup: exact model name first, then the longest configured prefix
+/// (so "gpt-5-nano-2025-08-07" is billed as "gpt-5-nano").
+#[derive(Debug, Clone, Default)]
+pub struct CostModel {
+    prices: HashMap<String, LlmPriceCfg>,
+}
+
+impl CostModel {
+    pub fn new(prices: HashMap<String, LlmPriceCfg>) -> Self {
+        Self { prices }
+    }
+
+    pub fn price_for(&self, model: &str) -> Option<&LlmPriceCfg> {
+        if let Some(p) = self.prices.get(model) {
+            return Some(p);
+        }
+        self.prices
+            .iter()
+            .filter(|(name, _)| model.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, p)| p)
+    }
+
+    /// Cost in USD, or 0.0 when the model has no configured price.
+    pub fn cost_usd(&self, model: &str, usage: &TokenUsage) -> f64 {
+        match self.price_for(model) {
+            Some(p) => {
+                (usage.prompt_tokens as f64 * p.input_per_mtok
+                    + usage.completion_tokens as f64 * p.output_per_mtok)
+                    / 1_000_000.0
+            }
+            None => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetState {
+    Normal,
+    /// Above `degradeAtPct` of a cap: fewer candidates, fallback model.
+    Degraded,
+    /// A cap has been reached: no more LLM calls until the window rolls over.
+    Exhausted,
+}
+
+impl BudgetState {
+    pub fn as_gauge(&self) -> f64 {
+        match self {
+            BudgetState::Normal => 0.0,
+            BudgetState::Degraded => 1.0,
+            BudgetState::Exhausted => 2.0,
+        }
+    }
+}
+
+/// Tracks spend for the current UTC day and month and maps it to a BudgetState.
+#[derive(Debug, Clone)]
+pub struct SpendTracker {
+    cfg: LlmBudgetCfg,
+    day: NaiveDate,
+    daily_usd: f64,
+    monthly_usd: f64,
+}
+
+impl SpendTracker {
+    pub fn new(cfg: LlmBudgetCfg, now: DateTime<Utc>) -> Self {
+        Self {
+            cfg,
+            day: now.date_naive(),
+            daily_usd: 0.0,
+            monthly_usd: 0.0,
+        }
+    }
+
+    /// Reset the windows whose period has ended.
+    fn roll(&mut self, now: DateTime<Utc>) {
+        let today = now.date_naive();
+        if today == self.day {
+            return;
+        }
+        if today.year() != self.day.year() || today.month() != self.day.month() {
+            self.monthly_usd = 0.0;
+        }
+        self.daily_usd = 0.0;
+        self.day = today;
+    }
+
+    /// Overwrite the counters, e.g. with totals loaded from the signals table on startup.
+    pub fn seed(&mut self, daily_usd: f64, monthly_usd: f64, now: DateTime<Utc>) {
+        self.day = now.date_naive();
+        self.daily_usd = daily_usd;
+        self.monthly_usd = monthly_usd;
+    }
+
+    pub fn record(&mut self, cost_usd: f64, now: DateTime<Utc>) {
+        self.roll(now);
+        self.daily_usd += cost_usd;
+        self.monthly_usd += cost_usd;
+    }
+
+    pub fn daily_usd(&self) -> f64 {
+        self.daily_usd
+    }
+
+    pub fn monthly_usd(&self) -> f64 {
+        self.monthly_usd
+    }
+
+    pub fn state(&mut self, now: DateTime<Utc>) -> BudgetState {
+        self.roll(now);
+        let windows = [
+            (self.daily_usd, self.cfg.daily_usd),
+            (self.monthly_usd, self.cfg.monthly_usd),
+        ];
+
+        let mut state = BudgetState::Normal;
+        for (spent, cap) in windows {
+            if cap <= 0.0 {
+                continue;
+            }
+            if spent >= cap {
+                return BudgetState::Exhausted;
+            }
+            if spent >= cap * self.cfg.degrade_at_pct {
+                state = BudgetState::Degraded;
+            }
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn budget(daily: f64, monthly: f64) -> LlmBudgetCfg {
+        LlmBudgetCfg {
+            daily_usd: daily,
+            monthly_usd: monthly,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cost_model_prefix_lookup() {
+        let mut prices = HashMap::new();
+        prices.insert(
+            "gpt-5".to_string(),
+            LlmPriceCfg {
+                input_per_mtok: 1.0,
+                output_per_mtok: 10.0,
+            },
+        );
+        prices.insert(
+            "gpt-5-nano".to_string(),
+            LlmPriceCfg {
+                input_per_mtok: 0.05,
+                output_per_mtok: 0.40,
+            },
+        );
+        let costs = CostModel::new(prices);
+        let usage = TokenUsage {
+            prompt_tokens: 1_000_000,
+            completion_tokens: 500_000,
+        };
+
+        // Longest prefix wins
+        let nano = costs.cost_usd("gpt-5-nano-2025-08-07", &usage);
+        assert!((nano - 0.25).abs() < 1e-9);
+
+        let full = costs.cost_usd("gpt-5", &usage);
+        assert!((full - 6.0).abs() < 1e-9);
+
+        // Unknown model is free (but still counted in tokens)
+        assert_eq!(costs.cost_usd("llama-3", &usage), 0.0);
+    }
+
+    #[test]
+    fn test_budget_states() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let mut tracker = SpendTracker::new(budget(10.0, 0.0), now);
+
+        assert_eq!(tracker.state(now), BudgetState::Normal);
+        tracker.record(7.9, now);
+        assert_eq!(tracker.state(now), BudgetState::Normal);
+        tracker.record(0.2, now); // 8.1 >= 80% of 10
+        assert_eq!(tracker.state(now), BudgetState::Degraded);
+        tracker.record(2.0, now);
+        assert_eq!(tracker.state(now), BudgetState::Exhausted);
+    }
+
+    #[test]
+    fn test_budget_rollover() {
+        let day1 = Utc.with_ymd_and_hms(2026, 3, 31, 23, 0, 0).unwrap();
+        let day2 = Utc.with_ymd_and_hms(2026, 4, 1, 1, 0, 0).unwrap();
+        let mut tracker = SpendTracker::new(budget(5.0, 6.0), day1);
+
+        tracker.record(5.0, day1);
+        assert_eq!(tracker.state(day1), BudgetState::Exhausted);
+
+        // New day and new month reset both windows
+        assert_eq!(tracker.state(day2), BudgetState::Normal);
+        assert_eq!(tracker.daily_usd(), 0.0);
+        assert_eq!(tracker.monthly_usd(), 0.0);
+
+        // Same month, new day: only daily resets
+        let day3 = Utc.with_ymd_and_hms(2026, 4, 2, 1, 0, 0).unwrap();
+        tracker.record(3.0, day2);
+        assert_eq!(tracker.state(day3), BudgetState::Normal);
+        tracker.record(3.0, day3);
+        assert_eq!(tracker.state(day3), BudgetState::Exhausted); // monthly 6.0
+    }
+}
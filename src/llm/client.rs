@@ -1,11 +1,17 @@
-use crate::config::config::LlmCfg;
+use crate::config::config::{LlmCfg, LlmResponseFormat};
+use crate::llm::breaker::CircuitBreaker;
+use crate::llm::budget::{BudgetState, CostModel, SpendTracker, TokenUsage, UsageRecord};
+use crate::llm::parsing::{parse_signal, signal_schema};
+use crate::llm::prompts::{PromptTemplate, PromptVars};
 use anyhow::{Context, Result};
 use async_openai::{
     Client,
     config::OpenAIConfig,
     types::chat::{
+        ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
         ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
-        CreateChatCompletionRequestArgs,
+        CreateChatCompletionRequest, CreateChatCompletionRequestArgs, ResponseFormat,
+        ResponseFormatJsonSchema,
     },
 };
 use governor::clock::DefaultClock;
@@ -13,8 +19,9 @@ use governor::state::{InMemoryState, NotKeyed};
 use governor::{Quota, RateLimiter};
 use serde::{Deserialize, Serialize};
 use std::num::NonZeroU32;
-use std::sync::Arc;
-use tracing::info;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{info, warn};
 
 #[derive(Clone)]
 pub struct LlmClient {
@@ -27,8 +34,18 @@ pub struct LlmClient {
     // LlmClient is cloned in StrategyActor? Yes.
     // RateLimiter inside Arc? Correct.
     limiter: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+    costs: Arc<CostModel>,
+    // Shared across clones so every caller sees the same daily/monthly spend.
+    spend: Arc<Mutex<SpendTracker>>,
+    breaker: Arc<Mutex<CircuitBreaker>>,
+    // Billed calls not yet persisted; drained by the caller with `take_usage`.
+    billed: Arc<Mutex<Vec<UsageRecord>>>,
 }
 
+const REPAIR_PROMPT: &str = "Your previous reply was not valid JSON matching the requested format. \
+Reply again with ONLY a single JSON object with the fields 'sentiment', 'confidence' and 'reasoning', \
+without markdown fences or any other text.";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SignalResponse {
     pub sentiment: String, // "Positive", "Negative", "Neutral"
@@ -36,6 +53,18 @@ pub struct SignalResponse {
     pub reasoning: String,
 }
 
+/// A parsed signal together with what it cost to produce.
+#[derive(Debug)]
+pub struct LlmAnalysis {
+    pub signal: SignalResponse,
+    pub prompt: String,
+    /// "name@version" of the template the prompt was rendered from.
+    pub template_id: String,
+    pub model: String,
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+}
+
 impl LlmClient {
     pub fn new(cfg: LlmCfg) -> Self {
         // Governor initialization
@@ -47,109 +76,314 @@ impl LlmClient {
             .with_api_key(&cfg.api_key)
             .with_api_base(&cfg.base_url);
 
-        // async-openai uses reqwest internally, but properly typed
-        let client = Client::with_config(openai_config);
+        // async-openai uses reqwest internally, but properly typed.
+        // Its built-in backoff retries 429/5xx; bound it so requestTimeout stays meaningful.
+        let backoff = backoff::ExponentialBackoffBuilder::new()
+            .with_initial_interval(cfg.retry.backoff_initial)
+            .with_max_elapsed_time(Some(cfg.retry.backoff_max_elapsed))
+            .build();
+        let client = Client::with_config(openai_config).with_backoff(backoff);
+
+        let costs = Arc::new(CostModel::new(cfg.pricing.clone()));
+        let spend = Arc::new(Mutex::new(SpendTracker::new(
+            cfg.budget.clone(),
+            chrono::Utc::now(),
+        )));
+
+        let breaker = Arc::new(Mutex::new(CircuitBreaker::new(
+            cfg.retry.breaker_failure_threshold,
+            cfg.retry.breaker_cooldown,
+        )));
 
         Self {
             client,
             cfg,
             limiter,
+            costs,
+            spend,
+            breaker,
+            billed: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
+    #[allow(dead_code)]
     pub fn model(&self) -> &str {
         &self.cfg.model
     }
 
-    pub async fn analyze(
-        &self,
-        news_title: &str,
-        market_question: &str,
-        outcomes: &[String],
-    ) -> Result<(SignalResponse, String)> {
-        // Enforce Rate Limit
-        self.limiter.until_ready().await;
+    /// False while the circuit breaker is open.
+    pub fn is_available(&self) -> bool {
+        let open = self.breaker.lock().unwrap().is_open(Instant::now());
+        metrics::gauge!("llm_circuit_breaker_open").set(if open { 1.0 } else { 0.0 });
+        !open
+    }
+
+    pub fn budget_state(&self) -> BudgetState {
+        let mut spend = self.spend.lock().unwrap();
+        let state = spend.state(chrono::Utc::now());
+        metrics::gauge!("llm_budget_state").set(state.as_gauge());
+        state
+    }
+
+    /// Fewer candidates are analysed while the budget is degraded.
+    pub fn candidate_limit(&self, default_limit: usize) -> usize {
+        match self.budget_state() {
+            BudgetState::Normal => default_limit,
+            BudgetState::Degraded => default_limit.min(self.cfg.budget.degraded_top_candidates),
+            BudgetState::Exhausted => 0,
+        }
+    }
 
-        let outcomes_list = outcomes.join(", ");
-        let prompt = format!(
-            "You are a financial analyst specializing in event-driven market prediction. Analyze the following news to determine if it predicts a specific outcome for the market.
+    /// Billed calls since the last call, for persistence. Includes attempts
+    /// whose output failed to parse.
+    pub fn take_usage(&self) -> Vec<UsageRecord> {
+        std::mem::take(&mut *self.billed.lock().unwrap())
+    }
 
-            News: \"{}\"
-            Market Question: \"{}\"
-            Possible Outcomes: [{}]
+    /// Seed spend counters with totals already persisted for the current day/month.
+    pub fn seed_spend(&self, daily_usd: f64, monthly_usd: f64) {
+        let mut spend = self.spend.lock().unwrap();
+        spend.seed(daily_usd, monthly_usd, chrono::Utc::now());
+        metrics::gauge!("llm_spend_usd", "window" => "daily").set(spend.daily_usd());
+        metrics::gauge!("llm_spend_usd", "window" => "monthly").set(spend.monthly_usd());
+    }
 
-            Perform the following analysis step-by-step:
-            1. Identify key entities/events in the news.
-            2. Determine if this news explicitly supports one of the Possible Outcomes.
-            3. If the news creates a high conviction that a specific outcome will occur (or win), select it.
-            4. If the news is irrelevant or ambiguous, select 'None'.
+    fn active_model(&self, state: BudgetState) -> &str {
+        match (state, &self.cfg.budget.fallback_model) {
+            (BudgetState::Degraded, Some(fallback)) => fallback,
+            _ => &self.cfg.model,
+        }
+    }
 
-            Output strictly valid JSON with fields: 
-            - 'sentiment' (The exact string of the selected outcome, or 'None'), 
-            - 'confidence' (0.0 to 1.0, representing the strength of the prediction), 
-            - 'reasoning' (A concise summary of your analysis).
+    fn record_usage(&self, model: &str, usage: &TokenUsage) -> f64 {
+        let cost = self.costs.cost_usd(model, usage);
+        if cost == 0.0 && usage.total() > 0 && self.costs.price_for(model).is_none() {
+            warn!(
+                "No price configured for model {}; spend is not tracked",
+                model
+            );
+        }
 
-            Example: If outcomes are [\"Yes\", \"No\"] and news strongly supports Yes, sentiment should be \"Yes\".",
-            news_title, market_question, outcomes_list
+        metrics::counter!("llm_tokens_total", "model" => model.to_string(), "type" => "prompt")
+            .increment(usage.prompt_tokens as u64);
+        metrics::counter!("llm_tokens_total", "model" => model.to_string(), "type" => "completion")
+            .increment(usage.completion_tokens as u64);
+        metrics::histogram!("llm_request_cost_usd", "model" => model.to_string()).record(cost);
+
+        let now = chrono::Utc::now();
+        self.billed.lock().unwrap().push(UsageRecord {
+            model: model.to_string(),
+            usage: *usage,
+            cost_usd: cost,
+            at: now,
+        });
+
+        let mut spend = self.spend.lock().unwrap();
+        spend.record(cost, now);
+        metrics::gauge!("llm_spend_usd", "window" => "daily").set(spend.daily_usd());
+        metrics::gauge!("llm_spend_usd", "window" => "monthly").set(spend.monthly_usd());
+        cost
+    }
+
+    pub async fn analyze(
+        &self,
+        template: &PromptTemplate,
+        vars: &PromptVars,
+    ) -> Result<LlmAnalysis> {
+        anyhow::ensure!(
+            self.is_available(),
+            "LLM circuit breaker open, provider is failing"
         );
+        let state = self.budget_state();
+        anyhow::ensure!(
+            state != BudgetState::Exhausted,
+            "LLM budget exhausted, refusing to call the provider"
+        );
+        let model = self.active_model(state).to_string();
+
+        // Enforce Rate Limit
+        self.limiter.until_ready().await;
+
+        let outcomes = &vars.outcomes;
+        let prompt = template.render(vars);
+        let template_id = template.id();
 
         // Models like o1-preview/o1-mini (reasoning models) do NOT support temperature.
         // gpt-4o, gpt-3.5 do.
         // We'll optionally set temperature only if not a reasoning model or let the lib handle default.
         // For strict JSON output, temperature 0 is good for standard GPT models.
 
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(&self.cfg.model)
-            .messages([
-                ChatCompletionRequestSystemMessageArgs::default()
-                    .content("You are a helpful assistant that outputs JSON.")
-                    .build()?
-                    .into(),
-                ChatCompletionRequestUserMessageArgs::default()
-                    .content(prompt.clone())
-                    .build()?
-                    .into(),
-            ])
-            // Do NOT set temperature for strict reasoning models if using o1 series.
-            // If user uses gpt-4o-mini (default), temperature 0.0 is fine.
-            // Safe bet: set it to 0.0 only if we know it's supported, or just leave it default (1.0).
-            // However, 0.0 is better for deterministic code output.
-            // Let's assume standard models for now as per config default.
-            .build()?;
-
-        info!(
-            "Calling LLM at {} with model {}",
-            self.cfg.base_url, self.cfg.model
+        let mut messages: Vec<ChatCompletionRequestMessage> = vec![
+            ChatCompletionRequestSystemMessageArgs::default()
+                .content("You are a helpful assistant that outputs JSON.")
+                .build()?
+                .into(),
+            ChatCompletionRequestUserMessageArgs::default()
+                .content(prompt.clone())
+                .build()?
+                .into(),
+        ];
+        let response_format = self.response_format(outcomes);
+
+        let mut usage = TokenUsage::default();
+        let mut cost_usd = 0.0;
+        let mut last_err = None;
+
+        // First attempt + bounded repair attempts when the output doesn't parse
+        for attempt in 0..=self.cfg.retry.max_parse_retries {
+            // Every repair attempt is billed too: stop once the budget runs out
+            if attempt > 0 && self.budget_state() == BudgetState::Exhausted {
+                metrics::counter!("llm_repairs_skipped_total", "reason" => "budget").increment(1);
+                return Err(last_err
+                    .unwrap_or_else(|| anyhow::anyhow!("LLM produced no parseable output"))
+                    .context("LLM budget exhausted, refusing to call the provider"));
+            }
+            let mut args = CreateChatCompletionRequestArgs::default();
+            args.model(&model).messages(messages.clone());
+            if let Some(format) = &response_format {
+                args.response_format(format.clone());
+            }
+            let request = args.build()?;
+
+            info!(
+                "Calling LLM at {} with model {} (attempt {})",
+                self.cfg.base_url,
+                model,
+                attempt + 1
+            );
+            let content = self
+                .complete(request, &model, &mut usage, &mut cost_usd)
+                .await?;
+
+            match parse_signal(&content) {
+                Ok(signal) => {
+                    return Ok(LlmAnalysis {
+                        signal,
+                        prompt,
+                        template_id,
+                        model,
+                        usage,
+                        cost_usd,
+                    });
+                }
+                Err(e) => {
+                    warn!(
+                        "LLM output failed to parse (attempt {}): {:#}",
+                        attempt + 1,
+                        e
+                    );
+                    metrics::counter!("llm_parse_failures_total", "model" => model.clone())
+                        .increment(1);
+
+                    messages.push(
+                        ChatCompletionRequestAssistantMessageArgs::default()
+                            .content(content)
+                            .build()?
+                            .into(),
+                    );
+                    messages.push(
+                        ChatCompletionRequestUserMessageArgs::default()
+                            .content(REPAIR_PROMPT)
+                            .build()?
+                            .into(),
+                    );
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        Err(last_err
+            .unwrap_or_else(|| anyhow::anyhow!("LLM produced no parseable output"))
+            .context("LLM output unparseable after repair attempts"))
+    }
+
+    fn response_format(&self, outcomes: &[String]) -> Option<ResponseFormat> {
+        match self.cfg.response_format {
+            LlmResponseFormat::JsonSchema => Some(ResponseFormat::JsonSchema {
+                json_schema: ResponseFormatJsonSchema {
+                    description: Some("Trading signal for a prediction market".to_string()),
+                    name: "signal".to_string(),
+                    schema: Some(signal_schema(outcomes)),
+                    strict: Some(true),
+                },
+            }),
+            LlmResponseFormat::JsonObject => Some(ResponseFormat::JsonObject),
+            LlmResponseFormat::Text => None,
+        }
+    }
+
+    /// One provider round-trip under the request timeout. Feeds the circuit
+    /// breaker and accumulates usage/cost for every billed attempt.
+    async fn complete(
+        &self,
+        request: CreateChatCompletionRequest,
+        model: &str,
+        usage: &mut TokenUsage,
+        cost_usd: &mut f64,
+    ) -> Result<String> {
+        anyhow::ensure!(
+            self.breaker.lock().unwrap().allow(Instant::now()),
+            "LLM circuit breaker open, provider is failing"
         );
+        let result = tokio::time::timeout(
+            self.cfg.retry.request_timeout,
+            self.client.chat().create(request),
+        )
+        .await;
+
+        let response = match result {
+            Ok(Ok(response)) => {
+                self.breaker.lock().unwrap().record_success();
+                response
+            }
+            Ok(Err(e)) => {
+                self.record_provider_failure("error");
+                return Err(e).context("LLM request failed");
+            }
+            Err(_) => {
+                self.record_provider_failure("timeout");
+                anyhow::bail!(
+                    "LLM request timed out after {:?}",
+                    self.cfg.retry.request_timeout
+                );
+            }
+        };
 
-        let response = self
-            .client
-            .chat()
-            .create(request)
-            .await
-            .context("LLM request failed")?;
+        // Tokens are billed whether or not the content parses, so record spend first.
+        let attempt_usage = response
+            .usage
+            .as_ref()
+            .map(|u| TokenUsage {
+                prompt_tokens: u.prompt_tokens,
+                completion_tokens: u.completion_tokens,
+            })
+            .unwrap_or_default();
+        *cost_usd += self.record_usage(model, &attempt_usage);
+        usage.prompt_tokens += attempt_usage.prompt_tokens;
+        usage.completion_tokens += attempt_usage.completion_tokens;
 
         let choice = response
             .choices
             .first()
             .context("No choices in LLM response")?;
-        let content_str = choice
+        let content = choice
             .message
             .content
-            .as_ref()
+            .clone()
             .context("No content in LLM response")?;
+        Ok(content)
+    }
 
-        // Parse JSON from content (handle potential markdown code blocks)
-        let clean_content = content_str
-            .trim()
-            .trim_start_matches("```json")
-            .trim_start_matches("```")
-            .trim_end_matches("```");
-
-        let signal: SignalResponse = serde_json::from_str(clean_content)
-            .context(format!("Failed to parse LLM JSON: {}", clean_content))?;
-
-        Ok((signal, prompt))
+    fn record_provider_failure(&self, kind: &'static str) {
+        metrics::counter!("llm_request_failures_total", "kind" => kind).increment(1);
+        let tripped = self.breaker.lock().unwrap().record_failure(Instant::now());
+        if tripped {
+            warn!(
+                "LLM circuit breaker OPEN for {:?} after repeated provider failures",
+                self.cfg.retry.breaker_cooldown
+            );
+            metrics::counter!("llm_circuit_breaker_trips_total").increment(1);
+        }
     }
 }
 
@@ -157,6 +391,7 @@ impl LlmClient {
 mod tests {
     use super::*;
     use crate::config::config::AppCfg;
+    use crate::llm::prompts::PromptLibrary;
 
     #[tokio::test]
     #[ignore] // Run with: cargo test -- --ignored
@@ -172,9 +407,21 @@ mod tests {
         let question = "Will Bitcoin be legal tender in the US in 2025?";
         let outcomes = vec!["Yes".to_string(), "No".to_string()];
 
-        let (signal, _prompt) = client.analyze(news, question, &outcomes).await?;
+        let vars = PromptVars {
+            news: news.to_string(),
+            market: question.to_string(),
+            outcomes,
+            prices: Vec::new(),
+            date: chrono::Utc::now().date_naive(),
+            story: String::new(),
+            source: String::new(),
+            article: String::new(),
+        };
+        let template = PromptLibrary::builtin();
+        let analysis = client.analyze(template.select(&[]), &vars).await?;
+        let signal = analysis.signal;
 
-        println!("Response: {:?}", signal);
+        println!("Response: {:?} (cost ${:.6})", signal, analysis.cost_usd);
 
         assert_eq!(signal.sentiment, "Yes");
         assert!(signal.confidence > 0.8);
@@ -1,4 +1,8 @@
+pub mod breaker;
+pub mod budget;
 pub mod client;
+pub mod parsing;
+pub mod prompts;
 
+pub use client::LlmAnalysis;
 pub use client::LlmClient;
-pub use client::SignalResponse;
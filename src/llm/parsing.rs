@@ -0,0 +1,135 @@
+use crate::llm::client::SignalResponse;
+use anyhow::{Context, Result};
+
+/// Return the first balanced top-level JSON object in `text`, ignoring braces
+/// that appear inside string literals. Handles markdown fences and prose
+/// around the payload ("Sure! Here is the JSON: {...}").
+pub fn extract_json_object(text: &str) -> Option<&str> {
+    let bytes = text.as_bytes();
+    let mut search_from = 0;
+
+    while let Some(offset) = text[search_from..].find('{') {
+        let start = search_from + offset;
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for (i, &b) in bytes.iter().enumerate().skip(start) {
+            if in_string {
+                match b {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match b {
+                b'"' => in_string = true,
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        let candidate = &text[start..=i];
+                        if serde_json::from_str::<serde_json::Value>(candidate).is_ok() {
+                            return Some(candidate);
+                        }
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Unbalanced or invalid object at this position: try the next '{'
+        search_from = start + 1;
+    }
+    None
+}
+
+/// Parse a SignalResponse from raw model output, tolerating fences and
+/// surrounding prose. Confidence is clamped to [0, 1].
+pub fn parse_signal(content: &str) -> Result<SignalResponse> {
+    let trimmed = content.trim();
+    let mut signal: SignalResponse = match serde_json::from_str(trimmed) {
+        Ok(s) => s,
+        Err(_) => {
+            let obj = extract_json_object(trimmed)
+                .with_context(|| format!("No JSON object in LLM output: {}", trimmed))?;
+            serde_json::from_str(obj)
+                .with_context(|| format!("Failed to parse LLM JSON: {}", obj))?
+        }
+    };
+
+    anyhow::ensure!(
+        signal.confidence.is_finite(),
+        "LLM returned non-finite confidence"
+    );
+    signal.confidence = signal.confidence.clamp(0.0, 1.0);
+    signal.sentiment = signal.sentiment.trim().to_string();
+    Ok(signal)
+}
+
+/// JSON schema used for structured outputs. Sentiment is constrained to the
+/// market outcomes plus "None".
+pub fn signal_schema(outcomes: &[String]) -> serde_json::Value {
+    let mut allowed: Vec<&str> = outcomes.iter().map(|o| o.as_str()).collect();
+    allowed.push("None");
+
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "sentiment": { "type": "string", "enum": allowed },
+            "confidence": { "type": "number" },
+            "reasoning": { "type": "string" }
+        },
+        "required": ["sentiment", "confidence", "reasoning"],
+        "additionalProperties": false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_json_object() {
+        let fenced = "```json\n{\"a\": 1}\n```";
+        assert_eq!(extract_json_object(fenced), Some("{\"a\": 1}"));
+
+        let prose = "Sure! Here you go: {\"a\": {\"b\": 2}} Hope that helps {";
+        assert_eq!(extract_json_object(prose), Some("{\"a\": {\"b\": 2}}"));
+
+        // Braces inside strings don't affect depth
+        let tricky = r#"{"reasoning": "use } and { freely \" ok", "x": 1}"#;
+        assert_eq!(extract_json_object(tricky), Some(tricky));
+
+        // Skips an invalid object and finds the next valid one
+        let invalid_first = "{not json} then {\"ok\": true}";
+        assert_eq!(extract_json_object(invalid_first), Some("{\"ok\": true}"));
+
+        assert_eq!(extract_json_object("no json here"), None);
+        assert_eq!(extract_json_object("{\"unterminated\": 1"), None);
+    }
+
+    #[test]
+    fn test_parse_signal_tolerant() {
+        let content = "Analysis follows.\n```json\n{\"sentiment\": \" Yes \", \"confidence\": 1.3, \"reasoning\": \"clear\"}\n```";
+        let signal = parse_signal(content).unwrap();
+        assert_eq!(signal.sentiment, "Yes");
+        assert_eq!(signal.confidence, 1.0);
+
+        assert!(parse_signal("I cannot answer that.").is_err());
+        assert!(parse_signal("{\"sentiment\": \"Yes\"}").is_err()); // missing fields
+    }
+
+    #[test]
+    fn test_signal_schema_enum() {
+        let schema = signal_schema(&["Yes".to_string(), "No".to_string()]);
+        let allowed = schema["properties"]["sentiment"]["enum"]
+            .as_array()
+            .unwrap();
+        assert_eq!(allowed.len(), 3);
+        assert_eq!(allowed[2], "None");
+    }
+}
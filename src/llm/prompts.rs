@@ -0,0 +1,268 @@
+use crate::config::config::PromptsCfg;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+
+/// Variables a template may reference as `{{name}}`.
+const KNOWN_VARS: [&str; 8] = [
+    "news", "market", "outcomes", "price", "date", "story", "source", "article",
+];
+
+const BUILTIN_NAME: &str = "analyst";
+const BUILTIN_VERSION: &str = "v1";
+const BUILTIN_BODY: &str = include_str!("../../prompts/analyst_v1.txt");
+
+/// Typed inputs for rendering an analysis prompt.
+#[derive(Debug, Clone, Default)]
+pub struct PromptVars {
+    pub news: String,
+    pub market: String,
+    pub outcomes: Vec<String>,
+    /// Current price per outcome, aligned with `outcomes` when known.
+    pub prices: Vec<(String, rust_decimal::Decimal)>,
+    pub date: chrono::NaiveDate,
+    /// Where the news stands in its story (first report, corroboration, update).
+    pub story: String,
+    /// The news source's track record and weight.
+    pub source: String,
+    /// Extracted article text, else the plain-text description.
+    pub article: String,
+}
+
+impl PromptVars {
+    fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "news" => Some(self.news.clone()),
+            "market" => Some(self.market.clone()),
+            "outcomes" => Some(self.outcomes.join(", ")),
+            "price" => Some(if self.prices.is_empty() {
+                "unknown".to_string()
+            } else {
+                self.prices
+                    .iter()
+                    .map(|(outcome, price)| format!("{}: {}", outcome, price))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }),
+            "date" => Some(self.date.format("%Y-%m-%d").to_string()),
+            "story" => Some(self.story.clone()),
+            "source" => Some(self.source.clone()),
+            "article" => Some(if self.article.is_empty() {
+                "unavailable".to_string()
+            } else {
+                self.article.clone()
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A named, versioned prompt. `id()` ("name@version") is persisted with each signal.
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub version: String,
+    body: String,
+}
+
+impl PromptTemplate {
+    /// Validates that every `{{var}}` in `body` is a known variable.
+    pub fn new(name: &str, version: &str, body: &str) -> Result<Self> {
+        for var in placeholders(body)? {
+            anyhow::ensure!(
+                KNOWN_VARS.contains(&var),
+                "prompt {}@{}: unknown variable {{{{{}}}}} (expected one of {:?})",
+                name,
+                version,
+                var,
+                KNOWN_VARS
+            );
+        }
+        Ok(Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            body: body.to_string(),
+        })
+    }
+
+    pub fn id(&self) -> String {
+        format!("{}@{}", self.name, self.version)
+    }
+
+    pub fn render(&self, vars: &PromptVars) -> String {
+        let mut out = String::with_capacity(self.body.len() + 256);
+        let mut rest = self.body.as_str();
+        while let Some(open) = rest.find("{{") {
+            out.push_str(&rest[..open]);
+            // Validated in `new`, so the closing braces and the variable exist
+            let close = rest[open..].find("}}").unwrap_or(rest.len() - open) + open;
+            let name = rest[open + 2..close].trim();
+            out.push_str(&vars.get(name).unwrap_or_default());
+            rest = &rest[(close + 2).min(rest.len())..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+fn placeholders(body: &str) -> Result<Vec<&str>> {
+    let mut vars = Vec::new();
+    let mut rest = body;
+    while let Some(open) = rest.find("{{") {
+        let close = rest[open..]
+            .find("}}")
+            .map(|i| i + open)
+            .context("unterminated '{{' in prompt template")?;
+        vars.push(rest[open + 2..close].trim());
+        rest = &rest[close + 2..];
+    }
+    Ok(vars)
+}
+
+/// All loaded templates plus the category -> template routing.
+#[derive(Debug, Clone)]
+pub struct PromptLibrary {
+    templates: HashMap<String, PromptTemplate>,
+    default_id: String,
+    by_category: HashMap<String, String>,
+}
+
+impl PromptLibrary {
+    /// Library containing only the built-in analyst prompt.
+    pub fn builtin() -> Self {
+        let template = PromptTemplate::new(BUILTIN_NAME, BUILTIN_VERSION, BUILTIN_BODY)
+            .expect("built-in prompt template is valid");
+        let default_id = template.id();
+        let mut templates = HashMap::new();
+        templates.insert(default_id.clone(), template);
+        Self {
+            templates,
+            default_id,
+            by_category: HashMap::new(),
+        }
+    }
+
+    /// Load templates from the files listed in config. The built-in template is
+    /// always available and is the default unless `prompts.default` says otherwise.
+    pub fn load(cfg: &PromptsCfg) -> Result<Self> {
+        let mut lib = Self::builtin();
+
+        for t in &cfg.templates {
+            let body = std::fs::read_to_string(&t.file)
+                .with_context(|| format!("reading prompt template {}", t.file))?;
+            let template = PromptTemplate::new(&t.name, &t.version, &body)?;
+            lib.templates.insert(template.id(), template);
+        }
+
+        if let Some(default_id) = &cfg.default {
+            anyhow::ensure!(
+                lib.templates.contains_key(default_id),
+                "prompts.default '{}' is not a loaded template",
+                default_id
+            );
+            lib.default_id = default_id.clone();
+        }
+
+        for (category, id) in &cfg.by_category {
+            anyhow::ensure!(
+                lib.templates.contains_key(id),
+                "prompts.byCategory.{} -> '{}' is not a loaded template",
+                category,
+                id
+            );
+            lib.by_category.insert(category.to_lowercase(), id.clone());
+        }
+
+        Ok(lib)
+    }
+
+    /// First category with a routing entry wins; otherwise the default template.
+    pub fn select(&self, categories: &[String]) -> &PromptTemplate {
+        categories
+            .iter()
+            .find_map(|c| self.by_category.get(&c.trim().to_lowercase()))
+            .and_then(|id| self.templates.get(id))
+            .unwrap_or_else(|| &self.templates[&self.default_id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config::PromptTemplateCfg;
+    use rust_decimal::Decimal;
+
+    fn vars() -> PromptVars {
+        PromptVars {
+            news: "Fed cuts rates".to_string(),
+            market: "Will the Fed cut in March?".to_string(),
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            prices: vec![
+                ("Yes".to_string(), Decimal::new(55, 2)),
+                ("No".to_string(), Decimal::new(45, 2)),
+            ],
+            date: chrono::NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            story: "first report of this story".to_string(),
+            source: "reuters (no track record, weight 1.00)".to_string(),
+            article: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_template() {
+        let t = PromptTemplate::new(
+            "t",
+            "v2",
+            "N={{news}} M={{ market }} O=[{{outcomes}}] P=[{{price}}] D={{date}} S={{story}}",
+        )
+        .unwrap();
+        assert_eq!(t.id(), "t@v2");
+        assert_eq!(
+            t.render(&vars()),
+            "N=Fed cuts rates M=Will the Fed cut in March? O=[Yes, No] P=[Yes: 0.55, No: 0.45] D=2026-03-01 S=first report of this story"
+        );
+    }
+
+    #[test]
+    fn test_template_validation() {
+        assert!(PromptTemplate::new("t", "v1", "{{headline}}").is_err());
+        assert!(PromptTemplate::new("t", "v1", "{{news").is_err());
+        assert!(PromptTemplate::new("t", "v1", "no variables").is_ok());
+    }
+
+    #[test]
+    fn test_builtin_and_category_routing() {
+        let dir = std::env::temp_dir().join(format!("polymind-prompts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("crypto_v1.txt");
+        std::fs::write(&file, "Crypto: {{news}}").unwrap();
+
+        let mut by_category = HashMap::new();
+        by_category.insert("Crypto".to_string(), "crypto@v1".to_string());
+        let cfg = PromptsCfg {
+            templates: vec![PromptTemplateCfg {
+                name: "crypto".to_string(),
+                version: "v1".to_string(),
+                file: file.to_string_lossy().to_string(),
+            }],
+            default: None,
+            by_category,
+        };
+
+        let lib = PromptLibrary::load(&cfg).unwrap();
+        assert_eq!(lib.select(&[]).id(), "analyst@v1");
+        assert_eq!(lib.select(&["politics".to_string()]).id(), "analyst@v1");
+        assert_eq!(
+            lib.select(&["politics".to_string(), "crypto".to_string()])
+                .id(),
+            "crypto@v1"
+        );
+
+        // The built-in prompt uses every variable
+        let rendered = lib.select(&[]).render(&vars());
+        assert!(rendered.contains("Fed cuts rates"));
+        assert!(rendered.contains("Yes: 0.55"));
+        assert!(!rendered.contains("{{"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
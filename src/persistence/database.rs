@@ -5,6 +5,54 @@ use sqlx::Row;
 use sqlx::postgres::{PgPool, PgPoolOptions};
 use tracing::info;
 
+/// Dedup and story state stored with each event.
+#[derive(Debug, Clone)]
+pub struct EventMeta<'a> {
+    /// ISO 639-1 code detected by the tokenizer
+    pub language: &'a str,
+    pub cluster_id: i64,
+    pub story_status: &'a str,
+    /// `ExactDuplicateDetector::content_hash`
+    pub exact_hash: u64,
+    pub sim_hash: u64,
+    pub first_seen: chrono::DateTime<chrono::Utc>,
+}
+
+/// An event row as needed to rebuild the dedup caches and stories. The hashes
+/// are missing on rows written before they were persisted.
+#[derive(Debug, Clone)]
+pub struct RecentEvent {
+    pub title: String,
+    pub description: String,
+    pub source: String,
+    pub cluster_id: Option<i64>,
+    pub exact_hash: Option<u64>,
+    pub sim_hash: Option<u64>,
+    pub first_seen: chrono::DateTime<chrono::Utc>,
+}
+
+/// A clustered report and how far behind its story's first report it came.
+#[derive(Debug, Clone)]
+pub struct SourceReport {
+    pub source: String,
+    pub status: String,
+    pub lag_secs: f64,
+}
+
+/// A signal on a closed market, with the market's final prices and the entry
+/// price if a decision was sized from it.
+#[derive(Debug, Clone)]
+pub struct ResolvedSignal {
+    pub source: String,
+    pub sentiment: String,
+    pub confidence: f64,
+    pub tokens: Vec<crate::core::types::MarketToken>,
+    /// Outcome bought by the signal's first decision, if it traded
+    pub side: Option<String>,
+    /// Price of the signal's outcome at that decision
+    pub entry_price: Option<Decimal>,
+}
+
 #[derive(Clone)]
 pub struct Database {
     pub pool: PgPool,
@@ -47,6 +95,20 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
+        sqlx::query(
+            r#"
+            ALTER TABLE events
+                ADD COLUMN IF NOT EXISTS language TEXT,
+                ADD COLUMN IF NOT EXISTS cluster_id BIGINT,
+                ADD COLUMN IF NOT EXISTS story_status TEXT,
+                ADD COLUMN IF NOT EXISTS exact_hash BIGINT,
+                ADD COLUMN IF NOT EXISTS sim_hash BIGINT,
+                ADD COLUMN IF NOT EXISTS first_seen TIMESTAMPTZ
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS markets (
@@ -66,6 +128,19 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
+        sqlx::query(
+            r#"
+            ALTER TABLE markets
+                ADD COLUMN IF NOT EXISTS category TEXT,
+                ADD COLUMN IF NOT EXISTS tags JSONB,
+                ADD COLUMN IF NOT EXISTS volume NUMERIC,
+                ADD COLUMN IF NOT EXISTS liquidity NUMERIC,
+                ADD COLUMN IF NOT EXISTS event_id TEXT
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Signals
         sqlx::query(
             r#"
@@ -85,6 +160,38 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
+        // LLM usage, cost and prompt template accounting (added after the initial schema)
+        sqlx::query(
+            r#"
+            ALTER TABLE signals
+                ADD COLUMN IF NOT EXISTS prompt_tokens INTEGER,
+                ADD COLUMN IF NOT EXISTS completion_tokens INTEGER,
+                ADD COLUMN IF NOT EXISTS cost_usd DOUBLE PRECISION,
+                ADD COLUMN IF NOT EXISTS prompt_template TEXT
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // One row per billed LLM call, including failed or unlinked ones; the
+        // source of truth for budget hydration
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS llm_usage (
+                usage_id BIGSERIAL PRIMARY KEY,
+                event_id BIGINT,
+                market_id TEXT,
+                model TEXT NOT NULL,
+                prompt_tokens INTEGER NOT NULL,
+                completion_tokens INTEGER NOT NULL,
+                cost_usd DOUBLE PRECISION NOT NULL,
+                created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Orders
         sqlx::query(
             r#"
@@ -289,13 +396,15 @@ impl Database {
         Ok(())
     }
 
-    pub async fn save_event(&self, news: &RawNews) -> Result<i64> {
+    /// Insert an event; an existing URL keeps its original dedup state.
+    pub async fn save_event(&self, news: &RawNews, meta: &EventMeta<'_>) -> Result<i64> {
         let start = std::time::Instant::now();
         // RETURNING event_id
         let rec = sqlx::query(
             r#"
-            INSERT INTO events (url, title, description, source, published_at)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO events (url, title, description, source, published_at, language,
+                                cluster_id, story_status, exact_hash, sim_hash, first_seen)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (url) DO UPDATE SET title = EXCLUDED.title -- Simple no-op or update
             RETURNING event_id
             "#,
@@ -305,6 +414,13 @@ impl Database {
         .bind(&news.description)
         .bind(&news.feed)
         .bind(news.published)
+        .bind(meta.language)
+        .bind(meta.cluster_id)
+        .bind(meta.story_status)
+        // BIGINT is signed; the bits round-trip through i64
+        .bind(meta.exact_hash as i64)
+        .bind(meta.sim_hash as i64)
+        .bind(meta.first_seen)
         .fetch_one(&self.pool)
         .await;
 
@@ -322,17 +438,27 @@ impl Database {
         let start = std::time::Instant::now();
         let tokens_vec = market.get_tokens();
         let tokens_json = serde_json::to_value(&tokens_vec).unwrap_or(serde_json::Value::Null);
+        let tags_json = market
+            .tags
+            .as_ref()
+            .and_then(|t| serde_json::to_value(t).ok());
 
         let res = sqlx::query(
             r#"
-            INSERT INTO markets (market_id, question, description, start_date, end_date, active, closed, archived, tokens)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO markets (market_id, question, description, start_date, end_date, active, closed, archived, tokens, category, tags, volume, liquidity, event_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             ON CONFLICT (market_id) DO UPDATE SET
                 question = EXCLUDED.question,
                 description = EXCLUDED.description,
+                end_date = EXCLUDED.end_date,
                 active = EXCLUDED.active,
                 closed = EXCLUDED.closed,
-                archived = EXCLUDED.archived
+                archived = EXCLUDED.archived,
+                category = COALESCE(EXCLUDED.category, markets.category),
+                tags = COALESCE(EXCLUDED.tags, markets.tags),
+                volume = COALESCE(EXCLUDED.volume, markets.volume),
+                liquidity = COALESCE(EXCLUDED.liquidity, markets.liquidity),
+                event_id = COALESCE(EXCLUDED.event_id, markets.event_id)
             "#,
         )
         .bind(&market.id)
@@ -344,6 +470,11 @@ impl Database {
         .bind(market.closed)
         .bind(market.archived)
         .bind(tokens_json)
+        .bind(&market.category)
+        .bind(tags_json)
+        .bind(market.volume)
+        .bind(market.liquidity)
+        .bind(&market.event_id)
         .execute(&self.pool)
         .await;
 
@@ -364,16 +495,15 @@ impl Database {
         &self,
         event_id: i64,
         market_id: &str,
-        signal: &crate::llm::SignalResponse,
-        prompt: &str,
-        model: &str,
+        analysis: &crate::llm::LlmAnalysis,
     ) -> Result<()> {
         let start = std::time::Instant::now();
+        let signal = &analysis.signal;
         let json = serde_json::to_string(signal)?;
         let res = sqlx::query(
             r#"
-            INSERT INTO signals (event_id, market_id, sentiment, confidence, raw_json, prompt, model)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO signals (event_id, market_id, sentiment, confidence, raw_json, prompt, model, prompt_tokens, completion_tokens, cost_usd, prompt_template)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             "#,
         )
         .bind(event_id)
@@ -381,8 +511,12 @@ impl Database {
         .bind(&signal.sentiment)
         .bind(signal.confidence)
         .bind(json)
-        .bind(prompt)
-        .bind(model)
+        .bind(&analysis.prompt)
+        .bind(&analysis.model)
+        .bind(analysis.usage.prompt_tokens as i32)
+        .bind(analysis.usage.completion_tokens as i32)
+        .bind(analysis.cost_usd)
+        .bind(&analysis.template_id)
         .execute(&self.pool)
         .await;
 
@@ -399,6 +533,42 @@ impl Database {
         Ok(())
     }
 
+    pub async fn save_llm_usage(
+        &self,
+        event_id: Option<i64>,
+        market_id: &str,
+        record: &crate::llm::budget::UsageRecord,
+    ) -> Result<()> {
+        let start = std::time::Instant::now();
+        let res = sqlx::query(
+            r#"
+            INSERT INTO llm_usage (event_id, market_id, model, prompt_tokens, completion_tokens, cost_usd, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(event_id)
+        .bind(market_id)
+        .bind(&record.model)
+        .bind(record.usage.prompt_tokens as i32)
+        .bind(record.usage.completion_tokens as i32)
+        .bind(record.cost_usd)
+        .bind(record.at)
+        .execute(&self.pool)
+        .await;
+
+        match res {
+            Ok(_) => {
+                metrics::counter!("database_queries_total", "table" => "llm_usage", "op" => "insert", "status" => "success").increment(1);
+            }
+            Err(_) => {
+                metrics::counter!("database_queries_total", "table" => "llm_usage", "op" => "insert", "status" => "error").increment(1);
+            }
+        }
+        res?;
+        metrics::histogram!("database_query_duration_seconds", "table" => "llm_usage", "op" => "insert").record(start.elapsed().as_secs_f64());
+        Ok(())
+    }
+
     pub async fn save_order(&self, order: &Order, market_data_snap_id: Option<i64>) -> Result<()> {
         let start = std::time::Instant::now();
         let side = match order.side {
@@ -583,17 +753,22 @@ impl Database {
         Ok(positions)
     }
 
-    pub async fn load_recent_events(&self, limit: i64) -> Result<Vec<(String, String)>> {
+    /// Events first seen at or after `since`, oldest first.
+    pub async fn load_events_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<RecentEvent>> {
         let start = std::time::Instant::now();
         let rows = sqlx::query(
             r#"
-            SELECT title, description
+            SELECT title, description, source, cluster_id, exact_hash, sim_hash,
+                   COALESCE(first_seen, created_at) AS first_seen
             FROM events
-            ORDER BY created_at DESC
-            LIMIT $1
+            WHERE COALESCE(first_seen, created_at) >= $1
+            ORDER BY COALESCE(first_seen, created_at) ASC
             "#,
         )
-        .bind(limit)
+        .bind(since)
         .fetch_all(&self.pool)
         .await;
 
@@ -610,18 +785,189 @@ impl Database {
 
         let mut events = Vec::new();
         for row in rows {
-            let title: String = row.get("title");
             let description: Option<String> = row.get("description");
-            events.push((title, description.unwrap_or_default()));
+            let source: Option<String> = row.get("source");
+            events.push(RecentEvent {
+                title: row.get("title"),
+                description: description.unwrap_or_default(),
+                source: source.unwrap_or_default(),
+                cluster_id: row.get("cluster_id"),
+                exact_hash: row.get::<Option<i64>, _>("exact_hash").map(|h| h as u64),
+                sim_hash: row.get::<Option<i64>, _>("sim_hash").map(|h| h as u64),
+                first_seen: row
+                    .get::<Option<chrono::DateTime<chrono::Utc>>, _>("first_seen")
+                    .unwrap_or_else(chrono::Utc::now),
+            });
         }
         Ok(events)
     }
 
+    /// Newest `published_at` per source, for the staleness watermarks.
+    pub async fn load_source_watermarks(
+        &self,
+    ) -> Result<Vec<(String, chrono::DateTime<chrono::Utc>)>> {
+        let start = std::time::Instant::now();
+        let rows = sqlx::query(
+            r#"
+            SELECT source, MAX(published_at) AS published
+            FROM events
+            WHERE source IS NOT NULL AND published_at IS NOT NULL
+            GROUP BY source
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match &rows {
+            Ok(_) => {
+                metrics::counter!("database_queries_total", "table" => "events", "op" => "select", "status" => "success").increment(1);
+            }
+            Err(_) => {
+                metrics::counter!("database_queries_total", "table" => "events", "op" => "select", "status" => "error").increment(1);
+            }
+        }
+        let rows = rows?;
+        metrics::histogram!("database_query_duration_seconds", "table" => "events", "op" => "select").record(start.elapsed().as_secs_f64());
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("source"), row.get("published")))
+            .collect())
+    }
+
+    /// Clustered events first seen at or after `since`, oldest first.
+    pub async fn load_source_reports(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<SourceReport>> {
+        let start = std::time::Instant::now();
+        let rows = sqlx::query(
+            r#"
+            SELECT source, story_status,
+                   EXTRACT(EPOCH FROM first_seen - MIN(first_seen) OVER (PARTITION BY cluster_id))::DOUBLE PRECISION AS lag_secs
+            FROM events
+            WHERE cluster_id IS NOT NULL AND story_status IS NOT NULL AND first_seen >= $1
+            ORDER BY first_seen ASC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await;
+
+        match &rows {
+            Ok(_) => {
+                metrics::counter!("database_queries_total", "table" => "events", "op" => "select", "status" => "success").increment(1);
+            }
+            Err(_) => {
+                metrics::counter!("database_queries_total", "table" => "events", "op" => "select", "status" => "error").increment(1);
+            }
+        }
+        let rows = rows?;
+        metrics::histogram!("database_query_duration_seconds", "table" => "events", "op" => "select").record(start.elapsed().as_secs_f64());
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SourceReport {
+                source: row.get::<Option<String>, _>("source").unwrap_or_default(),
+                status: row.get("story_status"),
+                lag_secs: row.get::<Option<f64>, _>("lag_secs").unwrap_or(0.0),
+            })
+            .collect())
+    }
+
+    /// Signals created at or after `since` whose markets have closed.
+    pub async fn load_resolved_signals(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ResolvedSignal>> {
+        let start = std::time::Instant::now();
+        let rows = sqlx::query(
+            r#"
+            SELECT e.source, s.sentiment, s.confidence, m.tokens,
+                   d.side, d.market_price AS entry_price
+            FROM signals s
+            JOIN events e ON e.event_id = s.event_id
+            JOIN markets m ON m.market_id = s.market_id
+            LEFT JOIN LATERAL (
+                SELECT side, market_price FROM decisions
+                WHERE event_id = s.event_id AND market_id = s.market_id
+                ORDER BY decision_id LIMIT 1
+            ) d ON TRUE
+            WHERE m.closed AND s.sentiment <> 'None' AND s.created_at >= $1
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await;
+
+        match &rows {
+            Ok(_) => {
+                metrics::counter!("database_queries_total", "table" => "signals", "op" => "select", "status" => "success").increment(1);
+            }
+            Err(_) => {
+                metrics::counter!("database_queries_total", "table" => "signals", "op" => "select", "status" => "error").increment(1);
+            }
+        }
+        let rows = rows?;
+        metrics::histogram!("database_query_duration_seconds", "table" => "signals", "op" => "select").record(start.elapsed().as_secs_f64());
+
+        let mut signals = Vec::new();
+        for row in rows {
+            let tokens = row
+                .get::<Option<serde_json::Value>, _>("tokens")
+                .and_then(|t| serde_json::from_value(t).ok())
+                .unwrap_or_default();
+            let entry_price: Option<String> = row.get("entry_price");
+            signals.push(ResolvedSignal {
+                source: row.get::<Option<String>, _>("source").unwrap_or_default(),
+                sentiment: row.get("sentiment"),
+                confidence: row.get("confidence"),
+                tokens,
+                side: row.get("side"),
+                entry_price: entry_price.and_then(|p| Decimal::from_str_exact(&p).ok()),
+            });
+        }
+        Ok(signals)
+    }
+
+    /// Total LLM spend (USD) billed at or after `since`. Signals written
+    /// before `llm_usage` existed still count through their own `cost_usd`.
+    pub async fn load_llm_spend_since(&self, since: chrono::DateTime<chrono::Utc>) -> Result<f64> {
+        let start = std::time::Instant::now();
+        let row = sqlx::query(
+            r#"
+            SELECT (
+                COALESCE((SELECT SUM(cost_usd) FROM llm_usage WHERE created_at >= $1), 0)
+                + COALESCE((SELECT SUM(cost_usd) FROM signals
+                            WHERE created_at >= $1
+                              AND created_at < COALESCE((SELECT MIN(created_at) FROM llm_usage), 'infinity')), 0)
+            )::DOUBLE PRECISION AS spend
+            "#,
+        )
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await;
+
+        match &row {
+            Ok(_) => {
+                metrics::counter!("database_queries_total", "table" => "llm_usage", "op" => "select", "status" => "success").increment(1);
+            }
+            Err(_) => {
+                metrics::counter!("database_queries_total", "table" => "llm_usage", "op" => "select", "status" => "error").increment(1);
+            }
+        }
+        let row = row?;
+        metrics::histogram!("database_query_duration_seconds", "table" => "llm_usage", "op" => "select").record(start.elapsed().as_secs_f64());
+
+        Ok(row.get("spend"))
+    }
+
     pub async fn load_markets(&self) -> Result<Vec<crate::core::types::PolyMarketMarket>> {
         let start = std::time::Instant::now();
         let rows = sqlx::query(
             r#"
-            SELECT market_id, question, description, active, closed, archived, start_date, end_date, tokens
+            SELECT market_id, question, description, active, closed, archived, start_date, end_date, tokens,
+                   category, tags, volume, liquidity, event_id
             FROM markets
             "#,
         )
@@ -651,6 +997,13 @@ impl Database {
             let start_date: Option<String> = row.get("start_date");
             let end_date: Option<String> = row.get("end_date");
             let tokens_val: Option<serde_json::Value> = row.get("tokens");
+            let category: Option<String> = row.get("category");
+            let tags: Option<Vec<crate::core::types::PolyTag>> = row
+                .get::<Option<serde_json::Value>, _>("tags")
+                .and_then(|v| serde_json::from_value(v).ok());
+            let volume: Option<rust_decimal::Decimal> = row.get("volume");
+            let liquidity: Option<rust_decimal::Decimal> = row.get("liquidity");
+            let event_id: Option<String> = row.get("event_id");
 
             let mut clob_token_ids = None;
             let mut outcomes = None;
@@ -683,6 +1036,11 @@ impl Database {
                 clob_token_ids,
                 outcomes,
                 outcome_prices,
+                category,
+                tags,
+                volume,
+                liquidity,
+                event_id,
             });
         }
         Ok(markets)
@@ -1,88 +1,241 @@
 use crate::bus::types::Bus;
-use crate::config::config::RssCfg;
+use crate::config::config::{RssCfg, RssFeedCfg};
 use crate::core::types::{Actor, RawNews};
+use crate::rss::article::{ArticleFetcher, strip_html};
+use crate::rss::feed_state::{FeedItem, FeedState};
+use crate::rss::filter::FeedFilter;
+use crate::rss::parser::{format_name, parse_feed};
 use anyhow::{Context, Result};
 use futures::{StreamExt, stream};
-use reqwest::Client;
-use rss::Channel;
-use std::time::Duration;
+use reqwest::header::{ETAG, HeaderName, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+use std::time::{Duration, Instant};
 
 use tokio::time::interval;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 pub struct RssActor {
     pub bus: Bus,
     pub client: Client,
     pub rss_cfg: RssCfg,
     pub shutdown: CancellationToken,
+    /// Enabled feeds, highest priority first
+    feeds: Vec<Feed>,
+    articles: ArticleFetcher,
+}
+
+struct Feed {
+    cfg: RssFeedCfg,
+    filter: FeedFilter,
+    state: FeedState,
+}
+
+/// Result of one conditional GET.
+enum FetchOutcome {
+    NotModified,
+    Fetched {
+        items: Vec<FeedItem<RawNews>>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
 }
 
 impl RssActor {
     pub fn new(bus: Bus, client: Client, rss_cfg: RssCfg, shutdown: CancellationToken) -> RssActor {
+        let now = Instant::now();
+        let mut feeds: Vec<Feed> = rss_cfg
+            .feeds
+            .iter()
+            .filter(|f| f.enabled)
+            .map(|f| Feed {
+                cfg: f.clone(),
+                filter: FeedFilter::new(f).expect("Invalid rss feed filter"),
+                state: FeedState::new(f.refresh.unwrap_or(rss_cfg.refresh), now),
+            })
+            .collect();
+        feeds.sort_by_key(|f| std::cmp::Reverse(f.cfg.priority));
+        let articles = ArticleFetcher::new(&rss_cfg.articles, client.clone());
         Self {
             bus,
             client,
             rss_cfg,
             shutdown,
+            feeds,
+            articles,
         }
     }
 
-    async fn fetch_rss_news(&mut self) -> Result<Vec<RawNews>> {
+    /// Poll the feeds that are due and return the items not published before.
+    async fn fetch_rss_news(&mut self) -> Vec<RawNews> {
         let start = std::time::Instant::now();
-        let client = self.client.clone();
-        let feeds = self.rss_cfg.feeds.clone();
-
-        // Create futures: one per feed
-        let fetches = feeds.into_iter().map(move |feed| {
-            let client = client.clone();
-            async move {
-                let resp = client
-                    .get(&feed.url)
-                    .send()
-                    .await
-                    .with_context(|| format!("RssActor: Failed to GET {}", feed.url))?;
-
-                let body = resp.text().await?;
-                let channel = Channel::read_from(body.as_bytes())
-                    .with_context(|| format!("RssActor: Failed to parse RSS from {}", feed.url))?;
-
-                let items = channel.items.into_iter().map(|item| RawNews {
-                    feed: feed.id.clone(),
-                    title: item.title().unwrap_or("").to_string(),
-                    url: item.link().unwrap_or("").to_string(),
-                    published: item
-                        .pub_date()
-                        .and_then(|d| chrono::DateTime::parse_from_rfc2822(d).ok())
-                        .map(|dt| dt.with_timezone(&chrono::Utc)),
-                    description: item.description().unwrap_or("").to_string(),
-                    labels: Vec::new(),
-                });
-
-                Ok::<Vec<RawNews>, anyhow::Error>(items.collect())
-            }
+        let now = Instant::now();
+        let due: Vec<usize> = (0..self.feeds.len())
+            .filter(|&i| self.feeds[i].state.is_due(now))
+            .collect();
+        if due.is_empty() {
+            return Vec::new();
+        }
+
+        // Create futures: one per due feed (by priority), with its conditional GET validators
+        let fetches = due.into_iter().map(|i| {
+            let client = self.client.clone();
+            let feed = &self.feeds[i];
+            let cfg = feed.cfg.clone();
+            let etag = feed.state.etag.clone();
+            let last_modified = feed.state.last_modified.clone();
+            async move { (i, fetch_feed(&client, &cfg, etag, last_modified).await) }
         });
 
         // Fetch concurrently with bounded parallelism
-        let results = stream::iter(fetches)
-            .buffer_unordered(8) // 8 feeds in parallel
+        let mut results = stream::iter(fetches)
+            .buffer_unordered(self.rss_cfg.concurrency.max(1))
             .collect::<Vec<_>>()
             .await;
+        results.sort_by_key(|(i, _)| *i);
 
-        // Flatten and handle errors
         let mut all_news = Vec::new();
-        for result in results {
+        let now = Instant::now();
+        for (i, result) in results {
+            let Feed { cfg, filter, state } = &mut self.feeds[i];
+            let feed_id = &cfg.id;
             match result {
-                Ok(mut items) => all_news.append(&mut items),
+                Ok(FetchOutcome::NotModified) => {
+                    metrics::counter!("rss_fetches_total", "feed" => feed_id.clone(), "status" => "not_modified").increment(1);
+                    state.succeeded(now);
+                }
+                Ok(FetchOutcome::Fetched {
+                    items,
+                    etag,
+                    last_modified,
+                }) => {
+                    metrics::counter!("rss_fetches_total", "feed" => feed_id.clone(), "status" => "success").increment(1);
+                    state.etag = etag;
+                    state.last_modified = last_modified;
+                    state.succeeded(now);
+                    let listed = items.len();
+                    let fresh = state.take_new(items);
+                    metrics::counter!("rss_items_skipped_total", "feed" => feed_id.clone())
+                        .increment((listed - fresh.len()) as u64);
+                    for mut news in fresh {
+                        news.description = strip_html(&news.description);
+                        if let Err(reason) = filter.check(&news) {
+                            metrics::counter!("rss_items_filtered_total", "feed" => feed_id.clone(), "reason" => reason.as_str())
+                                .increment(1);
+                            continue;
+                        }
+                        for label in &cfg.labels {
+                            if !news.labels.contains(label) {
+                                news.labels.push(label.clone());
+                            }
+                        }
+                        all_news.push(news);
+                    }
+                }
                 Err(e) => {
-                    error!("RssActor: fetch failed: {:?}", e);
+                    metrics::counter!("rss_fetches_total", "feed" => feed_id.clone(), "status" => "error").increment(1);
+                    let delay = state.failed(now, self.rss_cfg.max_backoff);
+                    warn!(
+                        "RssActor: fetch of {} failed, retrying in {:?}: {:#}",
+                        feed_id, delay, e
+                    );
                 }
             }
         }
 
         metrics::histogram!("rss_fetch_duration_seconds").record(start.elapsed().as_secs_f64());
-        Ok(all_news)
+        all_news
+    }
+
+    /// Publish the items oldest first, so downstream watermarks see them as
+    /// published. With article enrichment each item instead goes out as soon
+    /// as its own article is fetched (fetches start oldest first), so a slow
+    /// page never holds back the others. Stops early on shutdown.
+    async fn publish_news(&self, news: Vec<RawNews>) {
+        if !self.articles.enabled() {
+            for ev in news {
+                self.publish(ev).await;
+            }
+            return;
+        }
+        let articles = &self.articles;
+        let mut enriched = stream::iter(news.into_iter().map(|mut news| async move {
+            news.body = articles.fetch_body(&news.url).await;
+            news
+        }))
+        .buffer_unordered(articles.concurrency());
+        loop {
+            tokio::select! {
+                _ = self.shutdown.cancelled() => return,
+                next = enriched.next() => match next {
+                    Some(ev) => self.publish(ev).await,
+                    None => return,
+                },
+            }
+        }
+    }
+
+    async fn publish(&self, ev: RawNews) {
+        if let Err(e) = self.bus.raw_news.publish(ev).await {
+            error!(?e, "RssActor: publish to raw_news failed");
+        }
+    }
+}
+
+/// GET a feed, sending the validators from the last response so an unchanged
+/// feed answers 304 without a body.
+async fn fetch_feed(
+    client: &Client,
+    feed: &RssFeedCfg,
+    etag: Option<String>,
+    last_modified: Option<String>,
+) -> Result<FetchOutcome> {
+    let mut req = client.get(&feed.url);
+    for (name, value) in &feed.headers {
+        req = req.header(name.as_str(), value.as_str());
+    }
+    if let Some(timeout) = feed.timeout {
+        req = req.timeout(timeout);
     }
+    if let Some(etag) = &etag {
+        req = req.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &last_modified {
+        req = req.header(IF_MODIFIED_SINCE, last_modified);
+    }
+    let resp = req
+        .send()
+        .await
+        .with_context(|| format!("RssActor: Failed to GET {}", feed.url))?;
+
+    if resp.status() == StatusCode::NOT_MODIFIED {
+        return Ok(FetchOutcome::NotModified);
+    }
+    let resp = resp
+        .error_for_status()
+        .with_context(|| format!("RssActor: Bad status from {}", feed.url))?;
+
+    let header = |name: HeaderName| {
+        resp.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    // Keep the old validators if the server stops sending them
+    let etag = header(ETAG).or(etag);
+    let last_modified = header(LAST_MODIFIED).or(last_modified);
+
+    let body = resp.bytes().await?;
+    let (format, items) = parse_feed(&feed.id, &body)
+        .with_context(|| format!("RssActor: Failed to parse feed from {}", feed.url))?;
+    metrics::counter!("rss_feed_formats_total", "feed" => feed.id.clone(), "format" => format_name(&format))
+        .increment(1);
+
+    Ok(FetchOutcome::Fetched {
+        items,
+        etag,
+        last_modified,
+    })
 }
 
 #[async_trait::async_trait]
@@ -90,8 +243,16 @@ impl Actor for RssActor {
     async fn run(mut self) -> Result<()> {
         info!("RssActor started");
 
-        // throttle the loop
-        let mut tick = interval(Duration::from_secs(self.rss_cfg.refresh.as_secs())); // refresh cadence
+        // Wake up often enough for the most frequently polled feed; each feed
+        // keeps its own schedule and backoff.
+        let cadence = self
+            .feeds
+            .iter()
+            .map(|f| f.cfg.refresh.unwrap_or(self.rss_cfg.refresh))
+            .min()
+            .unwrap_or(self.rss_cfg.refresh)
+            .max(Duration::from_secs(1));
+        let mut tick = interval(cadence);
 
         loop {
             tokio::select! {
@@ -103,35 +264,12 @@ impl Actor for RssActor {
 
                 //Fetch rss news
                 _ = tick.tick() => {
-                    match self.fetch_rss_news().await  {
-                        Ok(rss_news) => {
-                            metrics::counter!("rss_fetches_total", "status" => "success").increment(1);
-                            metrics::counter!("rss_items_fetched_total").increment(rss_news.len() as u64);
-                            let bus = self.bus.clone();
-                            stream::iter(
-                                rss_news
-                                    .into_iter()
-                                    .map(move |ev|
-                                    {
-                                        let bus = bus.clone();
-                                        async move { bus.raw_news.publish(ev).await }
-                                    })
-                            )
-                            .buffer_unordered(32)       // cap concurrency
-                            .for_each(|res| async {
-                                if let Err(e) = res {
-                                    error!(?e, "RssActor: publish to raw_news failed");
-                                }
-                            })
-                            .await;
-                        }
-                        Err(e) => {
-                            metrics::counter!("rss_fetches_total", "status" => "error").increment(1);
-                            error!("RssActor: failed to fetch active poly market event: {:#}", e);
-                            // backoff to avoid hot loop on repeated failures
-                            tokio::time::sleep(Duration::from_secs(5)).await;
-                        }
+                    let rss_news = self.fetch_rss_news().await;
+                    if rss_news.is_empty() {
+                        continue;
                     }
+                    metrics::counter!("rss_items_fetched_total").increment(rss_news.len() as u64);
+                    self.publish_news(rss_news).await;
                 }
             }
         }
@@ -0,0 +1,339 @@
+//! Optional article enrichment: fetches the linked page and extracts its main
+//! text, so the analyst sees more than a headline.
+//!
+//! The main content is the page's `<article>` (else `<main>`, else `<body>`),
+//! read paragraph by paragraph while skipping scripts, navigation, headers,
+//! footers, forms and asides. Fetches from one domain are spaced out (waiting
+//! at most the fetch timeout for a slot) and capped in size; a page that
+//! cannot be fetched leaves the item without a body.
+
+use crate::config::config::ArticleCfg;
+use anyhow::{Context, Result, bail};
+use reqwest::Client;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Elements whose text is never part of the article.
+const BOILERPLATE: [&str; 12] = [
+    "script",
+    "style",
+    "noscript",
+    "nav",
+    "header",
+    "footer",
+    "aside",
+    "form",
+    "button",
+    "iframe",
+    "svg",
+    "figcaption",
+];
+
+/// Blocks shorter than this are usually bylines, captions or share links.
+const MIN_BLOCK_CHARS: usize = 40;
+
+lazy_static::lazy_static! {
+    static ref ROOTS: [Selector; 4] = [
+        Selector::parse("article").unwrap(),
+        Selector::parse("main").unwrap(),
+        Selector::parse("[role=main]").unwrap(),
+        Selector::parse("body").unwrap(),
+    ];
+    static ref BLOCKS: Selector = Selector::parse("p, h2, h3, li, blockquote, pre").unwrap();
+}
+
+pub struct ArticleFetcher {
+    cfg: ArticleCfg,
+    client: Client,
+    /// Latest fetch slot reserved per host
+    slots: Mutex<HashMap<String, Instant>>,
+}
+
+impl ArticleFetcher {
+    pub fn new(cfg: &ArticleCfg, client: Client) -> Self {
+        Self {
+            cfg: cfg.clone(),
+            client,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.cfg.enabled
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.cfg.concurrency.max(1)
+    }
+
+    /// Fetch `url` and extract its main text, truncated to `maxChars`, once
+    /// the domain's next slot comes up. `None` when that is more than the fetch
+    /// timeout away or the fetch failed.
+    pub async fn fetch_body(&self, url: &str) -> Option<String> {
+        let host = reqwest::Url::parse(url).ok()?.host_str()?.to_string();
+        let Some(wait) = self.reserve(&host) else {
+            metrics::counter!("rss_article_fetches_total", "status" => "rate_limited").increment(1);
+            return None;
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+
+        let start = std::time::Instant::now();
+        let result = self.fetch_html(url).await;
+        metrics::histogram!("rss_article_fetch_duration_seconds")
+            .record(start.elapsed().as_secs_f64());
+        match result {
+            Ok(html) => {
+                let body = truncate(&extract_main_text(&html), self.cfg.max_chars);
+                let status = if body.is_empty() { "empty" } else { "success" };
+                metrics::counter!("rss_article_fetches_total", "status" => status).increment(1);
+                (!body.is_empty()).then_some(body)
+            }
+            Err(e) => {
+                metrics::counter!("rss_article_fetches_total", "status" => "error").increment(1);
+                tracing::debug!("Article fetch failed for {}: {:#}", url, e);
+                None
+            }
+        }
+    }
+
+    /// Reserve the host's next fetch slot, `domainInterval` after the last
+    /// one, and return the wait until it. `None` if it is further away than
+    /// the fetch timeout.
+    fn reserve(&self, host: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = match slots.get(host) {
+            Some(last) => (*last + self.cfg.domain_interval).max(now),
+            None => now,
+        };
+        let wait = slot - now;
+        if wait > self.cfg.timeout {
+            return None;
+        }
+        slots.insert(host.to_string(), slot);
+        Some(wait)
+    }
+
+    async fn fetch_html(&self, url: &str) -> Result<String> {
+        let mut resp = self
+            .client
+            .get(url)
+            .timeout(self.cfg.timeout)
+            .send()
+            .await
+            .with_context(|| format!("GET {}", url))?
+            .error_for_status()?;
+
+        let is_html = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_none_or(|ct| ct.contains("html"));
+        if !is_html {
+            bail!("not an HTML page");
+        }
+        if resp
+            .content_length()
+            .is_some_and(|len| len > self.cfg.max_bytes as u64)
+        {
+            bail!("page larger than {} bytes", self.cfg.max_bytes);
+        }
+
+        // Content-Length may be missing or wrong; stop reading at the cap
+        let mut bytes = Vec::new();
+        while let Some(chunk) = resp.chunk().await? {
+            if bytes.len() + chunk.len() > self.cfg.max_bytes {
+                bail!("page larger than {} bytes", self.cfg.max_bytes);
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// Main text of an HTML page, one paragraph per line.
+pub fn extract_main_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let Some(root) = ROOTS
+        .iter()
+        .find_map(|selector| document.select(selector).next())
+    else {
+        return String::new();
+    };
+
+    let blocks: Vec<String> = root
+        .select(&BLOCKS)
+        .filter(|block| !in_boilerplate(block))
+        // Nested blocks (a <p> inside an <li>) are read with their parent
+        .filter(|block| {
+            !block
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .take_while(|a| a.id() != root.id())
+                .any(|a| BLOCKS.matches(&a))
+        })
+        .map(|block| collapse_whitespace(&block.text().collect::<String>()))
+        .filter(|text| text.chars().count() >= MIN_BLOCK_CHARS)
+        .collect();
+    if !blocks.is_empty() {
+        return blocks.join("\n");
+    }
+
+    // No paragraph markup: all text outside the boilerplate
+    let text: Vec<&str> = root
+        .descendants()
+        .filter_map(|node| node.value().as_text().map(|t| (node, t)))
+        .filter(|(node, _)| {
+            !node
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|a| BOILERPLATE.contains(&a.value().name()))
+        })
+        .map(|(_, t)| &**t)
+        .collect();
+    collapse_whitespace(&text.join(" "))
+}
+
+/// Plain text of an HTML fragment such as an RSS description.
+pub fn strip_html(fragment: &str) -> String {
+    if !fragment.contains('<') && !fragment.contains('&') {
+        return collapse_whitespace(fragment);
+    }
+    let html = Html::parse_fragment(fragment);
+    let text: Vec<&str> = html
+        .root_element()
+        .descendants()
+        .filter_map(|node| node.value().as_text().map(|t| (node, t)))
+        .filter(|(node, _)| {
+            !node
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|a| matches!(a.value().name(), "script" | "style"))
+        })
+        .map(|(_, t)| &**t)
+        .collect();
+    collapse_whitespace(&text.join(" "))
+}
+
+fn in_boilerplate(element: &ElementRef) -> bool {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .any(|a| BOILERPLATE.contains(&a.value().name()))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_article_text() {
+        let text = extract_main_text(include_str!("../../testdata/articles/article.html"));
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3, "{text}");
+        assert!(lines[0].starts_with("The Federal Reserve lowered its benchmark rate"));
+        assert!(lines[1].starts_with("Chair Jerome Powell said"));
+        assert!(lines[2].contains("two further cuts"));
+        for boilerplate in [
+            "Subscribe",
+            "Markets Home",
+            "track(",
+            "Related:",
+            "Share this",
+        ] {
+            assert!(!text.contains(boilerplate), "{boilerplate} in {text}");
+        }
+    }
+
+    #[test]
+    fn test_extract_without_paragraphs() {
+        let text = extract_main_text(include_str!("../../testdata/articles/no_paragraphs.html"));
+        assert_eq!(text, "ECB holds rates at 2% as Lagarde warns on inflation.");
+    }
+
+    #[test]
+    fn test_strip_html() {
+        assert_eq!(
+            strip_html("<p>Fed <b>cuts</b> rates &amp; signals more.</p><img src=\"x.png\">"),
+            "Fed cuts rates & signals more."
+        );
+        assert_eq!(strip_html("plain   text"), "plain text");
+        assert_eq!(truncate("héllo world", 5), "héllo");
+    }
+
+    #[test]
+    fn test_domain_rate_limit() {
+        let cfg = ArticleCfg {
+            domain_interval: Duration::from_secs(2),
+            timeout: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let fetcher = ArticleFetcher::new(&cfg, Client::new());
+        assert_eq!(fetcher.reserve("example.com"), Some(Duration::ZERO));
+        // Queued behind the first fetch
+        let wait = fetcher.reserve("example.com").unwrap();
+        assert!(wait > Duration::from_millis(1900) && wait <= Duration::from_secs(2));
+        assert!(fetcher.reserve("example.com").unwrap() > Duration::from_millis(3900));
+        // The next slot is more than the timeout away
+        assert_eq!(fetcher.reserve("example.com"), None);
+        assert_eq!(fetcher.reserve("example.org"), Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_same_host_articles_wait_for_their_slot() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let page = include_str!("../../testdata/articles/article.html");
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                tokio::spawn(async move {
+                    let mut request = [0u8; 4096];
+                    let _ = socket.read(&mut request).await;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        page.len(),
+                        page
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        let cfg = ArticleCfg {
+            enabled: true,
+            domain_interval: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let client = Client::builder().no_proxy().build().unwrap();
+        let fetcher = ArticleFetcher::new(&cfg, client);
+        let (fed, ecb) = (
+            format!("http://{addr}/markets/fed"),
+            format!("http://{addr}/markets/ecb"),
+        );
+        let start = Instant::now();
+        let (a, b) = tokio::join!(fetcher.fetch_body(&fed), fetcher.fetch_body(&ecb));
+        assert!(a.is_some_and(|body| body.starts_with("The Federal Reserve")));
+        assert!(b.is_some_and(|body| body.starts_with("The Federal Reserve")));
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
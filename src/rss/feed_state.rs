@@ -0,0 +1,156 @@
+//! Per-feed polling state: conditional GET validators, the high-water mark of
+//! items already published, the feed's schedule and its backoff.
+
+use chrono::{DateTime, Utc};
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// GUIDs remembered per feed; comfortably more than a channel lists.
+const MAX_SEEN: usize = 1000;
+
+/// An item as fetched, before the high-water check.
+#[derive(Debug, Clone)]
+pub struct FeedItem<T> {
+    /// GUID, else link, else title
+    pub id: String,
+    pub published: Option<DateTime<Utc>>,
+    pub item: T,
+}
+
+#[derive(Debug)]
+pub struct FeedState {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Newest pubDate published so far
+    high_water: Option<DateTime<Utc>>,
+    seen: HashSet<String>,
+    seen_order: VecDeque<String>,
+    refresh: Duration,
+    next_poll: Instant,
+    failures: u32,
+}
+
+impl FeedState {
+    pub fn new(refresh: Duration, now: Instant) -> Self {
+        Self {
+            etag: None,
+            last_modified: None,
+            high_water: None,
+            seen: HashSet::new(),
+            seen_order: VecDeque::new(),
+            refresh,
+            next_poll: now,
+            failures: 0,
+        }
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.next_poll
+    }
+
+    /// Items not published yet, oldest first: unseen GUIDs not older than the
+    /// high-water mark. Items without a pubDate are judged on their GUID alone.
+    pub fn take_new<T>(&mut self, mut items: Vec<FeedItem<T>>) -> Vec<T> {
+        items.sort_by_key(|i| i.published);
+        let high_water = self.high_water;
+        let mut fresh = Vec::new();
+        for item in items {
+            if self.seen.contains(&item.id) {
+                continue;
+            }
+            if let (Some(published), Some(hw)) = (item.published, high_water)
+                && published < hw
+            {
+                continue;
+            }
+            self.remember(item.id);
+            if let Some(published) = item.published {
+                self.high_water = Some(self.high_water.map_or(published, |hw| hw.max(published)));
+            }
+            fresh.push(item.item);
+        }
+        fresh
+    }
+
+    /// Schedule the next poll after a successful fetch (or a 304).
+    pub fn succeeded(&mut self, now: Instant) {
+        self.failures = 0;
+        self.next_poll = now + self.refresh;
+    }
+
+    /// Back off exponentially from the feed's refresh, up to `max_backoff`.
+    /// Returns the delay until the next attempt.
+    pub fn failed(&mut self, now: Instant, max_backoff: Duration) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        let factor = 2u32.saturating_pow(self.failures.min(16));
+        let delay = self
+            .refresh
+            .saturating_mul(factor)
+            .min(max_backoff.max(self.refresh));
+        self.next_poll = now + delay;
+        delay
+    }
+
+    fn remember(&mut self, id: String) {
+        if self.seen_order.len() == MAX_SEEN
+            && let Some(oldest) = self.seen_order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        self.seen.insert(id.clone());
+        self.seen_order.push_back(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn item(id: &str, published: Option<DateTime<Utc>>) -> FeedItem<String> {
+        FeedItem {
+            id: id.to_string(),
+            published,
+            item: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_only_new_items_pass() {
+        let mut state = FeedState::new(Duration::from_secs(60), Instant::now());
+        let t0 = Utc::now();
+        let at = |m: i64| Some(t0 + ChronoDuration::minutes(m));
+
+        let first = state.take_new(vec![item("b", at(1)), item("a", at(0))]);
+        assert_eq!(first, vec!["a", "b"]);
+
+        // Re-listed items are dropped; new GUIDs at or after the mark pass
+        let second = state.take_new(vec![
+            item("a", at(0)),
+            item("b", at(1)),
+            item("c", at(1)),
+            item("d", at(2)),
+            item("old", at(-5)),
+            item("undated", None),
+        ]);
+        assert_eq!(second, vec!["undated", "c", "d"]);
+        assert!(state.take_new(vec![item("undated", None)]).is_empty());
+    }
+
+    #[test]
+    fn test_backoff() {
+        let now = Instant::now();
+        let mut state = FeedState::new(Duration::from_secs(10), now);
+        assert!(state.is_due(now));
+
+        let max = Duration::from_secs(60);
+        assert_eq!(state.failed(now, max), Duration::from_secs(20));
+        assert_eq!(state.failed(now, max), Duration::from_secs(40));
+        assert_eq!(state.failed(now, max), Duration::from_secs(60));
+        assert!(!state.is_due(now + Duration::from_secs(59)));
+
+        state.succeeded(now);
+        assert!(state.is_due(now + Duration::from_secs(10)));
+        assert_eq!(state.failed(now, max), Duration::from_secs(20));
+    }
+}
@@ -0,0 +1,139 @@
+//! Per-feed keyword and regex filters over an item's title and description.
+
+use crate::config::config::RssFeedCfg;
+use crate::core::types::RawNews;
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// Why an item was filtered out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// Matched none of the include keywords or patterns
+    NotIncluded,
+    /// Matched an exclude keyword or pattern
+    Excluded,
+}
+
+impl Rejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rejection::NotIncluded => "not_included",
+            Rejection::Excluded => "excluded",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FeedFilter {
+    /// Lowercased
+    include_keywords: Vec<String>,
+    exclude_keywords: Vec<String>,
+    include_regex: Vec<Regex>,
+    exclude_regex: Vec<Regex>,
+}
+
+impl FeedFilter {
+    pub fn new(cfg: &RssFeedCfg) -> Result<Self> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| {
+                    Regex::new(p)
+                        .with_context(|| format!("rss feed {}: invalid regex {:?}", cfg.id, p))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        let lower = |keywords: &[String]| {
+            keywords
+                .iter()
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect()
+        };
+        Ok(Self {
+            include_keywords: lower(&cfg.include_keywords),
+            exclude_keywords: lower(&cfg.exclude_keywords),
+            include_regex: compile(&cfg.include_regex)?,
+            exclude_regex: compile(&cfg.exclude_regex)?,
+        })
+    }
+
+    /// Keywords match case-insensitively as substrings; patterns match as
+    /// written. An item must match an include rule, if there are any, and no
+    /// exclude rule.
+    pub fn check(&self, news: &RawNews) -> Result<(), Rejection> {
+        let text = format!("{} {}", news.title, news.description);
+        let lower = text.to_lowercase();
+
+        let excluded = self.exclude_keywords.iter().any(|k| lower.contains(k))
+            || self.exclude_regex.iter().any(|r| r.is_match(&text));
+        if excluded {
+            return Err(Rejection::Excluded);
+        }
+
+        let has_includes = !self.include_keywords.is_empty() || !self.include_regex.is_empty();
+        let included = self.include_keywords.iter().any(|k| lower.contains(k))
+            || self.include_regex.iter().any(|r| r.is_match(&text));
+        if has_includes && !included {
+            return Err(Rejection::NotIncluded);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn news(title: &str) -> RawNews {
+        RawNews {
+            url: String::new(),
+            title: title.to_string(),
+            description: String::new(),
+            feed: "test".to_string(),
+            published: None,
+            labels: vec![],
+            guid: None,
+            author: None,
+            updated: None,
+            body: None,
+        }
+    }
+
+    #[test]
+    fn test_feed_filter() {
+        let cfg = RssFeedCfg {
+            include_keywords: vec!["Fed".to_string(), "ECB".to_string()],
+            include_regex: vec![r"\bCPI\b".to_string()],
+            exclude_keywords: vec!["opinion".to_string()],
+            exclude_regex: vec![r"(?i)^sponsored:".to_string()],
+            ..Default::default()
+        };
+        let filter = FeedFilter::new(&cfg).unwrap();
+
+        assert_eq!(filter.check(&news("fed cuts rates")), Ok(()));
+        assert_eq!(filter.check(&news("US CPI rises 0.3%")), Ok(()));
+        assert_eq!(
+            filter.check(&news("Opinion: the Fed is wrong")),
+            Err(Rejection::Excluded)
+        );
+        assert_eq!(
+            filter.check(&news("Sponsored: ECB explained")),
+            Err(Rejection::Excluded)
+        );
+        assert_eq!(
+            filter.check(&news("Oil prices rise")),
+            Err(Rejection::NotIncluded)
+        );
+
+        // No rules: everything passes
+        let open = FeedFilter::new(&RssFeedCfg::default()).unwrap();
+        assert_eq!(open.check(&news("Oil prices rise")), Ok(()));
+
+        let bad = RssFeedCfg {
+            include_regex: vec!["(".to_string()],
+            ..Default::default()
+        };
+        assert!(FeedFilter::new(&bad).is_err());
+    }
+}
@@ -1 +1,5 @@
-pub mod actor;
\ No newline at end of file
+pub mod actor;
+mod article;
+mod feed_state;
+mod filter;
+mod parser;
@@ -0,0 +1,161 @@
+//! Feed parsing with format auto-detection: RSS 0.9x, 1.0 (RDF) and 2.0,
+//! Atom 1.0 and JSON Feed are all mapped into `RawNews`.
+
+use crate::core::types::RawNews;
+use crate::rss::feed_state::FeedItem;
+use anyhow::{Context, Result};
+use feed_rs::model::{Entry, FeedType};
+
+/// Short name of a feed format, for metrics and logs.
+pub fn format_name(feed_type: &FeedType) -> &'static str {
+    match feed_type {
+        FeedType::Atom => "atom",
+        FeedType::JSON => "json",
+        FeedType::RSS0 => "rss0",
+        FeedType::RSS1 => "rss1",
+        FeedType::RSS2 => "rss2",
+    }
+}
+
+/// Parse a feed body of any supported format into items for `feed_id`.
+pub fn parse_feed(feed_id: &str, body: &[u8]) -> Result<(FeedType, Vec<FeedItem<RawNews>>)> {
+    let feed = feed_rs::parser::parse(body).context("unrecognised or malformed feed")?;
+    let items = feed
+        .entries
+        .into_iter()
+        .map(|entry| to_item(feed_id, entry))
+        .collect();
+    Ok((feed.feed_type, items))
+}
+
+fn to_item(feed_id: &str, entry: Entry) -> FeedItem<RawNews> {
+    // Atom entries may carry only `updated`; RSS copies pubDate into both
+    let published = entry.published.or(entry.updated);
+    let url = entry
+        .links
+        .iter()
+        .find(|l| l.rel.as_deref().is_none_or(|r| r == "alternate"))
+        .or(entry.links.first())
+        .map(|l| l.href.clone())
+        .unwrap_or_default();
+    let description = entry
+        .summary
+        .map(|t| t.content)
+        .or_else(|| entry.content.and_then(|c| c.body))
+        .unwrap_or_default();
+    let labels = entry
+        .categories
+        .into_iter()
+        .map(|c| c.label.unwrap_or(c.term).trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let author = entry
+        .authors
+        .into_iter()
+        .map(|p| match p.email {
+            // RSS 2 <author> is an email, parsed as a person named "author"
+            Some(email) if p.name == "author" => email,
+            _ => p.name,
+        })
+        .map(|n| n.trim().to_string())
+        .find(|n| !n.is_empty());
+
+    FeedItem {
+        id: entry.id.clone(),
+        published,
+        item: RawNews {
+            feed: feed_id.to_string(),
+            title: entry.title.map(|t| t.content).unwrap_or_default(),
+            url,
+            published,
+            description,
+            labels,
+            guid: Some(entry.id),
+            author,
+            updated: entry.updated,
+            body: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(fixture: &str) -> (FeedType, Vec<RawNews>) {
+        let (feed_type, items) = parse_feed("test", fixture.as_bytes()).unwrap();
+        (feed_type, items.into_iter().map(|i| i.item).collect())
+    }
+
+    #[test]
+    fn test_rss_formats() {
+        let (feed_type, items) = parse(include_str!("../../testdata/feeds/rss2.xml"));
+        assert_eq!(format_name(&feed_type), "rss2");
+        assert_eq!(items.len(), 2);
+        let first = &items[0];
+        assert_eq!(first.title, "Fed cuts rates by 25bps");
+        assert_eq!(first.url, "https://example.com/fed-cut");
+        assert_eq!(first.guid.as_deref(), Some("fed-cut-2026-03-18"));
+        assert_eq!(first.labels, vec!["Economy", "Central Banks"]);
+        assert_eq!(
+            first.author.as_deref(),
+            Some("desk@example.com (Markets Desk)")
+        );
+        assert_eq!(
+            first.published.map(|p| p.to_rfc3339()).as_deref(),
+            Some("2026-03-18T18:00:00+00:00")
+        );
+
+        let (feed_type, items) = parse(include_str!("../../testdata/feeds/rss1.rdf"));
+        assert_eq!(format_name(&feed_type), "rss1");
+        assert_eq!(items[0].title, "ECB holds rates");
+        assert_eq!(items[0].url, "https://example.com/ecb-hold");
+
+        let (feed_type, items) = parse(include_str!("../../testdata/feeds/rss091.xml"));
+        assert_eq!(format_name(&feed_type), "rss0");
+        assert_eq!(items[0].title, "OPEC agrees output cut");
+    }
+
+    #[test]
+    fn test_atom() {
+        let (feed_type, items) = parse(include_str!("../../testdata/feeds/atom.xml"));
+        assert_eq!(format_name(&feed_type), "atom");
+        let entry = &items[0];
+        assert_eq!(entry.title, "Monetary policy decisions");
+        assert_eq!(entry.url, "https://example.org/press/2026/mp-decision");
+        assert_eq!(entry.guid.as_deref(), Some("tag:example.org,2026:press-1"));
+        assert_eq!(entry.author.as_deref(), Some("Press Office"));
+        assert_eq!(entry.labels, vec!["Monetary policy"]);
+        assert_eq!(
+            entry.description,
+            "The Governing Council decided to lower rates."
+        );
+        // No <published>: falls back to <updated>
+        assert_eq!(
+            entry.published.map(|p| p.to_rfc3339()).as_deref(),
+            Some("2026-03-12T13:15:00+00:00")
+        );
+        assert_eq!(entry.updated, entry.published);
+    }
+
+    #[test]
+    fn test_json_feed() {
+        let (feed_type, items) = parse(include_str!("../../testdata/feeds/feed.json"));
+        assert_eq!(format_name(&feed_type), "json");
+        let item = &items[0];
+        assert_eq!(item.title, "Bitcoin ETF approved");
+        assert_eq!(item.guid.as_deref(), Some("https://example.net/btc-etf"));
+        assert_eq!(item.author.as_deref(), Some("Crypto Desk"));
+        assert_eq!(item.labels, vec!["crypto", "sec"]);
+        assert_eq!(
+            item.description,
+            "The SEC approved the first spot bitcoin ETF."
+        );
+        assert!(item.updated.is_some() && item.published < item.updated);
+    }
+
+    #[test]
+    fn test_unknown_format() {
+        assert!(parse_feed("test", b"<html><body>not a feed</body></html>").is_err());
+    }
+}
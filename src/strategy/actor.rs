@@ -1,37 +1,66 @@
 use crate::bus::types::Bus;
-use crate::config::config::AppCfg;
+use crate::config::config::{AppCfg, NumericCheckCfg, RetrievalCfg};
 use crate::core::types::{
     Actor, Execution, MarketDataRequest, MarketDataSnap, Order, PolyMarketEvent, Portfolio, RawNews,
 };
 use crate::llm::LlmClient;
-use crate::persistence::database::Database;
+use crate::llm::prompts::PromptLibrary;
+use crate::persistence::database::{Database, EventMeta};
 use crate::strategy::analyst::MarketAnalyst;
-use crate::strategy::event_features::{EventFeatureExtractor, FeatureDictionaries};
+use crate::strategy::entity_dictionary::DictionaryReloader;
+use crate::strategy::event_features::{EventFeatureExtractor, EventFeatures};
 use crate::strategy::exact_duplicate_detector::{
     ExactDuplicateDetector, ExactDuplicateDetectorConfig,
 };
 use crate::strategy::hard_filters::HardFilterer;
 use crate::strategy::kelly::KellySizer;
-use crate::strategy::market_index::MarketIndex;
-use crate::strategy::sim_hash_cache::{SimHashCache, SimHashCacheConfig};
+use crate::strategy::market_index::{Embedder, MarketDoc, MarketIndex};
+use crate::strategy::market_indexer::{IndexOp, MarketIndexer, SharedMarketIndex};
+use crate::strategy::market_query::MarketQuery;
+use crate::strategy::numeric_facts::Verdict;
+use crate::strategy::reranker::Reranker;
+use crate::strategy::source_stats::SourceTracker;
+use crate::strategy::staleness::StalenessGate;
+use crate::strategy::story_clusterer::{StoryAssignment, StoryClusterer, StoryStatus};
+use crate::strategy::time_expressions::TimeExpressionParser;
 use crate::strategy::tokenization::{TokenizationConfig, TokenizedNews};
 use crate::strategy::types::*;
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use lru::LruCache;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 pub struct StrategyActor {
     pub bus: Bus,
     pub shutdown: CancellationToken,
+    /// Drops news published too long ago to trade on
+    pub staleness: StalenessGate,
     pub detector: ExactDuplicateDetector,
-    pub sim_hash_cache: SimHashCache,
+    pub story_clusterer: StoryClusterer,
+    /// Markets ordered per story, so corroborations and updates of a story
+    /// don't trade the same markets again
+    pub story_markets: LruCache<i64, HashSet<String>>,
+    /// Per-source reliability, passed to the analyst as a source weight
+    pub source_tracker: SourceTracker,
     pub event_feature_extractor: EventFeatureExtractor,
-    pub market_index: MarketIndex,
+    /// Rebuilds `event_feature_extractor` when the entity dictionary files change
+    pub entity_dicts: DictionaryReloader,
+    pub market_index: SharedMarketIndex,
+    /// Embeds news for story clustering without locking `market_index`
+    pub news_embedder: Embedder,
+    /// Index updates are applied by a background MarketIndexer
+    pub index_tx: mpsc::UnboundedSender<IndexOp>,
+    pub retrieval_cfg: RetrievalCfg,
+    pub numeric_check: NumericCheckCfg,
     pub hard_filterer: HardFilterer,
+    pub reranker: Reranker,
     pub kelly_sizer: KellySizer,
     pub market_data_cache: HashMap<String, MarketDataSnap>,
     pub analyst: MarketAnalyst,
@@ -46,20 +75,48 @@ pub struct StrategyActor {
 
 impl StrategyActor {
     pub fn new(bus: Bus, shutdown: CancellationToken, cfg: &AppCfg, db: Database) -> StrategyActor {
+        let market_index = MarketIndex::open(&cfg.strategy.market_index)
+            .expect("Failed to initialize MarketIndex");
+        let news_embedder = market_index.news_embedder();
+        let market_index: SharedMarketIndex = Arc::new(Mutex::new(market_index));
+        let index_tx = MarketIndexer::spawn(
+            market_index.clone(),
+            &cfg.strategy.market_index,
+            shutdown.clone(),
+        );
+
+        let mut entity_dicts = DictionaryReloader::new(&cfg.strategy.entities);
+        let dictionaries = entity_dicts
+            .load()
+            .expect("Failed to load entity dictionaries");
+        let time_parser = TimeExpressionParser::new(&cfg.strategy.time_expressions)
+            .expect("Invalid strategy.timeExpressions");
+
         Self {
             bus,
             shutdown,
+            staleness: StalenessGate::new(&cfg.strategy.staleness),
             detector: ExactDuplicateDetector::new(ExactDuplicateDetectorConfig::default()),
-            sim_hash_cache: SimHashCache::new(SimHashCacheConfig::default()),
-            event_feature_extractor: EventFeatureExtractor::new(
-                FeatureDictionaries::default_minimal(),
+            story_clusterer: StoryClusterer::new(&cfg.strategy.stories),
+            story_markets: LruCache::new(
+                NonZeroUsize::new(cfg.strategy.stories.capacity).unwrap_or(NonZeroUsize::MIN),
             ),
-            market_index: MarketIndex::new().expect("Failed to initialize MarketIndex"),
-            hard_filterer: HardFilterer::new(),
+            source_tracker: SourceTracker::new(&cfg.strategy.sources),
+            event_feature_extractor: EventFeatureExtractor::new(dictionaries, time_parser),
+            entity_dicts,
+            market_index,
+            news_embedder,
+            index_tx,
+            retrieval_cfg: cfg.strategy.retrieval.clone(),
+            numeric_check: cfg.strategy.numeric_check.clone(),
+            hard_filterer: HardFilterer::new()
+                .with_max_resolution_lag(cfg.strategy.time_expressions.max_resolution_lag),
+            reranker: Reranker::new(&cfg.strategy.rerank),
             kelly_sizer: KellySizer::default(),
             market_data_cache: HashMap::new(),
             analyst: MarketAnalyst::new(
                 LlmClient::new(cfg.llm.clone()),
+                PromptLibrary::load(&cfg.prompts).expect("Failed to load prompt templates"),
                 db.clone(),
                 cfg.strategy.top_candidates,
             ),
@@ -157,9 +214,10 @@ impl StrategyActor {
 
     /// Core pipeline for a fresh news item:
     /// 1) Check for halt
+    /// 2) Staleness gate on the published time
     /// 2) Exact Dedup based on raw news event
     /// 2) Normalize + tokenize
-    /// 3) Semantic dedup
+    /// 3) Story clustering (repeats dropped)
     /// 4) Entity & date extraction
     /// 5) Candidate generation using lexical and semantic lookup
     /// 6) Hard filters
@@ -179,6 +237,19 @@ impl StrategyActor {
 
         metrics::counter!("strategy_news_processed_total").increment(1);
 
+        // 2. Stale news: the market has likely moved already
+        let first_seen = Utc::now();
+        if let Err(reason) = self.staleness.check(raw_news, first_seen) {
+            info!(
+                "Stale news ({}, published {:?}) skipped.",
+                reason.as_str(),
+                raw_news.published
+            );
+            metrics::counter!("strategy_news_stale_total", "source" => raw_news.feed.clone(), "reason" => reason.as_str())
+                .increment(1);
+            return order;
+        }
+
         // 2. Cheap exact dedup to eliminate trivial duplicates
         if self.detector.is_duplicate(raw_news) {
             // Count exact duplicates
@@ -186,8 +257,37 @@ impl StrategyActor {
             return order; // Return empty if dup
         }
 
+        // 2. Tokenize (detects the language, which is persisted with the event)
+        let tokenized_news = TokenizedNews::from_raw(raw_news.clone(), &self.tokenization_config);
+        metrics::counter!("strategy_news_language_total", "language" => tokenized_news.language.code())
+            .increment(1);
+
+        // 3. Story clustering (SimHash + embedding): repeats are dropped, while
+        // corroborations and updates go on with the story as context
+        let now = Utc::now();
+        let sim_hash = self.story_clusterer.sim_hash(&tokenized_news.tokens);
+        let story = self.assign_story(&tokenized_news, sim_hash, now).await;
+        metrics::counter!("strategy_story_assignments_total", "status" => story.status.as_str())
+            .increment(1);
+        metrics::gauge!("strategy_stories_active").set(self.story_clusterer.len() as f64);
+        self.source_tracker.record_report(&raw_news.feed, &story);
+
         // Persist Event (fire and forget / log error)
-        let event_db_id = match self.db.save_event(raw_news).await {
+        let event_db_id = match self
+            .db
+            .save_event(
+                raw_news,
+                &EventMeta {
+                    language: tokenized_news.language.code(),
+                    cluster_id: story.cluster_id,
+                    story_status: story.status.as_str(),
+                    exact_hash: ExactDuplicateDetector::content_hash(raw_news),
+                    sim_hash,
+                    first_seen,
+                },
+            )
+            .await
+        {
             Ok(id) => Some(id),
             Err(e) => {
                 error!("Failed to save event to DB: {:#}", e);
@@ -195,27 +295,30 @@ impl StrategyActor {
             }
         };
 
-        info!("New event {:?} — continue pipeline.", event_db_id);
-
-        // 2. Tokenize
-        let tokenized_news = TokenizedNews::from_raw(raw_news.clone(), &self.tokenization_config);
-
-        // 3. Semantic dedup (SimHash) to eliminate rewritten versions
-        let h = self.sim_hash_cache.sim_hash(&tokenized_news.tokens);
-        if self.sim_hash_cache.is_near_duplicate(h) {
-            info!("Near-duplicate news skipped (SimHash).");
+        if story.status == StoryStatus::Repeat {
+            info!("Repeat of story {} skipped.", story.cluster_id);
             metrics::counter!("strategy_duplicates_total", "type" => "simhash").increment(1);
             return order;
         }
-        self.sim_hash_cache.insert(h);
+        info!(
+            "New event {:?} ({} of story {}) — continue pipeline.",
+            event_db_id,
+            story.status.as_str(),
+            story.cluster_id
+        );
 
         // 4. lexical and semantic using hard coded rules => semantic is generated from lexical
-        let now = Utc::now();
         let feat = self.event_feature_extractor.extract(&tokenized_news, now); // Lexical layer (layers, numbers, time window)
+        for entity in &feat.entities {
+            metrics::counter!("strategy_entities_extracted_total", "type" => entity.entity_type.as_str())
+                .increment(1);
+        }
 
         // 5. Candidate generation with Hybrid Search (BM25 + Semantic)
-        let raw_candidates =
-            self.retrieve_candidates(tokenized_news.tokens.as_slice(), &raw_news.title);
+        let query = self
+            .market_query(tokenized_news.tokens.as_slice(), &raw_news.title, now)
+            .language(tokenized_news.language);
+        let raw_candidates = self.retrieve_candidates(&query).await;
 
         metrics::counter!("strategy_candidates_found_total").increment(raw_candidates.len() as u64);
 
@@ -247,6 +350,25 @@ impl StrategyActor {
         let filtered_count = initial_count - filtered_candidates.len();
         metrics::counter!("strategy_candidates_filtered_total").increment(filtered_count as u64);
 
+        // 6a. Markets already ordered for this story were priced with its earlier reports
+        let filtered_candidates = self.skip_story_markets(story.cluster_id, filtered_candidates);
+
+        // 6b. Rerank by (news, market) relevance so the top N sent to the LLM are the best matches
+        let rerank_start = std::time::Instant::now();
+        let filtered_candidates = self
+            .reranker
+            .rerank(&raw_news.title, filtered_candidates)
+            .await;
+        metrics::histogram!("strategy_rerank_duration_seconds")
+            .record(rerank_start.elapsed().as_secs_f64());
+        if filtered_candidates.is_empty() {
+            info!(
+                "No candidates above relevance threshold for news: ({}). Skipping.",
+                raw_news.title
+            );
+            return order;
+        }
+
         // Take top N candidates for ensure_market_data optimization
         let top_candidates_for_data = filtered_candidates
             .iter()
@@ -259,7 +381,7 @@ impl StrategyActor {
             top_candidates_for_data.len(),
             top_candidates_for_data
                 .iter()
-                .map(|c| &c.market_id)
+                .map(|c| c.summary())
                 .collect::<Vec<_>>()
         );
 
@@ -274,22 +396,27 @@ impl StrategyActor {
         );
         self.ensure_market_data(&top_candidates_for_data).await;
 
+        // 6c. Deterministic check: thresholds settled by figures in the news skip the LLM
+        let (settled, unsettled) = self.settle_numeric_thresholds(&feat, top_candidates_for_data);
+
         // 7. Analyst (LLM Scoring)
         let analyst_start = std::time::Instant::now();
-        // Pass the candidates to analyst. Analyst will also limit to top_candidates internally,
-        // but since we already filtered for data fetching efficiency, we pass what we have.
-        // Analyst expects Vec<RawCandidate>.
-        let edged_candidates = self
+        let source = self.source_tracker.profile(&raw_news.feed);
+        // Only the top N have market data; the settled ones are already decided.
+        let mut edged_candidates = self
             .analyst
             .analyze_candidates(
                 raw_news,
-                filtered_candidates, // Pass all filtered, Analyst handles top_N logic
+                &story,
+                &source,
+                unsettled,
                 &self.market_data_cache,
                 event_db_id,
             )
             .await;
         metrics::histogram!("strategy_analyst_duration_seconds")
             .record(analyst_start.elapsed().as_secs_f64());
+        edged_candidates.extend(settled);
 
         // 8. Kelly Sizing
         let sized_decisions = self.kelly_sizer.size_positions(edged_candidates);
@@ -308,37 +435,226 @@ impl StrategyActor {
             .await;
 
         metrics::counter!("strategy_orders_generated_total").increment(orders.len() as u64);
+        if !orders.is_empty() {
+            self.story_markets
+                .get_or_insert_mut(story.cluster_id, HashSet::new)
+                .extend(orders.iter().map(|o| o.market_id.clone()));
+        }
         metrics::histogram!("strategy_processing_duration_seconds")
             .record(start.elapsed().as_secs_f64());
         orders
     }
 
-    fn retrieve_candidates(&mut self, tokens: &[String], raw_text: &str) -> Vec<RawCandidate> {
-        let mut candidates = HashMap::new();
-
-        // 1. BM25 Search
-        if let Ok(results) = self.market_index.search(tokens, 50) {
-            for c in results {
-                candidates.insert(c.market_id.clone(), c);
+    /// Cluster the news into a story. The embedding is computed on a blocking
+    /// thread and is best effort: without it the item is matched on SimHash alone.
+    async fn assign_story(
+        &mut self,
+        tokenized_news: &TokenizedNews,
+        hash: u64,
+        now: chrono::DateTime<Utc>,
+    ) -> StoryAssignment {
+        let raw = &tokenized_news.raw;
+        let embedder = self.news_embedder.clone();
+        let text = format!("{} {}", raw.title, raw.description);
+        let embedding = match tokio::task::spawn_blocking(move || embedder.embed_one(&text)).await {
+            Ok(Ok(embedding)) => Some(embedding),
+            Ok(Err(e)) => {
+                warn!("Failed to embed news for clustering: {:#}", e);
+                None
+            }
+            Err(e) => {
+                error!("News embedding task panicked: {}", e);
+                None
             }
-        } else {
-            warn!("BM25 search failed");
+        };
+        self.story_clusterer.assign(
+            &raw.feed,
+            hash,
+            embedding.as_deref(),
+            &tokenized_news.stemmed_tokens,
+            now,
+        )
+    }
+
+    /// Drop the candidates already ordered for the story.
+    fn skip_story_markets(
+        &mut self,
+        cluster_id: i64,
+        candidates: Vec<RawCandidate>,
+    ) -> Vec<RawCandidate> {
+        let Some(traded) = self.story_markets.get(&cluster_id) else {
+            return candidates;
+        };
+        let before = candidates.len();
+        let candidates: Vec<RawCandidate> = candidates
+            .into_iter()
+            .filter(|c| !traded.contains(&c.market_id))
+            .collect();
+        if candidates.len() < before {
+            info!(
+                "Skipped {} market(s) already traded for story {}",
+                before - candidates.len(),
+                cluster_id
+            );
+            metrics::counter!("strategy_story_markets_skipped_total")
+                .increment((before - candidates.len()) as u64);
         }
+        candidates
+    }
 
-        // 2. Semantic Search
-        if let Ok(results) = self.market_index.search_semantic(raw_text, 50) {
-            for c in results {
-                // If exists, we could merge scores or keep the one with higher score.
-                // For now, just insert if not present (union).
-                // Or maybe we want to boost score if found in both?
-                // Let's just add unique ones.
-                candidates.entry(c.market_id.clone()).or_insert(c);
+    /// Reload the outcomes of signals whose markets have resolved since.
+    async fn refresh_source_outcomes(&mut self) {
+        let since = Utc::now()
+            - chrono::Duration::from_std(self.source_tracker.window())
+                .unwrap_or(chrono::Duration::MAX);
+        match self.db.load_resolved_signals(since).await {
+            Ok(signals) => {
+                info!("Loaded {} resolved signals for source stats", signals.len());
+                self.source_tracker.set_outcomes(signals);
             }
-        } else {
-            warn!("Semantic search failed");
+            Err(e) => {
+                error!("Failed to load resolved signals for source stats: {:#}", e);
+            }
+        }
+    }
+
+    /// Split off the candidates whose threshold question ("will CPI exceed 3%?")
+    /// is confirmed or contradicted by figures in the news about the market's
+    /// period, priced at the configured probability for the Yes outcome (or its
+    /// complement).
+    fn settle_numeric_thresholds(
+        &self,
+        feat: &EventFeatures,
+        candidates: Vec<RawCandidate>,
+    ) -> (Vec<EdgedCandidate>, Vec<RawCandidate>) {
+        if !self.numeric_check.enabled || feat.numbers.is_empty() {
+            return (Vec::new(), candidates);
+        }
+        let now = Utc::now();
+        let confidence =
+            Decimal::from_f64(self.numeric_check.probability).unwrap_or(Decimal::new(95, 2));
+
+        let mut settled = Vec::new();
+        let mut unsettled = Vec::new();
+        for candidate in candidates {
+            let snap = self.market_data_cache.get(&candidate.market_id);
+            let yes_price = snap
+                .and_then(|s| s.tokens.as_ref())
+                .and_then(|tokens| {
+                    tokens
+                        .iter()
+                        .find(|t| t.outcome.eq_ignore_ascii_case("yes"))
+                })
+                .map(|t| t.price);
+            let question = snap
+                .map(|s| s.question.as_str())
+                .filter(|q| !q.is_empty())
+                .unwrap_or(&candidate.title);
+
+            let resolution = candidate
+                .resolution_date
+                .and_then(|ts| DateTime::from_timestamp(ts, 0));
+            let verdict = yes_price.and_then(|_| {
+                self.event_feature_extractor.settle_threshold(
+                    question,
+                    &feat.numbers,
+                    feat.time_window.as_ref(),
+                    resolution,
+                    now,
+                )
+            });
+            let (Some(verdict), Some(market_price)) = (verdict, yes_price) else {
+                unsettled.push(candidate);
+                continue;
+            };
+
+            metrics::counter!("strategy_numeric_checks_total", "verdict" => verdict.as_str())
+                .increment(1);
+            info!(
+                "Numeric check: news {} \"{}\" ({})",
+                verdict.as_str(),
+                question,
+                candidate.market_id
+            );
+            let probability = match verdict {
+                Verdict::Confirms => confidence,
+                Verdict::Contradicts => Decimal::ONE - confidence,
+            };
+            settled.push(EdgedCandidate {
+                candidate,
+                score: confidence,
+                probability,
+                market_price,
+                edge: probability - market_price,
+            });
+        }
+        (settled, unsettled)
+    }
+
+    /// News text plus the configured retrieval filters (`strategy.retrieval.filters`).
+    fn market_query(
+        &self,
+        tokens: &[String],
+        raw_text: &str,
+        now: chrono::DateTime<Utc>,
+    ) -> MarketQuery {
+        let cfg = &self.retrieval_cfg.filters;
+        let horizon =
+            |d: Option<std::time::Duration>| d.map(|d| now.timestamp() + d.as_secs() as i64);
+
+        let mut query = MarketQuery::new(tokens, raw_text)
+            .include_tags(&cfg.include_categories)
+            .exclude_tags(&cfg.exclude_categories)
+            .resolves_between(
+                horizon(cfg.min_time_to_resolution),
+                horizon(cfg.max_time_to_resolution),
+            );
+        if let Some(min) = cfg.min_liquidity {
+            query = query.min_liquidity(min);
+        }
+        if let Some(min) = cfg.min_volume {
+            query = query.min_volume(min);
+        }
+        if cfg.active_only {
+            query = query.active_only();
+        }
+        if cfg.exclude_held {
+            query = query.exclude_markets(
+                self.portfolio
+                    .positions
+                    .values()
+                    .filter(|p| p.quantity > Decimal::ZERO)
+                    .map(|p| p.market_id.clone()),
+            );
         }
+        query
+    }
 
-        candidates.into_values().collect()
+    /// BM25 + Semantic, fused so that agreement between the two boosts a
+    /// market. Runs on a blocking thread: the index may be locked by the
+    /// indexer and the query is embedded by the model.
+    async fn retrieve_candidates(&self, query: &MarketQuery) -> Vec<RawCandidate> {
+        let index = self.market_index.clone();
+        let query = query.clone();
+        let cfg = self.retrieval_cfg.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let index = index
+                .lock()
+                .map_err(|_| anyhow::anyhow!("MarketIndex lock poisoned"))?;
+            index.search_hybrid(&query, &cfg)
+        })
+        .await;
+        match result {
+            Ok(Ok(candidates)) => candidates,
+            Ok(Err(e)) => {
+                warn!("Hybrid search failed: {:#}", e);
+                Vec::new()
+            }
+            Err(e) => {
+                error!("Hybrid search task panicked: {}", e);
+                Vec::new()
+            }
+        }
     }
 
     async fn ensure_market_data(&mut self, candidates: &[RawCandidate]) {
@@ -550,37 +866,19 @@ impl StrategyActor {
 
                 if market.closed {
                     // Remove from index if closed
-                    if self.market_index.contains(&market.id) {
-                        if let Err(e) = self.market_index.delete_market(&market.id) {
-                            error!("Failed to delete closed market {}: {}", market.id, e);
-                        } else {
-                            info!("MarketIndex: Removed closed market {}", market.id);
-                        }
-                    }
+                    self.send_index_op(IndexOp::Delete(market.id.clone()));
                 } else {
-                    let question = market
-                        .question
-                        .as_deref()
-                        .or(event.title.as_deref())
-                        .unwrap_or("");
-                    let description = market
-                        .description
-                        .as_deref()
-                        .or(event.description.as_deref())
-                        .unwrap_or("");
-
-                    if !question.is_empty() {
-                        if let Err(e) = self.market_index.add_market(
-                            &market.id,
-                            question,
-                            description,
-                            "",
-                            None,
-                        ) {
-                            error!("Failed to index market {}: {}", market.id, e);
-                        } else {
-                            info!("MarketIndex: Added/Updated market {:#}", market.id);
-                        }
+                    // Single-market events often only carry text on the event
+                    let mut doc = MarketDoc::from_market(market);
+                    if doc.title.is_empty() {
+                        doc.title = event.title.clone().unwrap_or_default();
+                    }
+                    if doc.description.is_empty() {
+                        doc.description = event.description.clone().unwrap_or_default();
+                    }
+
+                    if !doc.title.is_empty() {
+                        self.send_index_op(IndexOp::Upsert(doc));
                     }
                 }
             }
@@ -588,6 +886,12 @@ impl StrategyActor {
         None
     }
 
+    fn send_index_op(&self, op: IndexOp) {
+        if self.index_tx.send(op).is_err() {
+            error!("MarketIndexer is not running; dropping index update");
+        }
+    }
+
     async fn decide_from_executions(&mut self, execution: &Execution) -> Option<Order> {
         info!("StrategyActor received execution: {:?}", execution);
 
@@ -626,6 +930,29 @@ impl StrategyActor {
         None
     }
 
+    fn reload_entity_dictionaries(&mut self) {
+        match self.entity_dicts.poll() {
+            Some(Ok(dictionaries)) => {
+                info!(
+                    "Reloaded entity dictionaries ({} aliases)",
+                    dictionaries.entities.len()
+                );
+                metrics::counter!("strategy_entity_dict_reloads_total", "status" => "success")
+                    .increment(1);
+                self.event_feature_extractor.set_dictionaries(dictionaries);
+            }
+            Some(Err(e)) => {
+                error!(
+                    "Entity dictionary reload failed, keeping the previous one: {:#}",
+                    e
+                );
+                metrics::counter!("strategy_entity_dict_reloads_total", "status" => "error")
+                    .increment(1);
+            }
+            None => {}
+        }
+    }
+
     fn reconcile_positions(&mut self, snap: &crate::core::types::PositionSnapshot) {
         info!(
             "Reconciling portfolio with {} external positions",
@@ -696,16 +1023,78 @@ impl Actor for StrategyActor {
             }
         }
 
-        // Hydrate Duplicate Detector
-        match self.db.load_recent_events(10000).await {
+        // Hydrate LLM spend so budget caps survive restarts
+        self.analyst.hydrate_spend().await;
+
+        // Hydrate staleness watermarks so a restart does not replay feed backlogs
+        match self.db.load_source_watermarks().await {
+            Ok(watermarks) => {
+                info!(
+                    "Hydrated staleness watermarks for {} sources",
+                    watermarks.len()
+                );
+                self.staleness.hydrate(watermarks);
+            }
+            Err(e) => {
+                error!("Failed to load staleness watermarks: {:#}", e);
+            }
+        }
+
+        // Hydrate Duplicate Detector & stories from the events still within their TTL
+        let window = self.detector.ttl().max(
+            chrono::Duration::from_std(self.story_clusterer.ttl()).unwrap_or(chrono::Duration::MAX),
+        );
+        match self.db.load_events_since(Utc::now() - window).await {
             Ok(events) => {
                 let count = events.len();
-                self.detector.hydrate(events.clone());
-                self.sim_hash_cache
-                    .hydrate(events, &self.tokenization_config);
+                for event in events {
+                    // Rows written before the hashes were persisted are re-hashed
+                    let news = RawNews {
+                        title: event.title,
+                        description: event.description,
+                        url: String::new(),
+                        feed: event.source,
+                        published: None,
+                        labels: vec![],
+                        guid: None,
+                        author: None,
+                        updated: None,
+                        body: None,
+                    };
+                    let exact_hash = event
+                        .exact_hash
+                        .unwrap_or_else(|| ExactDuplicateDetector::content_hash(&news));
+                    let sim_hash = event.sim_hash.unwrap_or_else(|| {
+                        let tokenized =
+                            TokenizedNews::from_raw(news.clone(), &self.tokenization_config);
+                        self.story_clusterer.sim_hash(&tokenized.tokens)
+                    });
+
+                    self.detector
+                        .hydrate([(exact_hash, event.first_seen.timestamp())]);
+                    match event.cluster_id {
+                        Some(cluster_id) => self.story_clusterer.restore(
+                            cluster_id,
+                            &news.feed,
+                            sim_hash,
+                            event.first_seen,
+                        ),
+                        None => {
+                            self.story_clusterer.assign(
+                                &news.feed,
+                                sim_hash,
+                                None,
+                                &[],
+                                event.first_seen,
+                            );
+                        }
+                    }
+                }
                 info!(
-                    "Hydrated Deduplication Cache & SimHash with {} recent events",
-                    count
+                    "Hydrated Deduplication Cache & {} stories from {} events of the last {}h",
+                    self.story_clusterer.len(),
+                    count,
+                    window.num_hours()
                 );
             }
             Err(e) => {
@@ -713,11 +1102,26 @@ impl Actor for StrategyActor {
             }
         }
 
+        // Hydrate source stats; outcomes are loaded by the first refresh tick
+        let since = Utc::now()
+            - chrono::Duration::from_std(self.source_tracker.window())
+                .unwrap_or(chrono::Duration::MAX);
+        match self.db.load_source_reports(since).await {
+            Ok(reports) => {
+                info!("Hydrated source stats from {} reports", reports.len());
+                self.source_tracker.hydrate_reports(reports);
+            }
+            Err(e) => {
+                error!("Failed to load source reports: {:#}", e);
+            }
+        }
+
         // Hydrate Market State Cache & Market Index
         match self.db.load_markets().await {
             Ok(markets) => {
                 let count = markets.len();
                 let mut indexed_count = 0;
+                let mut active_ids = HashSet::new();
                 for market in markets {
                     // 1. Hydrate Market State Cache
                     let mut hasher = std::collections::hash_map::DefaultHasher::new();
@@ -727,26 +1131,22 @@ impl Actor for StrategyActor {
 
                     // 2. Hydrate Market Index (if active/open)
                     if !market.closed && market.active {
-                        let question = market.question.as_deref().unwrap_or("");
-                        let description = market.description.as_deref().unwrap_or("");
-
-                        if !question.is_empty() {
-                            if let Err(e) = self.market_index.add_market(
-                                &market.id,
-                                question,
-                                description,
-                                "",
-                                None,
-                            ) {
-                                error!("Failed to index market {} from DB: {:#}", market.id, e);
-                            } else {
-                                indexed_count += 1;
-                            }
+                        active_ids.insert(market.id.clone());
+                        let doc = MarketDoc::from_market(&market);
+                        if !doc.title.is_empty() {
+                            self.send_index_op(IndexOp::Upsert(doc));
+                            indexed_count += 1;
                         }
                     }
                 }
                 info!("Hydrated market_state_cache with {} markets", count);
-                info!("Hydrated MarketIndex with {} active markets", indexed_count);
+                info!(
+                    "Queued {} active markets for MarketIndex hydration",
+                    indexed_count
+                );
+
+                // A persisted index may still hold markets that closed while we were down
+                self.send_index_op(IndexOp::Retain(active_ids));
             }
             Err(e) => {
                 error!("Failed to load markets for hydration: {:#}", e);
@@ -762,6 +1162,9 @@ impl Actor for StrategyActor {
         let mut status_rx = self.bus.system_status.subscribe();
         let mut snapshot_rx = self.bus.positions_snapshot.subscribe();
         let mut polling_interval = tokio::time::interval(std::time::Duration::from_secs(5));
+        let mut dict_reload_interval = tokio::time::interval(self.entity_dicts.interval);
+        let mut source_refresh_interval =
+            tokio::time::interval(self.source_tracker.refresh_interval());
 
         loop {
             tokio::select! {
@@ -787,6 +1190,16 @@ impl Actor for StrategyActor {
                     }
                 }
 
+                // Entity dictionary hot reload
+                _ = dict_reload_interval.tick() => {
+                    self.reload_entity_dictionaries();
+                }
+
+                // Signal outcomes for source stats, as markets resolve
+                _ = source_refresh_interval.tick() => {
+                    self.refresh_source_outcomes().await;
+                }
+
                 // Graceful shutdown signal
                 _ = self.shutdown.cancelled() => {
                     info!("StrategyActor: shutdown requested");
@@ -968,7 +1381,6 @@ mod tests {
     // use crate::config::config::CalibrationCfg;
     use crate::core::types::MarketToken;
     use crate::strategy::exact_duplicate_detector::ExactDuplicateDetectorConfig;
-    use crate::strategy::sim_hash_cache::SimHashCacheConfig;
     use crate::strategy::types::{EdgedCandidate, RawCandidate, SizedDecision, TradeSide};
 
     #[tokio::test]
@@ -984,22 +1396,34 @@ mod tests {
             Err(_) => return, // Skip if no DB
         };
 
+        let market_index = MarketIndex::new().unwrap();
         let mut actor = StrategyActor {
             bus: Bus::new(),
             shutdown: CancellationToken::new(),
+            staleness: StalenessGate::new(&Default::default()),
             detector: ExactDuplicateDetector::new(ExactDuplicateDetectorConfig::default()),
-            sim_hash_cache: SimHashCache::new(SimHashCacheConfig::default()),
-            event_feature_extractor: EventFeatureExtractor::new(
-                FeatureDictionaries::default_minimal(),
-            ),
-            market_index: MarketIndex::new().unwrap(),
+            story_clusterer: StoryClusterer::new(&Default::default()),
+            story_markets: LruCache::new(NonZeroUsize::MIN),
+            source_tracker: SourceTracker::new(&Default::default()),
+            event_feature_extractor: EventFeatureExtractor::with_default_dicts(),
+            entity_dicts: DictionaryReloader::new(&Default::default()),
+            news_embedder: market_index.news_embedder(),
+            market_index: Arc::new(Mutex::new(market_index)),
+            index_tx: tokio::sync::mpsc::unbounded_channel().0,
+            retrieval_cfg: RetrievalCfg::default(),
+            numeric_check: NumericCheckCfg::default(),
             hard_filterer: HardFilterer::new(),
+            reranker: Reranker::new(&crate::config::config::RerankCfg {
+                enabled: false,
+                ..Default::default()
+            }),
             // scorer: Scorer::new(),
             // calibrator: ProbabilityCalibrator::new(CalibrationCfg::default()),
             kelly_sizer: KellySizer::default(),
             market_data_cache: HashMap::new(),
             analyst: MarketAnalyst::new(
                 LlmClient::new(crate::config::config::LlmCfg::default()),
+                PromptLibrary::builtin(),
                 db.clone(),
                 5,
             ),
@@ -1,86 +1,194 @@
 use crate::core::types::{MarketDataSnap, RawNews};
 use crate::llm::LlmClient;
+use crate::llm::budget::BudgetState;
+use crate::llm::prompts::{PromptLibrary, PromptVars};
 use crate::persistence::database::Database;
+use crate::strategy::source_stats::SourceProfile;
+use crate::strategy::story_clusterer::StoryAssignment;
 use crate::strategy::types::{EdgedCandidate, RawCandidate};
+use chrono::{Datelike, TimeZone, Utc};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
 use std::collections::HashMap;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+/// No signal is priced as a certainty, however reliable its source.
+const MAX_SIGNAL_PROBABILITY: f64 = 0.99;
+
+/// Probability of the signal's outcome: LLM confidence 0..1 maps to 0.5..1,
+/// and the source weight scales the distance from 0.5 (an unreliable source
+/// is shrunk towards a coin flip), capped below 1.
+fn signal_probability(confidence: f64, source_weight: f64) -> f64 {
+    let distance = confidence.clamp(0.0, 1.0) * 0.5 * source_weight.max(0.0);
+    (0.5 + distance).min(MAX_SIGNAL_PROBABILITY)
+}
 
 pub struct MarketAnalyst {
     llm_client: LlmClient,
+    prompts: PromptLibrary,
     db: Database,
     top_candidates: usize,
 }
 
 impl MarketAnalyst {
-    pub fn new(llm_client: LlmClient, db: Database, top_candidates: usize) -> Self {
+    pub fn new(
+        llm_client: LlmClient,
+        prompts: PromptLibrary,
+        db: Database,
+        top_candidates: usize,
+    ) -> Self {
         Self {
             llm_client,
+            prompts,
             db,
             top_candidates,
         }
     }
 
+    /// Restore today's and this month's LLM spend from persisted usage so that
+    /// budget caps survive restarts.
+    pub async fn hydrate_spend(&self) {
+        let now = Utc::now();
+        let day_start = Utc
+            .with_ymd_and_hms(now.year(), now.month(), now.day(), 0, 0, 0)
+            .unwrap();
+        let month_start = Utc
+            .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+            .unwrap();
+
+        match (
+            self.db.load_llm_spend_since(day_start).await,
+            self.db.load_llm_spend_since(month_start).await,
+        ) {
+            (Ok(daily), Ok(monthly)) => {
+                info!(
+                    "Hydrated LLM spend: ${:.4} today, ${:.4} this month",
+                    daily, monthly
+                );
+                self.llm_client.seed_spend(daily, monthly);
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                error!("Failed to load LLM spend for budget hydration: {:#}", e);
+            }
+        }
+    }
+
     pub async fn analyze_candidates(
         &self,
         raw_news: &RawNews,
+        story: &StoryAssignment,
+        source: &SourceProfile,
         candidates: Vec<RawCandidate>,
         market_data_cache: &HashMap<String, MarketDataSnap>,
         event_db_id: Option<i64>,
     ) -> Vec<EdgedCandidate> {
         let mut edged_candidates = Vec::new();
 
-        // Take top N candidates for LLM analysis to save costs
-        let top_candidates = candidates
-            .into_iter()
-            .take(self.top_candidates)
-            .collect::<Vec<_>>();
+        // Take top N candidates for LLM analysis to save costs.
+        // The budget may shrink N (degraded) or skip analysis entirely (exhausted).
+        let limit = self.llm_client.candidate_limit(self.top_candidates);
+        if limit == 0 {
+            warn!(
+                "LLM budget exhausted, skipping analysis for: {}",
+                raw_news.title
+            );
+            metrics::counter!("strategy_analyst_budget_skips_total").increment(1);
+            return edged_candidates;
+        }
+        if limit < self.top_candidates {
+            metrics::counter!("strategy_analyst_budget_degraded_total").increment(1);
+        }
+        let top_candidates = candidates.into_iter().take(limit).collect::<Vec<_>>();
 
         // Note: ensure_market_data must be called by the caller (StrategyActor) before calling this,
         // because Analyst doesn't have access to the bus to request data.
 
         for candidate in top_candidates {
+            // Pause analysis while the provider is failing
+            if !self.llm_client.is_available() {
+                warn!("LLM circuit breaker open, pausing analysis");
+                metrics::counter!("strategy_analyst_breaker_skips_total").increment(1);
+                break;
+            }
+
+            // A cap may be hit mid-batch
+            if self.llm_client.budget_state() == BudgetState::Exhausted {
+                warn!("LLM budget exhausted mid-batch, stopping analysis");
+                metrics::counter!("strategy_analyst_budget_skips_total").increment(1);
+                break;
+            }
+
             // ... (market question retrieval)
-            let market_question = if let Some(snap) = market_data_cache.get(&candidate.market_id) {
-                snap.question.clone()
-            } else {
-                "Unknown Market Question".to_string()
+            let snap = market_data_cache.get(&candidate.market_id);
+            let market_question = match snap {
+                Some(snap) => snap.question.clone(),
+                None => "Unknown Market Question".to_string(),
             };
 
-            // Extract available outcomes
-            let outcomes = if let Some(snap) = market_data_cache.get(&candidate.market_id) {
-                if let Some(tokens) = &snap.tokens {
-                    tokens.iter().map(|t| t.outcome.clone()).collect()
-                } else {
-                    vec!["Yes".to_string(), "No".to_string()]
-                }
-            } else {
-                vec!["Yes".to_string(), "No".to_string()]
+            // Extract available outcomes and their current prices
+            let tokens = snap.and_then(|s| s.tokens.as_ref());
+            let outcomes = match tokens {
+                Some(tokens) => tokens.iter().map(|t| t.outcome.clone()).collect(),
+                None => vec!["Yes".to_string(), "No".to_string()],
+            };
+            let prices = tokens
+                .map(|tokens| {
+                    tokens
+                        .iter()
+                        .map(|t| (t.outcome.clone(), t.price))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let template = self.prompts.select(&candidate.tags);
+            let vars = PromptVars {
+                news: raw_news.title.clone(),
+                market: market_question,
+                outcomes,
+                prices,
+                date: Utc::now().date_naive(),
+                story: story.describe(),
+                source: source.summary.clone(),
+                article: raw_news
+                    .body
+                    .clone()
+                    .unwrap_or_else(|| raw_news.description.clone()),
             };
 
             // Call LLM
-            metrics::counter!("strategy_analyst_llm_calls_total").increment(1);
-            match self
-                .llm_client
-                .analyze(&raw_news.title, &market_question, &outcomes)
-                .await
-            {
-                Ok((signal, prompt)) => {
-                    info!("LLM Signal for {}: {:?}", candidate.market_id, signal);
+            metrics::counter!("strategy_analyst_llm_calls_total", "template" => template.id())
+                .increment(1);
+            let result = self.llm_client.analyze(template, &vars).await;
+            // Every billed attempt counts towards the caps after a restart,
+            // including unparseable replies and unlinked events
+            for record in self.llm_client.take_usage() {
+                if let Err(e) = self
+                    .db
+                    .save_llm_usage(event_db_id, &candidate.market_id, &record)
+                    .await
+                {
+                    error!("Failed to save LLM usage: {:#}", e);
+                }
+            }
+            match result {
+                Ok(analysis) => {
+                    let signal = &analysis.signal;
+                    info!(
+                        "LLM Signal for {}: {:?} (prompt {}, model {}, {} tokens, ${:.6})",
+                        candidate.market_id,
+                        signal,
+                        analysis.template_id,
+                        analysis.model,
+                        analysis.usage.total(),
+                        analysis.cost_usd
+                    );
                     metrics::counter!("strategy_analyst_signals_total", "sentiment" => signal.sentiment.clone()).increment(1);
 
                     // Persist Signal
                     if let Some(eid) = event_db_id {
                         if let Err(e) = self
                             .db
-                            .save_signal(
-                                eid,
-                                &candidate.market_id,
-                                &signal,
-                                &prompt,
-                                self.llm_client.model(),
-                            )
+                            .save_signal(eid, &candidate.market_id, &analysis)
                             .await
                         {
                             error!("Failed to save signal: {:#}", e);
@@ -89,12 +197,15 @@ impl MarketAnalyst {
 
                     // Convert signal to TradeSide and Probability
                     if signal.sentiment != "None"
-                        && outcomes
+                        && vars
+                            .outcomes
                             .iter()
                             .any(|o| o.eq_ignore_ascii_case(&signal.sentiment))
                     {
-                        let prob = Decimal::from_f64(0.5 + (signal.confidence * 0.5))
-                            .unwrap_or(Decimal::new(5, 1));
+                        // Signals from a reliable source count for more
+                        let prob =
+                            Decimal::from_f64(signal_probability(signal.confidence, source.weight))
+                                .unwrap_or(Decimal::new(5, 1));
 
                         // Get market price for the specific outcome
                         let market_price =
@@ -134,3 +245,19 @@ impl MarketAnalyst {
         edged_candidates
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_probability_stays_below_one() {
+        assert_eq!(signal_probability(0.6, 1.0), 0.8);
+        // An unreliable source is pulled towards 0.5
+        assert_eq!(signal_probability(0.6, 0.5), 0.65);
+        // A reliable one can't push a confident signal to certainty
+        assert_eq!(signal_probability(0.9, 1.5), MAX_SIGNAL_PROBABILITY);
+        assert_eq!(signal_probability(1.0, 1.0), MAX_SIGNAL_PROBABILITY);
+        assert_eq!(signal_probability(-0.2, 1.5), 0.5);
+    }
+}
@@ -0,0 +1,330 @@
+use anyhow::{Context, Result, ensure};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+const MAGIC: &[u8; 8] = b"PMEMB\0\0\x01";
+const OP_PUT: u8 = 1;
+const OP_DELETE: u8 = 0;
+/// Largest market ID and vector a record may hold. A length word past these
+/// is corruption, read like a torn tail rather than allocated.
+const MAX_ID_BYTES: usize = 1024;
+const MAX_DIM: usize = 4096;
+
+/// Stable content hash (first 8 bytes of SHA-256). `DefaultHasher` is not
+/// guaranteed stable across Rust releases, so it can't be persisted.
+pub fn text_hash(parts: &[&str]) -> u64 {
+    let mut hasher = Sha256::new();
+    for p in parts {
+        hasher.update((p.len() as u64).to_le_bytes());
+        hasher.update(p.as_bytes());
+    }
+    let digest = hasher.finalize();
+    u64::from_le_bytes(digest[..8].try_into().expect("sha256 digest is 32 bytes"))
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredEmbedding {
+    pub text_hash: u64,
+    pub vector: Vec<f32>,
+}
+
+/// Market embeddings keyed by market ID and text hash, optionally backed by an
+/// append-only log file. The log is replayed (last record wins) and compacted on open.
+pub struct EmbeddingStore {
+    entries: HashMap<String, StoredEmbedding>,
+    log: Option<BufWriter<File>>,
+}
+
+impl EmbeddingStore {
+    pub fn in_memory() -> Self {
+        Self {
+            entries: HashMap::new(),
+            log: None,
+        }
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        let (entries, records, clean) = if path.exists() {
+            replay(path).with_context(|| format!("reading embedding log {}", path.display()))?
+        } else {
+            (HashMap::new(), 0, false)
+        };
+
+        // Rewrite when the log is new, damaged, or mostly superseded records
+        if !clean || records > entries.len() * 2 {
+            compact(path, &entries)?;
+        }
+
+        let file = OpenOptions::new().append(true).open(path)?;
+        info!(
+            "Loaded {} market embeddings from {}",
+            entries.len(),
+            path.display()
+        );
+        Ok(Self {
+            entries,
+            log: Some(BufWriter::new(file)),
+        })
+    }
+
+    /// The stored vector, if it was computed from the same text.
+    pub fn get(&self, market_id: &str, text_hash: u64) -> Option<&Vec<f32>> {
+        self.entries
+            .get(market_id)
+            .filter(|e| e.text_hash == text_hash)
+            .map(|e| &e.vector)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &StoredEmbedding)> {
+        self.entries.iter()
+    }
+
+    pub fn put(&mut self, market_id: &str, text_hash: u64, vector: Vec<f32>) -> Result<()> {
+        if let Some(log) = self.log.as_mut() {
+            write_record(log, OP_PUT, market_id, text_hash, &vector)?;
+            log.flush()?;
+        }
+        self.entries
+            .insert(market_id.to_string(), StoredEmbedding { text_hash, vector });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, market_id: &str) -> Result<()> {
+        if self.entries.remove(market_id).is_some()
+            && let Some(log) = self.log.as_mut()
+        {
+            write_record(log, OP_DELETE, market_id, 0, &[])?;
+            log.flush()?;
+        }
+        Ok(())
+    }
+}
+
+fn write_record<W: Write>(
+    w: &mut W,
+    op: u8,
+    market_id: &str,
+    text_hash: u64,
+    vector: &[f32],
+) -> Result<()> {
+    ensure!(
+        market_id.len() <= MAX_ID_BYTES,
+        "market ID longer than {} bytes",
+        MAX_ID_BYTES
+    );
+    ensure!(
+        vector.len() <= MAX_DIM,
+        "embedding of {} dimensions, at most {} are stored",
+        vector.len(),
+        MAX_DIM
+    );
+    w.write_all(&[op])?;
+    w.write_all(&(market_id.len() as u32).to_le_bytes())?;
+    w.write_all(market_id.as_bytes())?;
+    w.write_all(&text_hash.to_le_bytes())?;
+    w.write_all(&(vector.len() as u32).to_le_bytes())?;
+    for x in vector {
+        w.write_all(&x.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Returns (entries, records read, whether the log ended cleanly).
+fn replay(path: &Path) -> Result<(HashMap<String, StoredEmbedding>, usize, bool)> {
+    let mut r = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 8];
+    if r.read_exact(&mut magic).is_err() || &magic != MAGIC {
+        warn!(
+            "Embedding log {} has an unknown format, starting empty",
+            path.display()
+        );
+        return Ok((HashMap::new(), 0, false));
+    }
+
+    let mut entries = HashMap::new();
+    let mut records = 0;
+    loop {
+        let mut op = [0u8; 1];
+        match r.read_exact(&mut op) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return Ok((entries, records, true));
+            }
+            Err(e) => return Err(e.into()),
+        }
+        // A crash mid-append leaves a partial record at the tail; drop it
+        let Ok((market_id, text_hash, vector)) = read_record_body(&mut r) else {
+            warn!(
+                "Truncated record at the end of {}, discarding it",
+                path.display()
+            );
+            return Ok((entries, records, false));
+        };
+        records += 1;
+        match op[0] {
+            OP_PUT => {
+                entries.insert(market_id, StoredEmbedding { text_hash, vector });
+            }
+            _ => {
+                entries.remove(&market_id);
+            }
+        }
+    }
+}
+
+fn read_record_body<R: Read>(r: &mut R) -> std::io::Result<(String, u64, Vec<f32>)> {
+    let mut u32_buf = [0u8; 4];
+    let mut u64_buf = [0u8; 8];
+    let bounded = |len: u32, max: usize, what: &str| {
+        let len = len as usize;
+        if len > max {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{} length {} exceeds {}", what, len, max),
+            ));
+        }
+        Ok(len)
+    };
+
+    r.read_exact(&mut u32_buf)?;
+    let id_len = bounded(u32::from_le_bytes(u32_buf), MAX_ID_BYTES, "market ID")?;
+    let mut id = vec![0u8; id_len];
+    r.read_exact(&mut id)?;
+    let market_id = String::from_utf8(id)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    r.read_exact(&mut u64_buf)?;
+    let text_hash = u64::from_le_bytes(u64_buf);
+
+    r.read_exact(&mut u32_buf)?;
+    let dim = bounded(u32::from_le_bytes(u32_buf), MAX_DIM, "vector")?;
+    let mut vector = Vec::with_capacity(dim);
+    for _ in 0..dim {
+        r.read_exact(&mut u32_buf)?;
+        vector.push(f32::from_le_bytes(u32_buf));
+    }
+    Ok((market_id, text_hash, vector))
+}
+
+/// Write a fresh log with one record per live entry and atomically replace the old one.
+fn compact(path: &Path, entries: &HashMap<String, StoredEmbedding>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp = PathBuf::from(path);
+    tmp.set_extension("tmp");
+
+    let mut w = BufWriter::new(File::create(&tmp)?);
+    w.write_all(MAGIC)?;
+    for (id, e) in entries {
+        write_record(&mut w, OP_PUT, id, e.text_hash, &e.vector)?;
+    }
+    w.into_inner()?.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("polymind-emb-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("embeddings.log")
+    }
+
+    #[test]
+    fn test_text_hash_stable() {
+        assert_eq!(text_hash(&["a", "b"]), text_hash(&["a", "b"]));
+        // Length-prefixed, so field boundaries matter
+        assert_ne!(text_hash(&["ab", ""]), text_hash(&["a", "b"]));
+    }
+
+    #[test]
+    fn test_store_roundtrip_and_compaction() -> Result<()> {
+        let path = temp_path("roundtrip");
+        {
+            let mut store = EmbeddingStore::open(&path)?;
+            store.put("m1", 1, vec![0.1, 0.2])?;
+            store.put("m2", 2, vec![0.3])?;
+            store.put("m1", 3, vec![0.5, 0.6])?; // update
+            store.put("m2", 2, vec![0.3])?;
+            store.put("m2", 2, vec![0.3])?;
+            store.remove("m2")?;
+        }
+
+        let store = EmbeddingStore::open(&path)?;
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.get("m1", 3), Some(&vec![0.5, 0.6]));
+        assert!(store.get("m1", 1).is_none()); // stale text hash
+        assert!(store.get("m2", 2).is_none());
+
+        // 6 records for 1 live entry triggered a compaction on open
+        let size = std::fs::metadata(&path)?.len() as usize;
+        assert_eq!(size, MAGIC.len() + 1 + 4 + 2 + 8 + 4 + 2 * 4);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncated_tail_is_dropped() -> Result<()> {
+        let path = temp_path("truncated");
+        {
+            let mut store = EmbeddingStore::open(&path)?;
+            store.put("m1", 1, vec![1.0, 2.0])?;
+            store.put("m2", 2, vec![3.0, 4.0])?;
+        }
+        let len = std::fs::metadata(&path)?.len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)?
+            .set_len(len - 3)?;
+
+        let store = EmbeddingStore::open(&path)?;
+        assert_eq!(store.len(), 1);
+        assert!(store.get("m1", 1).is_some());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_lengths_are_dropped() -> Result<()> {
+        for (name, corrupt_at) in [("corrupt-id", 1), ("corrupt-dim", 1 + 4 + 2 + 8)] {
+            let path = temp_path(name);
+            {
+                let mut store = EmbeddingStore::open(&path)?;
+                store.put("m1", 1, vec![1.0, 2.0])?;
+            }
+            let mut bytes = std::fs::read(&path)?;
+            let clean_len = bytes.len();
+            let mut record = Vec::new();
+            write_record(&mut record, OP_PUT, "m2", 2, &[3.0, 4.0])?;
+            // A length word of ~4 GiB must not be allocated
+            record[corrupt_at..corrupt_at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+            bytes.extend_from_slice(&record);
+            std::fs::write(&path, &bytes)?;
+
+            let store = EmbeddingStore::open(&path)?;
+            assert_eq!(store.len(), 1, "{name}");
+            assert!(store.get("m1", 1).is_some());
+            // Rewritten without the corrupt record
+            assert_eq!(std::fs::metadata(&path)?.len() as usize, clean_len);
+
+            std::fs::remove_dir_all(path.parent().unwrap()).ok();
+        }
+        assert!(write_record(&mut Vec::new(), OP_PUT, "m", 1, &vec![0.0; MAX_DIM + 1]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use crate::config::config::EntityDictCfg;
+use crate::strategy::event_features::{EntityType, FeatureDictionaries};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+/// On-disk dictionary (YAML or JSON, by file extension):
+///
+/// ```yaml
+/// entities:
+///   - name: "Fed"
+///     type: organization
+///     aliases: ["federal reserve", "fomc"]
+/// ```
+///
+/// The name is always an alias of itself.
+#[derive(Debug, Deserialize)]
+struct DictionaryFile {
+    #[serde(default)]
+    entities: Vec<EntityEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EntityEntry {
+    name: String,
+    #[serde(default, rename = "type")]
+    entity_type: EntityType,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+/// Merge the given files in order; on a duplicate alias, the later file wins.
+pub fn load_dictionaries(files: &[PathBuf]) -> Result<FeatureDictionaries> {
+    let mut dict = FeatureDictionaries::default();
+    for path in files {
+        let file = read_file(path).with_context(|| format!("loading {}", path.display()))?;
+        for entry in file.entities {
+            let name = entry.name.trim();
+            anyhow::ensure!(
+                !name.is_empty(),
+                "{}: entity with an empty name",
+                path.display()
+            );
+            let aliases: Vec<&str> = std::iter::once(name)
+                .chain(entry.aliases.iter().map(|a| a.as_str()))
+                .filter(|a| !a.trim().is_empty())
+                .collect();
+            for alias in &aliases {
+                if let Some(existing) = dict.entities.get(&alias.trim().to_lowercase())
+                    && existing.label != name
+                {
+                    warn!(
+                        "{}: alias '{}' of {} overrides {}",
+                        path.display(),
+                        alias,
+                        name,
+                        existing.label
+                    );
+                }
+            }
+            dict.insert(name, entry.entity_type, &aliases);
+        }
+    }
+    Ok(dict)
+}
+
+fn read_file(path: &Path) -> Result<DictionaryFile> {
+    let format = match path.extension().and_then(|e| e.to_str()) {
+        Some("yml" | "yaml") => config::FileFormat::Yaml,
+        Some("json") => config::FileFormat::Json,
+        other => anyhow::bail!("unsupported dictionary format {:?}", other),
+    };
+    let text = std::fs::read_to_string(path)?;
+    let parsed = config::Config::builder()
+        .add_source(config::File::from_str(&text, format))
+        .build()?;
+    Ok(parsed.try_deserialize()?)
+}
+
+/// Tracks the configured dictionary files and reloads them when any changes.
+/// With no files configured, the built-in minimal dictionary is used.
+pub struct DictionaryReloader {
+    files: Vec<PathBuf>,
+    stamps: Vec<Option<SystemTime>>,
+    /// How often the owner should `poll`
+    pub interval: Duration,
+}
+
+impl DictionaryReloader {
+    pub fn new(cfg: &EntityDictCfg) -> Self {
+        let files: Vec<PathBuf> = cfg.files.iter().map(PathBuf::from).collect();
+        Self {
+            stamps: vec![None; files.len()],
+            files,
+            interval: cfg.reload_interval,
+        }
+    }
+
+    /// Initial load. Unlike reloads, errors here are fatal to the caller.
+    pub fn load(&mut self) -> Result<FeatureDictionaries> {
+        if self.files.is_empty() {
+            return Ok(FeatureDictionaries::default_minimal());
+        }
+        self.stamps = self.current_stamps();
+        let dict = load_dictionaries(&self.files)?;
+        info!(
+            "Loaded {} entity aliases from {} dictionary files",
+            dict.entities.len(),
+            self.files.len()
+        );
+        Ok(dict)
+    }
+
+    /// Reloaded dictionaries if any file changed since the last load. A failed
+    /// reload is retried on the next change only.
+    pub fn poll(&mut self) -> Option<Result<FeatureDictionaries>> {
+        if self.files.is_empty() {
+            return None;
+        }
+        let stamps = self.current_stamps();
+        if stamps == self.stamps {
+            return None;
+        }
+        self.stamps = stamps;
+        Some(load_dictionaries(&self.files))
+    }
+
+    fn current_stamps(&self) -> Vec<Option<SystemTime>> {
+        self.files
+            .iter()
+            .map(|f| std::fs::metadata(f).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_and_reload_dictionaries() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("polymind-dicts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let yaml = dir.join("people.yml");
+        let json = dir.join("crypto.json");
+        std::fs::write(
+            &yaml,
+            "entities:\n  - name: \"Jerome Powell\"\n    type: person\n    aliases: [\"powell\", \"Fed Chair\"]\n",
+        )?;
+        std::fs::write(
+            &json,
+            r#"{"entities": [{"name": "BTC", "type": "cryptoAsset", "aliases": ["bitcoin"]}]}"#,
+        )?;
+
+        let cfg = EntityDictCfg {
+            files: vec![
+                yaml.to_string_lossy().to_string(),
+                json.to_string_lossy().to_string(),
+            ],
+            ..Default::default()
+        };
+        let mut reloader = DictionaryReloader::new(&cfg);
+        let dict = reloader.load()?;
+        assert_eq!(dict.entities["fed chair"].label, "Jerome Powell");
+        assert_eq!(
+            dict.entities["jerome powell"].entity_type,
+            EntityType::Person
+        );
+        assert_eq!(
+            dict.entities["bitcoin"].entity_type,
+            EntityType::CryptoAsset
+        );
+        assert!(reloader.poll().is_none());
+
+        // Modify a file: the next poll reloads it
+        std::fs::write(
+            &json,
+            r#"{"entities": [{"name": "ETH", "type": "cryptoAsset", "aliases": ["ether"]}]}"#,
+        )?;
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&json)?
+            .set_modified(later)?;
+        let dict = reloader.poll().expect("change detected")?;
+        assert!(dict.entities.contains_key("ether"));
+        assert!(!dict.entities.contains_key("bitcoin"));
+
+        // A broken file surfaces as an error rather than an empty dictionary
+        std::fs::write(&json, "{not json")?;
+        std::fs::File::options()
+            .write(true)
+            .open(&json)?
+            .set_modified(later + Duration::from_secs(5))?;
+        assert!(reloader.poll().expect("change detected").is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+}
@@ -1,255 +1,325 @@
 //! event_features.rs
 //!
 //! Low-level feature extraction from TokenizedNews:
-//!   - entities (via curated dictionaries / Aho-Corasick)
-//!   - numbers (%, bps, years, generic numbers)
-//!   - coarse time windows (year-end, next week, Q4, etc.)
+//!   - entities (via curated dictionaries / Aho-Corasick, on token boundaries)
+//!   - numbers (%, bps, amounts, moves; see `numeric_facts`)
+//!   - time windows (dates, weekdays, relative spans, named events; see `time_expressions`)
 
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::ops::Range;
 
 use aho_corasick::AhoCorasick;
-use chrono::{DateTime, Duration, Utc};
-use lazy_static::lazy_static;
-use regex::Regex;
+use chrono::{DateTime, Utc};
+use deunicode::deunicode;
+use serde::Deserialize;
 
+use crate::strategy::numeric_facts::{self, NumericFact, Verdict};
+use crate::strategy::time_expressions::TimeExpressionParser;
 use crate::strategy::tokenization::TokenizedNews;
 
-/// Simple extracted entity.
-#[derive(Debug, Clone)]
+/// What kind of thing an entity is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EntityType {
+    Person,
+    Organization,
+    Country,
+    Ticker,
+    SportsTeam,
+    CryptoAsset,
+    /// Topics such as "inflation" or "GDP": useful for retrieval, too generic to discriminate markets
+    #[default]
+    Concept,
+}
+
+impl EntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityType::Person => "person",
+            EntityType::Organization => "organization",
+            EntityType::Country => "country",
+            EntityType::Ticker => "ticker",
+            EntityType::SportsTeam => "sportsTeam",
+            EntityType::CryptoAsset => "cryptoAsset",
+            EntityType::Concept => "concept",
+        }
+    }
+}
+
+/// Simple extracted entity, once per canonical label.
+#[derive(Debug, Clone, Default)]
 pub struct Entity {
     pub value: String, // canonical label, e.g. "ECB", "Eurozone"
+    pub entity_type: EntityType,
+    /// Byte ranges of each mention in `TokenizedNews::normalized`, in order.
+    pub spans: Vec<Range<usize>>,
+    /// Every dictionary alias of the label, lowercased ASCII ("donald trump",
+    /// "trump"), so text naming the entity another way still matches.
+    pub aliases: Vec<String>,
 }
 
-/// Coarse time window extracted from text.
-#[derive(Debug, Clone)]
+impl Entity {
+    /// Whether lowercase `text` names this entity, by its label or any alias,
+    /// as a whole term.
+    pub fn is_named_in(&self, text: &str) -> bool {
+        let text = deunicode(text);
+        contains_term(&text, &deunicode(&self.value).to_lowercase())
+            || self.aliases.iter().any(|alias| contains_term(&text, alias))
+    }
+}
+
+/// Time window the news refers to, from the first time expression in the text.
+#[derive(Debug, Clone, PartialEq)]
 pub struct TimeWindow {
     pub start: DateTime<Utc>,
-    #[allow(dead_code)]
     pub end: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct EventFeatures {
     pub entities: Vec<Entity>,
+    pub numbers: Vec<NumericFact>,
     pub time_window: Option<TimeWindow>,
 }
 
-/// Dictionaries used in feature extraction.
-/// Keep this minimal & composable – load from config/JSON later.
-#[derive(Debug, Clone)]
+/// Canonical label and type an alias resolves to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictEntity {
+    pub label: String,
+    pub entity_type: EntityType,
+}
+
+/// Dictionaries used in feature extraction. Built-in minimal set, or loaded
+/// from the files in `strategy.entities` (see `entity_dictionary`).
+#[derive(Debug, Clone, Default)]
 pub struct FeatureDictionaries {
-    /// Lowercased pattern -> canonical label
-    pub entities: HashMap<String, String>,
+    /// Lowercased pattern -> canonical entity
+    pub entities: HashMap<String, DictEntity>,
 }
 
 impl FeatureDictionaries {
+    /// Register `label` under each alias (lowercased). A later entry wins on conflict.
+    pub fn insert(&mut self, label: &str, entity_type: EntityType, aliases: &[&str]) {
+        for alias in aliases {
+            self.entities.insert(
+                alias.trim().to_lowercase(),
+                DictEntity {
+                    label: label.to_string(),
+                    entity_type,
+                },
+            );
+        }
+    }
+
     pub fn default_minimal() -> Self {
-        let mut entities = HashMap::new();
+        use EntityType::*;
+        let mut dict = Self::default();
 
         // Central banks
-        entities.insert("ecb".into(), "ECB".into());
-        entities.insert("fed".into(), "Fed".into());
-        entities.insert("fomc".into(), "Fed".into());
-        entities.insert("bank of england".into(), "BoE".into());
-        entities.insert("boj".into(), "BoJ".into());
+        dict.insert("ECB", Organization, &["ecb"]);
+        dict.insert("Fed", Organization, &["fed", "fomc"]);
+        dict.insert("BoE", Organization, &["bank of england"]);
+        dict.insert("BoJ", Organization, &["boj"]);
 
         // Macro concepts
-        entities.insert("inflation".into(), "inflation".into());
-        entities.insert("cpi".into(), "CPI".into());
-        entities.insert("gdp".into(), "GDP".into());
+        dict.insert("inflation", Concept, &["inflation"]);
+        dict.insert("CPI", Concept, &["cpi"]);
+        dict.insert("GDP", Concept, &["gdp"]);
 
         // Countries (tiny sample)
-        entities.insert("united states".into(), "US".into());
-        entities.insert("u.s.".into(), "US".into());
-        entities.insert("us".into(), "US".into());
-        entities.insert("china".into(), "China".into());
-        entities.insert("germany".into(), "Germany".into());
+        dict.insert("US", Country, &["united states", "u.s.", "us"]);
+        dict.insert("China", Country, &["china"]);
+        dict.insert("Germany", Country, &["germany"]);
 
         // Crypto
-        entities.insert("bitcoin".into(), "BTC".into());
-        entities.insert("btc".into(), "BTC".into());
-        entities.insert("ether".into(), "ETH".into());
-        entities.insert("eth".into(), "ETH".into());
+        dict.insert("BTC", CryptoAsset, &["bitcoin", "btc"]);
+        dict.insert("ETH", CryptoAsset, &["ether", "eth"]);
 
-        Self { entities }
+        dict
     }
 }
 
-/// Extracts low-level features from normalized text.
 /// Extracts low-level features from normalized text.
 pub struct EventFeatureExtractor {
     ac_entities: AhoCorasick,
-    entity_labels: Vec<String>,
-    re_date_phrase: Regex,
+    entity_labels: Vec<DictEntity>,
+    /// Label -> all of its (folded) aliases
+    label_aliases: HashMap<String, Vec<String>>,
+    time_parser: TimeExpressionParser,
 }
 
 impl EventFeatureExtractor {
-    pub fn new(dict: FeatureDictionaries) -> Self {
-        let mut patterns = Vec::new();
-        let mut labels = Vec::new();
-
-        // Build AC over all entity patterns (keys must be lowercase).
-        for (pat, label) in dict.entities.into_iter() {
-            patterns.push(pat);
-            labels.push(label);
-        }
-
-        let ac_entities = AhoCorasick::new(&patterns).expect("failed to build AC for entities");
-
-        lazy_static! {
-            static ref RE_DATE_PHRASE: Regex = Regex::new(
-                r"\b(year[- ]end|year end|next week|this week|next month|this month|q[1-4])\b"
-            )
-            .unwrap();
-        }
-
+    pub fn new(dict: FeatureDictionaries, time_parser: TimeExpressionParser) -> Self {
+        let (ac_entities, entity_labels, label_aliases) = build_entity_matcher(dict);
         Self {
             ac_entities,
-            entity_labels: labels,
-            re_date_phrase: RE_DATE_PHRASE.clone(),
+            entity_labels,
+            label_aliases,
+            time_parser,
         }
     }
 
     #[allow(dead_code)]
     pub fn with_default_dicts() -> Self {
-        Self::new(FeatureDictionaries::default_minimal())
+        Self::new(
+            FeatureDictionaries::default_minimal(),
+            TimeExpressionParser::default(),
+        )
+    }
+
+    /// Swap in reloaded dictionaries, keeping the time expression settings.
+    pub fn set_dictionaries(&mut self, dict: FeatureDictionaries) {
+        (self.ac_entities, self.entity_labels, self.label_aliases) = build_entity_matcher(dict);
     }
 
     pub fn extract(&self, tok: &TokenizedNews, now: DateTime<Utc>) -> EventFeatures {
         let text = tok.normalized.as_str();
 
         let entities = self.extract_entities(text);
-        let time_window = self.derive_time_window(text, now);
+        let mut numbers = numeric_facts::extract_numeric_facts(text);
+        numeric_facts::assign_subjects(text, &mut numbers, &entities);
+        let time_window = self.time_parser.window(text, now);
 
         EventFeatures {
             entities,
+            numbers,
             time_window,
         }
     }
 
-    fn extract_entities(&self, text: &str) -> Vec<Entity> {
-        let mut entities = Vec::new();
-
-        for m in self.ac_entities.find_iter(text) {
-            let label = &self.entity_labels[m.pattern()];
-            entities.push(Entity {
-                value: label.clone(),
-            });
+    /// Settle the numeric threshold in a market question ("will cpi exceed 3%?")
+    /// from facts about an entity the question names. None if the question has
+    /// no threshold or the facts don't decide it.
+    ///
+    /// The facts must be dated (`fact_window`, the news' time window) and about
+    /// the period the market resolves on: the question's own time expression,
+    /// else its resolution date. A level must be reported for a period that
+    /// covers the market's and has already begun, so a current level never
+    /// settles a level on a future date; a touch ("hit 100k") must be reported
+    /// within the market's period.
+    pub fn settle_threshold(
+        &self,
+        question: &str,
+        facts: &[NumericFact],
+        fact_window: Option<&TimeWindow>,
+        resolution: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Option<Verdict> {
+        if facts.is_empty() {
+            return None;
+        }
+        let fact_window = fact_window?;
+        let question = deunicode(question).to_lowercase();
+        let threshold = numeric_facts::parse_threshold(&question)?;
+
+        let stated = self.time_parser.window(&question, now);
+        let period_ok = if threshold.touch {
+            let period = stated.or(resolution.map(|end| TimeWindow {
+                start: DateTime::<Utc>::MIN_UTC,
+                end,
+            }))?;
+            fact_window.start <= now
+                && period.start <= fact_window.start
+                && fact_window.end <= period.end
+        } else {
+            let period = stated.or(resolution.map(|at| TimeWindow { start: at, end: at }))?;
+            period.start <= now
+                && fact_window.start <= period.start
+                && period.end <= fact_window.end
+        };
+        if !period_ok {
+            return None;
         }
 
-        entities
+        let entities = self.extract_entities(&question);
+        let subjects: Vec<&str> = entities.iter().map(|e| e.value.as_str()).collect();
+        numeric_facts::settle(&threshold, &subjects, facts)
     }
 
-    fn derive_time_window(&self, text: &str, now: DateTime<Utc>) -> Option<TimeWindow> {
-        // Phrase-based first.
-        for caps in self.re_date_phrase.captures_iter(text) {
-            if let Some(m) = caps.get(1) {
-                if let Some(tw) = map_phrase_to_window(m.as_str(), now) {
-                    return Some(tw);
-                }
+    /// Dictionary matches on token boundaries ("us" never matches inside
+    /// "business"), resolved leftmost-longest ("bank of england" wins over a
+    /// nested "england"), one entity per label with every mention's span.
+    fn extract_entities(&self, text: &str) -> Vec<Entity> {
+        // Overlapping search, so a hit rejected at a boundary can't hide a valid one
+        let mut hits: Vec<(usize, usize, usize)> = self
+            .ac_entities
+            .find_overlapping_iter(text)
+            .filter(|m| on_token_boundaries(text, m.start(), m.end()))
+            .map(|m| (m.start(), m.end(), m.pattern().as_usize()))
+            .collect();
+        hits.sort_by_key(|&(start, end, _)| (start, Reverse(end)));
+
+        let mut entities: Vec<Entity> = Vec::new();
+        let mut covered_to = 0;
+        for (start, end, pattern) in hits {
+            if start < covered_to {
+                continue;
+            }
+            covered_to = end;
+
+            let dict_entity = &self.entity_labels[pattern];
+            match entities.iter_mut().find(|e| e.value == dict_entity.label) {
+                Some(entity) => entity.spans.push(start..end),
+                None => entities.push(Entity {
+                    value: dict_entity.label.clone(),
+                    entity_type: dict_entity.entity_type,
+                    spans: std::iter::once(start..end).collect(),
+                    aliases: self
+                        .label_aliases
+                        .get(&dict_entity.label)
+                        .cloned()
+                        .unwrap_or_default(),
+                }),
             }
         }
 
-        None
+        entities
     }
 }
 
 // --- helpers ---
 
-// --- helpers ---
-
-fn map_phrase_to_window(phrase: &str, now: DateTime<Utc>) -> Option<TimeWindow> {
-    use chrono::{Datelike, TimeZone};
-
-    let lower = phrase.to_lowercase();
-
-    if lower.contains("year-end") || lower.contains("year end") {
-        let year = now.year();
-        let start = now;
-        let end = Utc.with_ymd_and_hms(year, 12, 31, 23, 59, 59).unwrap();
-        return Some(TimeWindow { start, end });
-    }
-
-    if lower == "next week" {
-        let weekday = now.weekday();
-        let days_from_monday = weekday.num_days_from_monday() as i64;
-        let days_to_next_monday = 7 - days_from_monday;
-        let start_date = (now + Duration::days(days_to_next_monday)).date_naive();
-        let end_date = start_date + Duration::days(6);
-
-        let start = DateTime::<Utc>::from_naive_utc_and_offset(
-            start_date.and_hms_opt(0, 0, 0).unwrap(),
-            Utc,
-        );
-        let end = DateTime::<Utc>::from_naive_utc_and_offset(
-            end_date.and_hms_opt(23, 59, 59).unwrap(),
-            Utc,
-        );
-        return Some(TimeWindow { start, end });
-    }
-
-    if lower == "this week" {
-        let weekday = now.weekday();
-        let days_from_monday = weekday.num_days_from_monday() as i64;
-        let start_date = (now - Duration::days(days_from_monday)).date_naive();
-        let end_date = start_date + Duration::days(6);
-
-        let start = DateTime::<Utc>::from_naive_utc_and_offset(
-            start_date.and_hms_opt(0, 0, 0).unwrap(),
-            Utc,
-        );
-        let end = DateTime::<Utc>::from_naive_utc_and_offset(
-            end_date.and_hms_opt(23, 59, 59).unwrap(),
-            Utc,
-        );
-        return Some(TimeWindow { start, end });
-    }
-
-    if lower == "this month" || lower == "next month" {
-        let mut year = now.year();
-        let mut month = now.month();
-
-        if lower == "next month" {
-            if month == 12 {
-                year += 1;
-                month = 1;
-            } else {
-                month += 1;
-            }
+/// Aho-Corasick over all aliases, plus each label's aliases. Keys are
+/// lowercase; fold them to ASCII like the normalized text they are matched against.
+fn build_entity_matcher(
+    dict: FeatureDictionaries,
+) -> (AhoCorasick, Vec<DictEntity>, HashMap<String, Vec<String>>) {
+    let (patterns, labels): (Vec<String>, Vec<DictEntity>) = dict
+        .entities
+        .into_iter()
+        .map(|(pat, entity)| (deunicode(&pat), entity))
+        .unzip();
+    let mut label_aliases: HashMap<String, Vec<String>> = HashMap::new();
+    for (pattern, entity) in patterns.iter().zip(&labels) {
+        let aliases = label_aliases.entry(entity.label.clone()).or_default();
+        if !aliases.contains(pattern) {
+            aliases.push(pattern.clone());
         }
-
-        let start = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap();
-        // naive end of month: +32 days → clamp to 1st of following month -1 sec
-        let approx_next_month = start + Duration::days(32);
-        let ny = approx_next_month.year();
-        let nm = approx_next_month.month();
-        let next_month_start = Utc.with_ymd_and_hms(ny, nm, 1, 0, 0, 0).unwrap();
-        let end = next_month_start - Duration::seconds(1);
-        return Some(TimeWindow { start, end });
     }
-
-    if lower.starts_with('q') && lower.len() == 2 {
-        if let Some(qch) = lower.chars().nth(1) {
-            if let Some(q) = qch.to_digit(10) {
-                let year = now.year();
-                let (start_month, end_month) = match q {
-                    1 => (1, 3),
-                    2 => (4, 6),
-                    3 => (7, 9),
-                    4 => (10, 12),
-                    _ => return None,
-                };
-                let start = Utc.with_ymd_and_hms(year, start_month, 1, 0, 0, 0).unwrap();
-                let approx_end = Utc
-                    .with_ymd_and_hms(year, end_month, 28, 23, 59, 59)
-                    .unwrap();
-                let end = approx_end + Duration::days(7); // approximate
-                return Some(TimeWindow { start, end });
-            }
-        }
+    for aliases in label_aliases.values_mut() {
+        aliases.sort();
     }
+    let ac = AhoCorasick::new(&patterns).expect("failed to build AC for entities");
+    (ac, labels, label_aliases)
+}
 
-    None
+/// Whether `needle` occurs in `haystack` as a whole term (both lowercase).
+pub fn contains_term(haystack: &str, needle: &str) -> bool {
+    !needle.is_empty()
+        && haystack
+            .match_indices(needle)
+            .any(|(start, m)| on_token_boundaries(haystack, start, start + m.len()))
+}
+
+/// A match must not start or end in the middle of a word. Edges that are
+/// punctuation themselves ("u.s.", "opec+") need no boundary on that side.
+fn on_token_boundaries(text: &str, start: usize, end: usize) -> bool {
+    let is_word = |c: char| c.is_alphanumeric();
+    let matched = &text[start..end];
+    let left_ok = !matched.starts_with(is_word) || !text[..start].ends_with(is_word);
+    let right_ok = !matched.ends_with(is_word) || !text[end..].starts_with(is_word);
+    left_ok && right_ok
 }
 
 #[cfg(test)]
@@ -257,6 +327,7 @@ mod tests {
     use super::*;
     use crate::core::types::RawNews;
     use crate::strategy::tokenization::{TokenizationConfig, TokenizedNews};
+    use chrono::TimeZone;
 
     fn make_tokenized(text: &str) -> TokenizedNews {
         let raw = RawNews {
@@ -266,6 +337,10 @@ mod tests {
             feed: "test".to_string(),
             published: Some(Utc::now()),
             labels: vec![],
+            guid: None,
+            author: None,
+            updated: None,
+            body: None,
         };
         let cfg = TokenizationConfig::default();
         TokenizedNews::from_raw(raw, &cfg)
@@ -283,6 +358,152 @@ mod tests {
         let values: Vec<String> = feat.entities.iter().map(|e| e.value.clone()).collect();
         assert!(values.contains(&"Fed".to_string()));
         assert!(values.contains(&"inflation".to_string()));
+
+        let fed = feat.entities.iter().find(|e| e.value == "Fed").unwrap();
+        assert_eq!(fed.entity_type, EntityType::Organization);
+        // Carries every alias, so "FOMC" in a market title names it too
+        assert_eq!(fed.aliases, vec!["fed", "fomc"]);
+        assert!(fed.is_named_in("will the fomc hike in june?"));
+        assert!(!fed.is_named_in("federal express earnings"));
+    }
+
+    #[test]
+    fn test_tricky_headline_corpus() {
+        let extractor = EventFeatureExtractor::with_default_dicts();
+        let corpus = include_str!("../../testdata/entity_headlines.txt");
+
+        let mut cases = 0;
+        for line in corpus.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (headline, expected) = line.split_once('|').expect("headline | labels");
+            let expected: Vec<&str> = expected
+                .split(',')
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty())
+                .collect();
+
+            let feat = extractor.extract(&make_tokenized(headline.trim()), Utc::now());
+            let found: Vec<&str> = feat.entities.iter().map(|e| e.value.as_str()).collect();
+            assert_eq!(found, expected, "headline: {}", headline.trim());
+            cases += 1;
+        }
+        assert!(cases >= 15);
+    }
+
+    #[test]
+    fn test_leftmost_longest_with_spans() {
+        let mut dict = FeatureDictionaries::default();
+        dict.insert("NYC", EntityType::Country, &["new york", "nyc"]);
+        dict.insert("NYT", EntityType::Organization, &["new york times"]);
+        dict.insert("York", EntityType::Country, &["york"]);
+        let extractor = EventFeatureExtractor::new(dict, TimeExpressionParser::default());
+
+        let tok = make_tokenized("New York Times says NYC rents rise in new york");
+        let entities = extractor.extract(&tok, Utc::now()).entities;
+
+        // The longest alias wins; nested "new york" and "york" are not reported
+        let values: Vec<&str> = entities.iter().map(|e| e.value.as_str()).collect();
+        assert_eq!(values, vec!["NYT", "NYC"]);
+
+        // Deduplicated by label, with one span per mention
+        let nyc = &entities[1];
+        let mentions: Vec<&str> = nyc
+            .spans
+            .iter()
+            .map(|r| &tok.normalized[r.clone()])
+            .collect();
+        assert_eq!(mentions, vec!["nyc", "new york"]);
+        assert_eq!(
+            &tok.normalized[entities[0].spans[0].clone()],
+            "new york times"
+        );
+    }
+
+    #[test]
+    fn test_settle_threshold_from_news() {
+        let extractor = EventFeatureExtractor::with_default_dicts();
+        let now = Utc.with_ymd_and_hms(2026, 3, 20, 12, 0, 0).unwrap();
+        let feat = extractor.extract(&make_tokenized("US CPI rose 3.2% in March"), now);
+        assert_eq!(feat.numbers.len(), 1);
+        // The closest entity before the number
+        assert_eq!(feat.numbers[0].subject.as_deref(), Some("CPI"));
+
+        let numbers = &feat.numbers;
+        let window = feat.time_window.as_ref();
+        let resolved = Some(Utc.with_ymd_and_hms(2026, 3, 15, 0, 0, 0).unwrap());
+        let settle = |question: &str, resolution| {
+            extractor.settle_threshold(question, numbers, window, resolution, now)
+        };
+        assert_eq!(
+            settle("Will US CPI exceed 3% in March?", None),
+            Some(Verdict::Confirms)
+        );
+        assert_eq!(
+            settle("Will CPI be above 3.5%?", resolved),
+            Some(Verdict::Contradicts)
+        );
+        // No threshold, or nothing about the question's entities
+        assert_eq!(settle("Will the US enter a recession?", resolved), None);
+        assert_eq!(settle("Will US inflation exceed 3%?", resolved), None);
+        assert_eq!(settle("Will China GDP exceed 5%?", resolved), None);
+        // A different or unknown period
+        assert_eq!(settle("Will US CPI exceed 3% in April?", None), None);
+        assert_eq!(settle("Will CPI be above 3.5%?", None), None);
+        // The level at a date still ahead
+        let month_end = Some(Utc.with_ymd_and_hms(2026, 3, 31, 0, 0, 0).unwrap());
+        assert_eq!(settle("Will CPI be above 3.5%?", month_end), None);
+        // Undated figures settle nothing
+        assert_eq!(
+            extractor.settle_threshold("Will US CPI exceed 3% in March?", numbers, None, None, now),
+            None
+        );
+    }
+
+    #[test]
+    fn test_settle_threshold_future_level() {
+        let extractor = EventFeatureExtractor::with_default_dicts();
+        let now = Utc.with_ymd_and_hms(2026, 6, 10, 12, 0, 0).unwrap();
+        let feat = extractor.extract(&make_tokenized("Bitcoin trades at $120,000 today"), now);
+        assert_eq!(feat.numbers.len(), 1);
+        let window = feat.time_window.as_ref();
+        assert!(window.is_some());
+        let dec_31 = Some(Utc.with_ymd_and_hms(2026, 12, 31, 0, 0, 0).unwrap());
+
+        // Today's level says nothing about the level at year-end
+        assert_eq!(
+            extractor.settle_threshold(
+                "Will bitcoin be above $100,000 on December 31?",
+                &feat.numbers,
+                window,
+                None,
+                now
+            ),
+            None
+        );
+        assert_eq!(
+            extractor.settle_threshold(
+                "Will BTC be above 100k?",
+                &feat.numbers,
+                window,
+                dec_31,
+                now
+            ),
+            None
+        );
+        // ...but a level touched today meets a "reach" before year-end
+        assert_eq!(
+            extractor.settle_threshold(
+                "Will bitcoin hit $110,000 in 2026?",
+                &feat.numbers,
+                window,
+                dec_31,
+                now
+            ),
+            Some(Verdict::Confirms)
+        );
     }
 
     #[test]
@@ -1,10 +1,9 @@
 use crate::core::types::RawNews;
+use crate::strategy::embedding_store::text_hash;
 use crate::strategy::normalizers::normalize_news_item_dedup_stage;
-use ahash::AHasher;
 use chrono::Utc;
 use lru::LruCache;
 
-use std::hash::{Hash, Hasher};
 use std::num::{NonZero, NonZeroUsize};
 
 /// Configuration for the exact duplication pipeline
@@ -37,17 +36,19 @@ impl ExactDuplicateDetector {
         }
     }
 
-    fn hash_str(s: &str) -> u64 {
-        let mut hasher = AHasher::default();
-        s.hash(&mut hasher);
-        hasher.finish()
+    /// Hash of the normalized news, stable across restarts so it can be persisted.
+    pub fn content_hash(news: &RawNews) -> u64 {
+        text_hash(&[&normalize_news_item_dedup_stage(news)])
+    }
+
+    pub fn ttl(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.ttl_hours)
     }
 
     // we don't have to clean expired keys, what matters is if it is still within ttl and we have
     //  a collision it is a duplicate
     pub fn is_duplicate(&mut self, news: &RawNews) -> bool {
-        let normalized = normalize_news_item_dedup_stage(news);
-        let hash = Self::hash_str(&normalized);
+        let hash = Self::content_hash(news);
         let now = Utc::now().timestamp(); // seconds
 
         if let Some(&ts) = self.cache.get(&hash) {
@@ -61,21 +62,12 @@ impl ExactDuplicateDetector {
         self.cache.put(hash, now);
         false
     }
-    pub fn hydrate(&mut self, items: Vec<(String, String)>) {
-        let now = Utc::now().timestamp();
-        for (title, description) in items {
-            // Reconstruct minimal RawNews for normalization
-            let news = RawNews {
-                title,
-                description,
-                url: "".to_string(),
-                feed: "".to_string(),
-                published: None,
-                labels: vec![],
-            };
-            let normalized = normalize_news_item_dedup_stage(&news);
-            let hash = Self::hash_str(&normalized);
-            self.cache.put(hash, now);
+
+    /// Restore persisted `(content hash, first seen)` pairs, oldest first, so
+    /// their TTL keeps running from when they were first seen.
+    pub fn hydrate(&mut self, items: impl IntoIterator<Item = (u64, i64)>) {
+        for (hash, first_seen) in items {
+            self.cache.put(hash, first_seen);
         }
     }
 }
@@ -93,6 +85,10 @@ mod tests {
             feed: "test".to_string(),
             published: None,
             labels: vec![],
+            guid: None,
+            author: None,
+            updated: None,
+            body: None,
         }
     }
 
@@ -124,4 +120,31 @@ mod tests {
         // Second time (different case) -> Duplicate
         assert!(detector.is_duplicate(&news2));
     }
+
+    #[test]
+    fn test_hydrate_keeps_first_seen() {
+        let mut detector = ExactDuplicateDetector::new(ExactDuplicateDetectorConfig::default());
+        let fresh = make_news("Fed cuts rates");
+        let stale = make_news("ECB holds rates");
+        let now = Utc::now();
+
+        detector.hydrate([
+            (
+                ExactDuplicateDetector::content_hash(&stale),
+                (now - chrono::Duration::hours(49)).timestamp(),
+            ),
+            (
+                ExactDuplicateDetector::content_hash(&fresh),
+                (now - chrono::Duration::hours(47)).timestamp(),
+            ),
+        ]);
+
+        assert!(detector.is_duplicate(&fresh));
+        assert!(!detector.is_duplicate(&stale));
+        // Stable across processes
+        assert_eq!(
+            ExactDuplicateDetector::content_hash(&fresh),
+            text_hash(&["fed cuts rates"])
+        );
+    }
 }
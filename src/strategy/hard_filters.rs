@@ -1,12 +1,20 @@
-use crate::strategy::event_features::{Entity, TimeWindow};
+use crate::strategy::event_features::{Entity, EntityType, TimeWindow};
 use crate::strategy::types::RawCandidate;
 
 #[derive(Default)]
-pub struct HardFilterer;
+pub struct HardFilterer {
+    /// How long after the end of the news' time window a market may still resolve
+    max_resolution_lag: Option<chrono::Duration>,
+}
 
 impl HardFilterer {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_max_resolution_lag(mut self, lag: Option<std::time::Duration>) -> Self {
+        self.max_resolution_lag = lag.and_then(|l| chrono::Duration::from_std(l).ok());
+        self
     }
 
     pub fn apply(
@@ -15,23 +23,39 @@ impl HardFilterer {
         entities: &[Entity],
         time_window: &Option<TimeWindow>,
     ) -> Vec<RawCandidate> {
+        // Concepts ("inflation", "GDP") appear in too many markets to discriminate;
+        // require overlap on named entities only
+        let entities: Vec<&Entity> = entities
+            .iter()
+            .filter(|e| e.entity_type != EntityType::Concept)
+            .collect();
+
         candidates
             .into_iter()
             .filter(|c| {
-                // 1. Time Window Filter
-                if let Some(tw) = time_window {
-                    if let Some(res_ts) = c.resolution_date {
-                        let res_date =
-                            chrono::DateTime::from_timestamp(res_ts, 0).unwrap_or_default();
-
-                        if res_date < tw.start {
-                            return false;
-                        }
+                // 0. Empty book: nothing to trade against
+                if c.liquidity.is_some_and(|l| l <= 0.0) {
+                    return false;
+                }
+
+                // 1. Time Window Filter: resolves before the event, or long after it
+                if let Some(tw) = time_window
+                    && let Some(res_ts) = c.resolution_date
+                {
+                    let res_date = chrono::DateTime::from_timestamp(res_ts, 0).unwrap_or_default();
+
+                    if res_date < tw.start {
+                        return false;
+                    }
+                    if let Some(lag) = self.max_resolution_lag
+                        && res_date > tw.end + lag
+                    {
+                        return false;
                     }
                 }
 
                 // 2. Entity Filter
-                if !entities.is_empty() && !self.check_entity_overlap(c, entities) {
+                if !entities.is_empty() && !self.check_entity_overlap(c, &entities) {
                     return false;
                 }
 
@@ -40,37 +64,25 @@ impl HardFilterer {
             .collect()
     }
 
-    fn check_entity_overlap(&self, candidate: &RawCandidate, entities: &[Entity]) -> bool {
-        for entity in entities {
-            let val = entity.value.to_lowercase();
-
-            // Check tags
-            if candidate
-                .tags
-                .iter()
-                .any(|t| t.to_lowercase().contains(&val))
-            {
-                return true;
-            }
-
-            // Check title
-            if candidate.title.to_lowercase().contains(&val) {
-                return true;
-            }
-
-            // Check description
-            if candidate.description.to_lowercase().contains(&val) {
-                return true;
-            }
-        }
-        false
+    /// Any entity named, by its label or an alias, in the tags, title or
+    /// description.
+    fn check_entity_overlap(&self, candidate: &RawCandidate, entities: &[&Entity]) -> bool {
+        let title = candidate.title.to_lowercase();
+        let description = candidate.description.to_lowercase();
+        let tags: Vec<String> = candidate.tags.iter().map(|t| t.to_lowercase()).collect();
+
+        entities.iter().any(|entity| {
+            tags.iter().any(|t| entity.is_named_in(t))
+                || entity.is_named_in(&title)
+                || entity.is_named_in(&description)
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::strategy::event_features::{Entity, TimeWindow};
+    use crate::strategy::event_features::{Entity, EntityType, TimeWindow};
     use crate::strategy::types::RawCandidate;
     use chrono::{Duration, Utc};
 
@@ -112,12 +124,59 @@ mod tests {
         assert_eq!(filtered[1].market_id, "3");
     }
 
+    #[test]
+    fn test_hard_filter_resolution_lag() {
+        let now = Utc::now();
+        let tw = TimeWindow {
+            start: now,
+            end: now + Duration::days(2),
+        };
+        let candidate = |id: &str, resolves_in_days: i64| RawCandidate {
+            market_id: id.to_string(),
+            resolution_date: Some((now + Duration::days(resolves_in_days)).timestamp()),
+            ..Default::default()
+        };
+        let candidates = vec![candidate("1", 1), candidate("2", 20), candidate("3", 200)];
+
+        // Unbounded by default
+        let filtered = HardFilterer::new().apply(candidates.clone(), &[], &Some(tw.clone()));
+        assert_eq!(filtered.len(), 3);
+
+        let filterer = HardFilterer::new()
+            .with_max_resolution_lag(Some(std::time::Duration::from_secs(30 * 24 * 3600)));
+        let filtered = filterer.apply(candidates, &[], &Some(tw));
+        let ids: Vec<_> = filtered.iter().map(|c| c.market_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_hard_filter_liquidity() {
+        let filterer = HardFilterer::new();
+        let candidate = |id: &str, liquidity: Option<f64>| RawCandidate {
+            market_id: id.to_string(),
+            liquidity,
+            ..Default::default()
+        };
+
+        let candidates = vec![
+            candidate("1", Some(0.0)),
+            candidate("2", Some(1500.0)),
+            candidate("3", None), // Unknown, should pass
+        ];
+        let filtered = filterer.apply(candidates, &[], &None);
+
+        let ids: Vec<_> = filtered.iter().map(|c| c.market_id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3"]);
+    }
+
     #[test]
     fn test_hard_filter_entities() {
         let filterer = HardFilterer::new();
 
         let entity_apple = Entity {
             value: "Apple".to_string(),
+            entity_type: EntityType::Organization,
+            ..Default::default()
         };
         let entities = vec![entity_apple];
 
@@ -142,10 +201,10 @@ mod tests {
             ..Default::default()
         };
 
-        // No match
+        // No match ("apple" only inside another word)
         let c4 = RawCandidate {
             market_id: "4".to_string(),
-            title: "Microsoft earnings".to_string(),
+            title: "Microsoft earnings, Snapplesauce sales".to_string(),
             tags: vec!["tech".to_string(), "msft".to_string()],
             ..Default::default()
         };
@@ -168,4 +227,74 @@ mod tests {
         let filtered_empty = filterer.apply(candidates_empty, &[], &None);
         assert_eq!(filtered_empty.len(), 1);
     }
+
+    #[test]
+    fn test_hard_filter_entity_aliases() {
+        let filterer = HardFilterer::new();
+        // As extracted from "Trump says a bitcoin reserve is coming"
+        let entity = |value: &str, entity_type, aliases: &[&str]| Entity {
+            value: value.to_string(),
+            entity_type,
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            ..Default::default()
+        };
+        let entities = vec![
+            entity(
+                "Donald Trump",
+                EntityType::Person,
+                &["donald trump", "trump"],
+            ),
+            entity("BTC", EntityType::CryptoAsset, &["bitcoin", "btc"]),
+        ];
+
+        let candidate = |id: &str, title: &str| RawCandidate {
+            market_id: id.to_string(),
+            title: title.to_string(),
+            ..Default::default()
+        };
+        // Neither title contains a canonical label
+        let candidates = vec![
+            candidate("1", "Will Trump sign a crypto bill in 2026?"),
+            candidate("2", "Will Bitcoin hit $150k?"),
+            candidate("3", "Will the Fed cut rates in March?"),
+        ];
+        let filtered = filterer.apply(candidates, &entities, &None);
+        let ids: Vec<_> = filtered.iter().map(|c| c.market_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_hard_filter_ignores_concepts() {
+        let filterer = HardFilterer::new();
+        let entity = |value: &str, entity_type| Entity {
+            value: value.to_string(),
+            entity_type,
+            ..Default::default()
+        };
+        let candidate = |id: &str, title: &str| RawCandidate {
+            market_id: id.to_string(),
+            title: title.to_string(),
+            ..Default::default()
+        };
+        let candidates = vec![
+            candidate("1", "Will the Fed cut rates in March?"),
+            candidate("2", "US inflation above 3% in 2026?"),
+        ];
+
+        // Only a concept: no entity constraint
+        let concepts = vec![entity("inflation", EntityType::Concept)];
+        assert_eq!(
+            filterer.apply(candidates.clone(), &concepts, &None).len(),
+            2
+        );
+
+        // A named entity decides, the concept alongside it does not
+        let mixed = vec![
+            entity("inflation", EntityType::Concept),
+            entity("Fed", EntityType::Organization),
+        ];
+        let filtered = filterer.apply(candidates, &mixed, &None);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].market_id, "1");
+    }
 }
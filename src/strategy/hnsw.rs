@@ -0,0 +1,530 @@
+use crate::config::config::AnnCfg;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// In-process HNSW (hierarchical navigable small world) index over cosine similarity.
+///
+/// Vectors are normalized on insert so similarity is a dot product. Deletes are
+/// tombstones that still route searches but are never returned; once they
+/// outnumber live nodes (`needs_rebuild`) the owner rebuilds the graph from a
+/// `rebuild_plan`, possibly on another thread, and swaps it in with
+/// `replace_with`. Updates are a delete followed by an insert. Filtered searches route through every node but only
+/// collect accepted ones, so a selective filter costs visits, not recall.
+pub struct Hnsw {
+    m: usize,
+    m0: usize,
+    ef_construction: usize,
+    ef_search: usize,
+    level_mult: f64,
+    nodes: Vec<Node>,
+    index_of: HashMap<String, u32>,
+    entry: Option<u32>,
+    max_level: usize,
+    deleted: usize,
+    rng: u64,
+    /// Bumped on every change, so a rebuild of a stale snapshot is discarded
+    generation: u64,
+}
+
+/// A fresh graph to build from a snapshot of the live vectors.
+pub struct HnswRebuild {
+    graph: Hnsw,
+    live: Vec<(String, Vec<f32>)>,
+    generation: u64,
+}
+
+impl HnswRebuild {
+    /// Insert the live vectors into the fresh graph. The slow part; needs no
+    /// access to the original.
+    pub fn build(&mut self) {
+        for (id, vector) in self.live.drain(..) {
+            self.graph.insert(&id, &vector);
+        }
+    }
+}
+
+struct Node {
+    id: String,
+    vector: Vec<f32>,
+    /// neighbors[layer], for layers 0..=level
+    neighbors: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+/// Distance (1 - cosine) with a total order, for the heaps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Dist(f32);
+
+impl Eq for Dist {}
+
+impl PartialOrd for Dist {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Dist {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hnsw {
+    pub fn new(cfg: &AnnCfg) -> Self {
+        let m = cfg.m.max(2);
+        Self {
+            m,
+            m0: m * 2,
+            ef_construction: cfg.ef_construction.max(m),
+            ef_search: cfg.ef_search.max(1),
+            level_mult: 1.0 / (m as f64).ln(),
+            nodes: Vec::new(),
+            index_of: HashMap::new(),
+            entry: None,
+            max_level: 0,
+            deleted: 0,
+            // Fixed seed: the graph shape is reproducible for a given insert order
+            rng: 0x9E37_79B9_7F4A_7C15,
+            generation: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.index_of.len()
+    }
+
+    /// Insert or replace the vector for `id`.
+    pub fn insert(&mut self, id: &str, vector: &[f32]) {
+        self.remove(id);
+        let Some(vector) = normalize(vector) else {
+            return;
+        };
+
+        self.generation += 1;
+        let idx = self.nodes.len() as u32;
+        let level = self.random_level();
+        self.nodes.push(Node {
+            id: id.to_string(),
+            vector,
+            neighbors: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.index_of.insert(id.to_string(), idx);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(idx);
+            self.max_level = level;
+            return;
+        };
+
+        let query = self.nodes[idx as usize].vector.clone();
+        let mut entry_points = vec![entry];
+
+        // Greedy descent through layers above the new node's level
+        for layer in (level + 1..=self.max_level).rev() {
+            let nearest = self.search_layer(&query, &entry_points, 1, layer, |_| true);
+            entry_points = vec![nearest[0].1];
+        }
+
+        for layer in (0..=level.min(self.max_level)).rev() {
+            let found =
+                self.search_layer(&query, &entry_points, self.ef_construction, layer, |_| true);
+            let max_links = if layer == 0 { self.m0 } else { self.m };
+
+            let links: Vec<u32> = found.iter().take(self.m).map(|(_, n)| *n).collect();
+            self.nodes[idx as usize].neighbors[layer] = links.clone();
+
+            for n in links {
+                self.nodes[n as usize].neighbors[layer].push(idx);
+                if self.nodes[n as usize].neighbors[layer].len() > max_links {
+                    self.shrink(n, layer, max_links);
+                }
+            }
+            entry_points = found.into_iter().map(|(_, n)| n).collect();
+        }
+
+        if level > self.max_level {
+            self.max_level = level;
+            self.entry = Some(idx);
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(idx) = self.index_of.remove(id) else {
+            return false;
+        };
+        self.nodes[idx as usize].deleted = true;
+        self.deleted += 1;
+        self.generation += 1;
+
+        if self.index_of.is_empty() {
+            self.clear();
+        }
+        true
+    }
+
+    /// Whether tombstones outnumber live nodes, so searches visit mostly dead ends.
+    pub fn needs_rebuild(&self) -> bool {
+        self.deleted > self.index_of.len()
+    }
+
+    /// An empty graph with the same parameters plus a copy of the live vectors.
+    pub fn rebuild_plan(&self) -> HnswRebuild {
+        HnswRebuild {
+            graph: Hnsw {
+                m: self.m,
+                m0: self.m0,
+                ef_construction: self.ef_construction,
+                ef_search: self.ef_search,
+                level_mult: self.level_mult,
+                nodes: Vec::new(),
+                index_of: HashMap::new(),
+                entry: None,
+                max_level: 0,
+                deleted: 0,
+                rng: self.rng,
+                generation: 0,
+            },
+            live: self
+                .nodes
+                .iter()
+                .filter(|n| !n.deleted)
+                .map(|n| (n.id.clone(), n.vector.clone()))
+                .collect(),
+            generation: self.generation,
+        }
+    }
+
+    /// Swap in a built rebuild. Returns false, keeping the current graph, if
+    /// it changed since the plan was taken.
+    pub fn replace_with(&mut self, rebuild: HnswRebuild) -> bool {
+        if rebuild.generation != self.generation || !rebuild.live.is_empty() {
+            return false;
+        }
+        let generation = self.generation;
+        *self = rebuild.graph;
+        self.generation = generation + 1;
+        true
+    }
+
+    /// Top `k` live vectors by cosine similarity, best first.
+    #[allow(dead_code)]
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(String, f32)> {
+        self.search_filtered(query, k, |_| true)
+    }
+
+    /// Top `k` live vectors whose ID passes `accept`, best first.
+    pub fn search_filtered(
+        &self,
+        query: &[f32],
+        k: usize,
+        accept: impl Fn(&str) -> bool,
+    ) -> Vec<(String, f32)> {
+        let (Some(entry), Some(query)) = (self.entry, normalize(query)) else {
+            return Vec::new();
+        };
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut entry_points = vec![entry];
+        for layer in (1..=self.max_level).rev() {
+            let nearest = self.search_layer(&query, &entry_points, 1, layer, |_| true);
+            entry_points = vec![nearest[0].1];
+        }
+
+        let ef = self.ef_search.max(k);
+        self.search_layer(&query, &entry_points, ef, 0, |n| {
+            let node = &self.nodes[n as usize];
+            !node.deleted && accept(&node.id)
+        })
+        .into_iter()
+        .take(k)
+        .map(|(d, n)| (self.nodes[n as usize].id.clone(), 1.0 - d.0))
+        .collect()
+    }
+
+    /// Beam search on one layer. Every neighbor is routed through, but only
+    /// nodes passing `accept` are collected. Returns up to `ef` (distance, node)
+    /// pairs, nearest first.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[u32],
+        ef: usize,
+        layer: usize,
+        accept: impl Fn(u32) -> bool,
+    ) -> Vec<(Dist, u32)> {
+        let mut visited: HashSet<u32> = entry_points.iter().copied().collect();
+        let mut candidates: BinaryHeap<Reverse<(Dist, u32)>> = BinaryHeap::new();
+        let mut results: BinaryHeap<(Dist, u32)> = BinaryHeap::new();
+
+        for &ep in entry_points {
+            let d = self.distance(query, ep);
+            candidates.push(Reverse((d, ep)));
+            if accept(ep) {
+                results.push((d, ep));
+            }
+        }
+        while results.len() > ef {
+            results.pop();
+        }
+
+        while let Some(Reverse((d, current))) = candidates.pop() {
+            let worst = results.peek().map(|(w, _)| *w).unwrap_or(Dist(f32::MAX));
+            if d > worst && results.len() >= ef {
+                break;
+            }
+
+            let node = &self.nodes[current as usize];
+            let Some(neighbors) = node.neighbors.get(layer) else {
+                continue;
+            };
+            for &n in neighbors {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let dn = self.distance(query, n);
+                let worst = results.peek().map(|(w, _)| *w).unwrap_or(Dist(f32::MAX));
+                if results.len() < ef || dn < worst {
+                    candidates.push(Reverse((dn, n)));
+                    if accept(n) {
+                        results.push((dn, n));
+                        if results.len() > ef {
+                            results.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        results.into_sorted_vec()
+    }
+
+    /// Keep only the `max_links` closest neighbors of `node` on `layer`.
+    fn shrink(&mut self, node: u32, layer: usize, max_links: usize) {
+        let base = self.nodes[node as usize].vector.clone();
+        let mut scored: Vec<(Dist, u32)> = self.nodes[node as usize].neighbors[layer]
+            .iter()
+            .map(|&n| (self.distance(&base, n), n))
+            .collect();
+        scored.sort();
+        scored.truncate(max_links);
+        self.nodes[node as usize].neighbors[layer] = scored.into_iter().map(|(_, n)| n).collect();
+    }
+
+    fn clear(&mut self) {
+        self.nodes.clear();
+        self.index_of.clear();
+        self.entry = None;
+        self.max_level = 0;
+        self.deleted = 0;
+        self.generation += 1;
+    }
+
+    fn distance(&self, query: &[f32], node: u32) -> Dist {
+        let v = &self.nodes[node as usize].vector;
+        let dot: f32 = query.iter().zip(v).map(|(a, b)| a * b).sum();
+        Dist(1.0 - dot)
+    }
+
+    fn random_level(&mut self) -> usize {
+        // xorshift64*
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let r = self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        let uniform = ((r >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        (-uniform.ln() * self.level_mult).floor() as usize
+    }
+}
+
+fn normalize(v: &[f32]) -> Option<Vec<f32>> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 || !norm.is_finite() {
+        return None;
+    }
+    Some(v.iter().map(|x| x / norm).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Rng(u64);
+
+    impl Rng {
+        fn next_f32(&mut self) -> f32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+        }
+
+        fn vector(&mut self, dim: usize) -> Vec<f32> {
+            (0..dim).map(|_| self.next_f32()).collect()
+        }
+    }
+
+    fn brute_force(data: &HashMap<String, Vec<f32>>, query: &[f32], k: usize) -> Vec<String> {
+        let q = normalize(query).unwrap();
+        let mut scored: Vec<(f32, &String)> = data
+            .iter()
+            .map(|(id, v)| {
+                let v = normalize(v).unwrap();
+                (q.iter().zip(&v).map(|(a, b)| a * b).sum(), id)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored
+            .into_iter()
+            .take(k)
+            .map(|(_, id)| id.clone())
+            .collect()
+    }
+
+    fn recall(index: &Hnsw, data: &HashMap<String, Vec<f32>>, rng: &mut Rng, k: usize) -> f64 {
+        let queries = 50;
+        let mut hits = 0;
+        for _ in 0..queries {
+            let q = rng.vector(32);
+            let expected: HashSet<String> = brute_force(data, &q, k).into_iter().collect();
+            hits += index
+                .search(&q, k)
+                .iter()
+                .filter(|(id, _)| expected.contains(id))
+                .count();
+        }
+        hits as f64 / (queries * k) as f64
+    }
+
+    #[test]
+    fn test_recall_against_brute_force() {
+        let mut rng = Rng(42);
+        let mut index = Hnsw::new(&AnnCfg::default());
+        let mut data = HashMap::new();
+        for i in 0..2000 {
+            let v = rng.vector(32);
+            index.insert(&i.to_string(), &v);
+            data.insert(i.to_string(), v);
+        }
+        assert_eq!(index.len(), 2000);
+
+        let r = recall(&index, &data, &mut rng, 10);
+        assert!(r >= 0.9, "recall@10 = {}", r);
+    }
+
+    #[test]
+    fn test_recall_after_deletes_and_updates() {
+        let mut rng = Rng(7);
+        let mut index = Hnsw::new(&AnnCfg::default());
+        let mut data = HashMap::new();
+        for i in 0..1500 {
+            let v = rng.vector(32);
+            index.insert(&i.to_string(), &v);
+            data.insert(i.to_string(), v);
+        }
+
+        // Delete 40% and move 10% to new positions
+        for i in 0..600 {
+            assert!(index.remove(&i.to_string()));
+            data.remove(&i.to_string());
+        }
+        for i in 600..750 {
+            let v = rng.vector(32);
+            index.insert(&i.to_string(), &v);
+            data.insert(i.to_string(), v);
+        }
+        assert_eq!(index.len(), 900);
+        assert!(!index.remove("0"));
+        // 750 tombstones (deletes and moves) to 900 live nodes
+        assert!(!index.needs_rebuild());
+
+        let r = recall(&index, &data, &mut rng, 10);
+        assert!(r >= 0.9, "recall@10 = {}", r);
+
+        for i in 750..1000 {
+            index.remove(&i.to_string());
+            data.remove(&i.to_string());
+        }
+        assert!(index.needs_rebuild());
+
+        // A plan taken before a change is discarded
+        let mut stale = index.rebuild_plan();
+        stale.build();
+        index.remove("1000");
+        data.remove("1000");
+        assert!(!index.replace_with(stale));
+
+        let mut rebuild = index.rebuild_plan();
+        rebuild.build();
+        assert!(index.replace_with(rebuild));
+        assert!(!index.needs_rebuild());
+        assert_eq!(index.len(), 649);
+        let r = recall(&index, &data, &mut rng, 10);
+        assert!(r >= 0.9, "recall@10 after rebuild = {}", r);
+
+        // Deleted IDs are never returned, and updated vectors are found by themselves
+        let updated = data["700"].clone();
+        let results = index.search(&updated, 5);
+        assert_eq!(results[0].0, "700");
+        assert!((results[0].1 - 1.0).abs() < 1e-4);
+        assert!(results.iter().all(|(id, _)| data.contains_key(id)));
+    }
+
+    #[test]
+    fn test_filtered_search_matches_brute_force() {
+        let mut rng = Rng(11);
+        let mut index = Hnsw::new(&AnnCfg::default());
+        let mut data = HashMap::new();
+        for i in 0..1000 {
+            let v = rng.vector(32);
+            index.insert(&i.to_string(), &v);
+            data.insert(i.to_string(), v);
+        }
+
+        // A selective filter: 1 in 10 markets
+        let accept = |id: &str| id.parse::<u32>().unwrap() % 10 == 0;
+        let allowed: HashMap<String, Vec<f32>> = data
+            .iter()
+            .filter(|(id, _)| accept(id))
+            .map(|(id, v)| (id.clone(), v.clone()))
+            .collect();
+
+        let (queries, k) = (30, 10);
+        let mut hits = 0;
+        for _ in 0..queries {
+            let q = rng.vector(32);
+            let expected: HashSet<String> = brute_force(&allowed, &q, k).into_iter().collect();
+            let results = index.search_filtered(&q, k, accept);
+            assert_eq!(results.len(), k);
+            assert!(results.iter().all(|(id, _)| accept(id)));
+            hits += results
+                .iter()
+                .filter(|(id, _)| expected.contains(id))
+                .count();
+        }
+        let r = hits as f64 / (queries * k) as f64;
+        assert!(r >= 0.9, "filtered recall@10 = {}", r);
+
+        assert!(
+            index
+                .search_filtered(&rng.vector(32), k, |_| false)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_delete_everything_and_zero_vectors() {
+        let mut index = Hnsw::new(&AnnCfg::default());
+        index.insert("a", &[1.0, 0.0]);
+        index.insert("zero", &[0.0, 0.0]); // not indexable
+        assert_eq!(index.len(), 1);
+
+        index.remove("a");
+        assert!(index.search(&[1.0, 0.0], 3).is_empty());
+
+        index.insert("b", &[0.0, 1.0]);
+        assert_eq!(index.search(&[0.1, 1.0], 3)[0].0, "b");
+    }
+}
@@ -0,0 +1,388 @@
+//! language.rs
+//!
+//! Language detection for news text and the per-language tokenization
+//! settings: one entry per Snowball algorithm in `rust-stemmers`, each with a
+//! stopword list. Detection goes by script first (Cyrillic, Greek, Arabic,
+//! Tamil), then by stopword hits and distinctive letters for Latin scripts.
+//! Undecided or short text is treated as English.
+
+use rust_stemmers::Algorithm;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Portuguese,
+    Dutch,
+    Swedish,
+    Norwegian,
+    Danish,
+    Finnish,
+    Hungarian,
+    Romanian,
+    Turkish,
+    Russian,
+    Greek,
+    Arabic,
+    Tamil,
+}
+
+/// Languages told apart by stopwords, in tie-break order.
+const LATIN: [Language; 14] = [
+    Language::English,
+    Language::German,
+    Language::French,
+    Language::Spanish,
+    Language::Italian,
+    Language::Portuguese,
+    Language::Dutch,
+    Language::Swedish,
+    Language::Norwegian,
+    Language::Danish,
+    Language::Finnish,
+    Language::Hungarian,
+    Language::Romanian,
+    Language::Turkish,
+];
+
+/// Stopword hits a non-English language needs to be picked.
+const MIN_HITS: usize = 2;
+
+impl Language {
+    /// ISO 639-1 code, as persisted on `events.language`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+            Language::Italian => "it",
+            Language::Portuguese => "pt",
+            Language::Dutch => "nl",
+            Language::Swedish => "sv",
+            Language::Norwegian => "no",
+            Language::Danish => "da",
+            Language::Finnish => "fi",
+            Language::Hungarian => "hu",
+            Language::Romanian => "ro",
+            Language::Turkish => "tr",
+            Language::Russian => "ru",
+            Language::Greek => "el",
+            Language::Arabic => "ar",
+            Language::Tamil => "ta",
+        }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        match self {
+            Language::English => Algorithm::English,
+            Language::German => Algorithm::German,
+            Language::French => Algorithm::French,
+            Language::Spanish => Algorithm::Spanish,
+            Language::Italian => Algorithm::Italian,
+            Language::Portuguese => Algorithm::Portuguese,
+            Language::Dutch => Algorithm::Dutch,
+            Language::Swedish => Algorithm::Swedish,
+            Language::Norwegian => Algorithm::Norwegian,
+            Language::Danish => Algorithm::Danish,
+            Language::Finnish => Algorithm::Finnish,
+            Language::Hungarian => Algorithm::Hungarian,
+            Language::Romanian => Algorithm::Romanian,
+            Language::Turkish => Algorithm::Turkish,
+            Language::Russian => Algorithm::Russian,
+            Language::Greek => Algorithm::Greek,
+            Language::Arabic => Algorithm::Arabic,
+            Language::Tamil => Algorithm::Tamil,
+        }
+    }
+
+    /// Whether ASCII folding keeps words recognisable (accents only).
+    pub fn is_latin_script(&self) -> bool {
+        LATIN.contains(self)
+    }
+
+    /// Common function words, lowercase, in the language's own script.
+    pub fn stopwords(&self) -> &'static [&'static str] {
+        match self {
+            Language::English => &[
+                "the", "a", "an", "of", "and", "or", "to", "in", "on", "for", "with", "by", "at",
+                "from", "is", "are", "was", "were", "be", "this", "that", "it", "as", "will",
+                "has", "have", "not", "but", "after", "its", "his", "her", "their", "over",
+            ],
+            Language::German => &[
+                "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "einen", "dem", "den",
+                "des", "mit", "von", "zu", "im", "auf", "für", "sich", "auch", "es", "wird",
+                "werden", "bei", "nach", "aus", "wie", "über", "dass", "sie", "er", "noch", "um",
+                "hat", "sind", "am", "vor",
+            ],
+            Language::French => &[
+                "le", "la", "les", "de", "des", "du", "un", "une", "et", "est", "en", "au", "aux",
+                "pour", "dans", "sur", "par", "que", "qui", "ne", "pas", "se", "ce", "il", "elle",
+                "avec", "plus", "son", "sa", "ses", "sont", "été", "après", "l", "d", "qu",
+            ],
+            Language::Spanish => &[
+                "el", "la", "los", "las", "de", "del", "y", "en", "que", "un", "una", "es", "por",
+                "con", "para", "se", "no", "al", "lo", "su", "sus", "como", "más", "pero", "ha",
+                "sobre", "este", "esta", "tras", "según", "fue", "son",
+            ],
+            Language::Italian => &[
+                "il", "lo", "la", "i", "gli", "le", "di", "del", "della", "dei", "che", "e", "è",
+                "un", "una", "per", "con", "non", "in", "su", "da", "al", "alla", "nel", "nella",
+                "sono", "ha", "più", "anche", "come", "dopo",
+            ],
+            Language::Portuguese => &[
+                "o", "a", "os", "as", "de", "do", "da", "dos", "das", "e", "é", "em", "no", "na",
+                "nos", "um", "uma", "que", "para", "com", "não", "por", "se", "ao", "mais", "foi",
+                "como", "sobre", "após", "ser", "são",
+            ],
+            Language::Dutch => &[
+                "de", "het", "een", "en", "van", "in", "is", "op", "te", "dat", "die", "met",
+                "voor", "niet", "zijn", "aan", "er", "ook", "als", "bij", "door", "naar", "om",
+                "wordt", "worden", "maar", "nog", "over", "uit",
+            ],
+            Language::Swedish => &[
+                "och", "att", "det", "som", "en", "ett", "på", "är", "av", "för", "med", "till",
+                "den", "inte", "har", "om", "de", "var", "men", "sig", "från", "vid", "kan",
+                "efter", "också", "ska", "nya",
+            ],
+            Language::Norwegian => &[
+                "og", "i", "det", "som", "en", "et", "på", "er", "av", "for", "med", "til", "den",
+                "ikke", "har", "om", "de", "var", "men", "seg", "fra", "ved", "kan", "etter",
+                "også", "skal", "nye", "ble",
+            ],
+            Language::Danish => &[
+                "og", "i", "det", "som", "en", "et", "på", "er", "af", "for", "med", "til", "den",
+                "ikke", "har", "om", "de", "var", "men", "sig", "fra", "ved", "kan", "efter",
+                "også", "skal", "nye", "blev",
+            ],
+            Language::Finnish => &[
+                "ja", "on", "ei", "että", "se", "hän", "oli", "ovat", "mutta", "kun", "myös",
+                "tai", "jo", "kuin", "niin", "joka", "mukaan", "sekä", "jälkeen", "nyt", "ole",
+                "olla",
+            ],
+            Language::Hungarian => &[
+                "a", "az", "és", "egy", "hogy", "nem", "is", "van", "meg", "de", "csak", "már",
+                "ki", "be", "el", "mint", "után", "szerint", "vagy", "volt", "lesz", "még", "ez",
+                "ezt",
+            ],
+            Language::Romanian => &[
+                "și", "în", "de", "la", "cu", "pe", "a", "un", "o", "care", "din", "nu", "este",
+                "pentru", "mai", "să", "se", "ce", "fost", "după", "sunt", "ca", "al", "ale",
+                "prin",
+            ],
+            Language::Turkish => &[
+                "ve", "bir", "bu", "da", "de", "için", "ile", "ne", "çok", "daha", "gibi",
+                "olarak", "sonra", "kadar", "ama", "değil", "her", "mi", "en", "o", "olan", "göre",
+            ],
+            Language::Russian => &[
+                "и",
+                "в",
+                "во",
+                "не",
+                "что",
+                "он",
+                "на",
+                "с",
+                "со",
+                "как",
+                "а",
+                "то",
+                "все",
+                "она",
+                "так",
+                "его",
+                "но",
+                "да",
+                "к",
+                "у",
+                "же",
+                "за",
+                "бы",
+                "по",
+                "только",
+                "ее",
+                "было",
+                "от",
+                "из",
+                "о",
+                "для",
+                "это",
+                "после",
+                "при",
+            ],
+            Language::Greek => &[
+                "και",
+                "το",
+                "η",
+                "ο",
+                "να",
+                "του",
+                "της",
+                "σε",
+                "με",
+                "τα",
+                "την",
+                "των",
+                "για",
+                "που",
+                "από",
+                "στο",
+                "στην",
+                "οι",
+                "είναι",
+                "θα",
+                "δεν",
+                "τον",
+            ],
+            Language::Arabic => &[
+                "في", "من", "على", "إلى", "عن", "أن", "مع", "هذا", "هذه", "التي", "الذي", "ما",
+                "لا", "كان", "و",
+            ],
+            Language::Tamil => &["ஒரு", "மற்றும்", "இந்த", "என்று", "அந்த", "இது", "உள்ள", "என்ற"],
+        }
+    }
+
+    /// Letters that only (or mostly) occur in this language.
+    fn distinctive_letters(&self) -> &'static [char] {
+        match self {
+            Language::German => &['ß'],
+            Language::French => &['ç', 'è', 'ê', 'œ'],
+            Language::Spanish => &['ñ', '¿', '¡'],
+            Language::Portuguese => &['ã', 'õ'],
+            Language::Swedish => &['å', 'ä'],
+            Language::Norwegian | Language::Danish => &['ø', 'æ'],
+            Language::Hungarian => &['ő', 'ű'],
+            Language::Romanian => &['ș', 'ț', 'ă'],
+            Language::Turkish => &['ğ', 'ş', 'ı'],
+            _ => &[],
+        }
+    }
+
+    /// Detect the language of `text` (any case).
+    pub fn detect(text: &str) -> Language {
+        let text = text.to_lowercase();
+
+        let (mut latin, mut cyrillic, mut greek, mut arabic, mut tamil) = (0, 0, 0, 0, 0);
+        for c in text.chars().filter(|c| c.is_alphabetic()) {
+            match c {
+                '\u{0400}'..='\u{04FF}' => cyrillic += 1,
+                '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => greek += 1,
+                '\u{0600}'..='\u{06FF}' => arabic += 1,
+                '\u{0B80}'..='\u{0BFF}' => tamil += 1,
+                _ => latin += 1,
+            }
+        }
+        let letters = latin + cyrillic + greek + arabic + tamil;
+        for (count, language) in [
+            (cyrillic, Language::Russian),
+            (greek, Language::Greek),
+            (arabic, Language::Arabic),
+            (tamil, Language::Tamil),
+        ] {
+            if count * 2 > letters {
+                return language;
+            }
+        }
+
+        let words: Vec<&str> = text
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|w| !w.is_empty())
+            .collect();
+        let score = |language: &Language| {
+            let stopwords = language.stopwords();
+            let hits = words.iter().filter(|w| stopwords.contains(w)).count();
+            let letters = language.distinctive_letters();
+            let marked = words.iter().filter(|w| w.contains(letters)).count();
+            hits + marked
+        };
+
+        let english = score(&Language::English);
+        let (best, best_score) =
+            LATIN[1..]
+                .iter()
+                .map(|l| (*l, score(l)))
+                .fold((Language::English, 0), |best, cur| {
+                    if cur.1 > best.1 { cur } else { best }
+                });
+        if best_score >= MIN_HITS && best_score > english {
+            best
+        } else {
+            Language::English
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        let cases = [
+            (
+                "Fed holds rates steady as inflation cools",
+                Language::English,
+            ),
+            ("Trump visits La Paz", Language::English),
+            (
+                "Die EZB senkt die Zinsen und warnt vor einer Rezession",
+                Language::German,
+            ),
+            (
+                "La BCE baisse ses taux pour la première fois depuis 2019",
+                Language::French,
+            ),
+            (
+                "El Banco de España prevé que la inflación baje en 2026",
+                Language::Spanish,
+            ),
+            (
+                "La Banca d'Italia: il PIL crescerà più del previsto",
+                Language::Italian,
+            ),
+            (
+                "Banco Central do Brasil mantém a taxa de juros",
+                Language::Portuguese,
+            ),
+            (
+                "De Nederlandsche Bank verwacht dat de inflatie daalt",
+                Language::Dutch,
+            ),
+            (
+                "Riksbanken sänker räntan och det är första gången",
+                Language::Swedish,
+            ),
+            (
+                "Norges Bank holder renten uendret, og den ble ikke hevet",
+                Language::Norwegian,
+            ),
+            (
+                "ЦБ России сохранил ключевую ставку на уровне 16%",
+                Language::Russian,
+            ),
+            (
+                "Η ΕΚΤ μειώνει τα επιτόκια για την ευρωζώνη",
+                Language::Greek,
+            ),
+            ("", Language::English),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(Language::detect(text), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_language_settings() {
+        for language in LATIN {
+            assert!(!language.stopwords().is_empty());
+            assert_eq!(language.code().len(), 2);
+        }
+        assert!(!Language::Russian.is_latin_script());
+        let stemmer = rust_stemmers::Stemmer::create(Language::German.algorithm());
+        assert_eq!(stemmer.stem("zinsen"), "zins");
+    }
+}
@@ -1,83 +1,484 @@
 use anyhow::Result;
 use tantivy::collector::TopDocs as TopDocsStruct;
-use tantivy::query::QueryParser;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{
+    BooleanQuery, ConstScoreQuery, Occur, Query, QueryParser, RangeQuery, TermQuery,
+};
 use tantivy::schema::*;
-use tantivy::{Index, IndexWriter};
+use tantivy::{DocAddress, Index, IndexReader, IndexWriter, ReloadPolicy};
 
+use crate::config::config::{AnnCfg, FusionMethod, MarketIndexCfg, RetrievalCfg};
+use crate::core::types::PolyMarketMarket;
+use crate::strategy::embedding_store::{EmbeddingStore, text_hash};
+use crate::strategy::hnsw::{Hnsw, HnswRebuild};
+use crate::strategy::language::Language;
+use crate::strategy::market_query::{MarketAttrs, MarketFilter, MarketQuery};
+use crate::strategy::types::RawCandidate;
 use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
-use std::collections::HashMap;
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{HashMap, HashSet};
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+/// Texts per call into the embedding model.
+const EMBED_BATCH_SIZE: usize = 64;
+
+/// A market as indexed for retrieval.
+#[derive(Debug, Clone, Default)]
+pub struct MarketDoc {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// Comma-separated tags
+    pub tags: String,
+    /// Timestamp in seconds
+    pub resolution_date: Option<i64>,
+    /// Parent Gamma event
+    pub event_id: Option<String>,
+    pub volume: Option<f64>,
+    pub liquidity: Option<f64>,
+    /// Open for trading (active, not closed or archived)
+    pub active: bool,
+}
+
+impl MarketDoc {
+    /// Index document for a Gamma market. The title is empty if it has no question.
+    pub fn from_market(market: &PolyMarketMarket) -> Self {
+        Self {
+            id: market.id.clone(),
+            title: market.question.as_deref().unwrap_or("").trim().to_string(),
+            description: market.description.clone().unwrap_or_default(),
+            tags: market.tag_labels().join(", "),
+            resolution_date: market.resolution_ts(),
+            event_id: market.event_id.clone(),
+            volume: market.volume.and_then(|v| v.to_f64()),
+            liquidity: market.liquidity.and_then(|v| v.to_f64()),
+            active: market.active && !market.closed && !market.archived,
+        }
+    }
+
+    /// Individual lowercased tags, as matched by tag filters.
+    fn tag_list(&self) -> Vec<String> {
+        self.tags
+            .split(',')
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect()
+    }
+
+    fn attrs(&self) -> MarketAttrs {
+        MarketAttrs {
+            tags: self.tag_list(),
+            resolution_date: self.resolution_date,
+            volume: self.volume,
+            liquidity: self.liquidity,
+            active: self.active,
+        }
+    }
+}
+
+struct IndexedDoc {
+    hash: u64,
+    attrs: MarketAttrs,
+}
+
+/// An embedding model behind its own lock, so that embedding never holds the
+/// `MarketIndex` lock. Clones share the model.
+#[derive(Clone)]
+pub struct Embedder {
+    model: Arc<Mutex<TextEmbedding>>,
+}
+
+impl Embedder {
+    fn new(model: EmbeddingModel) -> Result<Self> {
+        let model =
+            TextEmbedding::try_new(InitOptions::new(model).with_show_download_progress(true))?;
+        Ok(Self {
+            model: Arc::new(Mutex::new(model)),
+        })
+    }
+
+    pub fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let mut model = self
+            .model
+            .lock()
+            .map_err(|_| anyhow::anyhow!("embedding model lock poisoned"))?;
+        model.embed(texts, None)
+    }
+
+    pub fn embed_one(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed(vec![text.to_string()])?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("no embedding returned"))
+    }
+}
+
+/// Market embeddings to compute, planned under the index lock, computed
+/// without it and applied with `MarketIndex::apply_embeddings`.
+pub struct EmbeddingJob {
+    space: &'static str,
+    embedder: Embedder,
+    /// (market id, text hash, text)
+    items: Vec<(String, u64, String)>,
+    vectors: Vec<Vec<f32>>,
+}
+
+impl EmbeddingJob {
+    /// Embed in batches of `EMBED_BATCH_SIZE`, releasing the model between
+    /// batches so query embeddings are not held up for the whole job.
+    pub fn compute(&mut self) -> Result<()> {
+        self.vectors.clear();
+        for chunk in self.items.chunks(EMBED_BATCH_SIZE) {
+            let texts = chunk.iter().map(|(_, _, text)| text.clone()).collect();
+            self.vectors.extend(self.embedder.embed(texts)?);
+            metrics::counter!("market_index_embeddings_total", "result" => "computed", "space" => self.space)
+                .increment(chunk.len() as u64);
+        }
+        anyhow::ensure!(
+            self.vectors.len() == self.items.len(),
+            "embedding model returned {} vectors for {} markets",
+            self.vectors.len(),
+            self.items.len()
+        );
+        Ok(())
+    }
+}
+
+/// An ANN graph to rebuild without its tombstones, planned under the index
+/// lock, built without it and swapped in with `MarketIndex::apply_ann_rebuilds`.
+pub struct AnnRebuild {
+    space: &'static str,
+    rebuild: HnswRebuild,
+}
+
+impl AnnRebuild {
+    pub fn build(&mut self) {
+        let start = std::time::Instant::now();
+        self.rebuild.build();
+        metrics::histogram!("market_index_ann_rebuild_duration_seconds", "space" => self.space)
+            .record(start.elapsed().as_secs_f64());
+    }
+}
+
+/// One embedding model with its persisted vectors and ANN graph.
+struct VectorSpace {
+    name: &'static str,
+    model: Embedder,
+    embeddings: EmbeddingStore,
+    ann: Hnsw,
+}
+
+impl VectorSpace {
+    fn new(name: &'static str, model: EmbeddingModel, ann_cfg: &AnnCfg) -> Result<Self> {
+        Ok(Self {
+            name,
+            model: Embedder::new(model)?,
+            embeddings: EmbeddingStore::in_memory(),
+            ann: Hnsw::new(ann_cfg),
+        })
+    }
+
+    /// Adopt a store, dropping vectors without a document (they can never be
+    /// returned) and building the ANN graph from the rest.
+    fn load(
+        &mut self,
+        mut embeddings: EmbeddingStore,
+        docs: &HashMap<String, IndexedDoc>,
+    ) -> Result<()> {
+        let orphans: Vec<String> = embeddings
+            .iter()
+            .map(|(id, _)| id.clone())
+            .filter(|id| !docs.contains_key(id))
+            .collect();
+        for id in &orphans {
+            embeddings.remove(id)?;
+        }
+        for (id, stored) in embeddings.iter() {
+            self.ann.insert(id, &stored.vector);
+        }
+        self.embeddings = embeddings;
+        Ok(())
+    }
+
+    fn remove(&mut self, market_id: &str) -> Result<()> {
+        self.embeddings.remove(market_id)?;
+        self.ann.remove(market_id);
+        Ok(())
+    }
+
+    /// The markets whose title or description changed since they were last
+    /// embedded in this space. For repeated ids the last document wins.
+    fn plan(&self, docs: &[MarketDoc]) -> EmbeddingJob {
+        let mut items: Vec<(String, u64, String)> = Vec::new();
+        for market in docs.iter().rev() {
+            let embed_hash = text_hash(&[&market.title, &market.description]);
+            if items.iter().any(|(id, _, _)| *id == market.id) {
+                continue;
+            }
+            if self.embeddings.get(&market.id, embed_hash).is_some() {
+                metrics::counter!("market_index_embeddings_total", "result" => "cached", "space" => self.name)
+                    .increment(1);
+            } else {
+                // Combine title and description for better context
+                let text = format!("{} {}", market.title, market.description);
+                items.push((market.id.clone(), embed_hash, text));
+            }
+        }
+        items.reverse();
+        EmbeddingJob {
+            space: self.name,
+            embedder: self.model.clone(),
+            items,
+            vectors: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, job: EmbeddingJob, docs: &HashMap<String, IndexedDoc>) -> Result<()> {
+        for ((id, embed_hash, _), vector) in job.items.into_iter().zip(job.vectors) {
+            // Deleted while the embedding was computed
+            if !docs.contains_key(&id) {
+                continue;
+            }
+            self.ann.insert(&id, &vector);
+            self.embeddings.put(&id, embed_hash, vector)?;
+        }
+        Ok(())
+    }
+}
 
 pub struct MarketIndex {
     index: Index,
     writer: IndexWriter,
+    /// Reloaded explicitly after each commit rather than re-created per search
+    reader: IndexReader,
     // Fields
     title: Field,
     description: Field,
     tags: Field,
     id: Field,
     resolution_date: Field,
+    event_id: Field,
+    volume: Field,
+    liquidity: Field,
+    tag: Field,
+    active: Field,
+    doc_hash: Field,
+    /// Content hash and filterable attributes of each indexed document. The
+    /// hash skips unchanged markets; the attributes filter the vector search.
+    docs: HashMap<String, IndexedDoc>,
     // Semantic Search
-    embedding_model: TextEmbedding,
-    vectors: HashMap<String, Vec<f32>>,
+    semantic: VectorSpace,
+    /// Multilingual model for non-English queries (`multilingual: true`).
+    multilingual: Option<VectorSpace>,
 }
 
 impl MarketIndex {
+    /// In-memory index (fast, ephemeral).
+    #[allow(dead_code)]
     pub fn new() -> Result<Self> {
+        Self::open(&MarketIndexCfg::default())
+    }
+
+    /// On-disk index under `cfg.path` (tantivy directory + embedding log), so
+    /// restarts only re-embed new or changed markets. Falls back to RAM without a path.
+    pub fn open(cfg: &MarketIndexCfg) -> Result<Self> {
+        let Some(path) = &cfg.path else {
+            let index = Index::create_in_ram(Self::schema());
+            return Self::build(
+                index,
+                EmbeddingStore::in_memory(),
+                EmbeddingStore::in_memory(),
+                cfg,
+            );
+        };
+        let root = Path::new(path);
+        let tantivy_dir = root.join("tantivy");
+        std::fs::create_dir_all(&tantivy_dir)?;
+
+        let index = match Index::open_or_create(MmapDirectory::open(&tantivy_dir)?, Self::schema())
+        {
+            Ok(index) => index,
+            // Written by an older schema: rebuild the documents. Embeddings are
+            // keyed by text hash, so they survive and are not recomputed.
+            Err(tantivy::TantivyError::SchemaError(e)) => {
+                warn!(
+                    "MarketIndex schema changed ({}), rebuilding {}",
+                    e,
+                    tantivy_dir.display()
+                );
+                std::fs::remove_dir_all(&tantivy_dir)?;
+                std::fs::create_dir_all(&tantivy_dir)?;
+                Index::create_in_dir(&tantivy_dir, Self::schema())?
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let embeddings = EmbeddingStore::open(&root.join("embeddings.log"))?;
+        let multilingual = if cfg.multilingual {
+            EmbeddingStore::open(&root.join("embeddings-multilingual.log"))?
+        } else {
+            EmbeddingStore::in_memory()
+        };
+        Self::build(index, embeddings, multilingual, cfg)
+    }
+
+    fn schema() -> Schema {
         let mut schema_builder = Schema::builder();
 
         // Text fields are indexed and stored (if needed for retrieval, though we mostly need ID)
         // For BM25, we need them indexed.
-        let title = schema_builder.add_text_field("title", TEXT | STORED);
-        let description = schema_builder.add_text_field("description", TEXT);
-        let tags = schema_builder.add_text_field("tags", TEXT);
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.add_text_field("description", TEXT | STORED);
+        schema_builder.add_text_field("tags", TEXT | STORED);
 
         // ID is stored so we can map back to the market
-        let id = schema_builder.add_text_field("id", STRING | STORED);
+        schema_builder.add_text_field("id", STRING | STORED);
 
         // Resolution date (timestamp in seconds) - stored and indexed (FAST) for range queries
-        let resolution_date = schema_builder.add_i64_field("resolution_date", INDEXED | STORED);
+        schema_builder.add_i64_field("resolution_date", INDEXED | STORED);
+
+        // Gamma event grouping and market size
+        schema_builder.add_text_field("event_id", STRING | STORED);
+        schema_builder.add_f64_field("volume", INDEXED | STORED);
+        schema_builder.add_f64_field("liquidity", INDEXED | STORED);
+
+        // Exact lowercased tags (one value per tag) and tradeability, for filters
+        schema_builder.add_text_field("tag", STRING | STORED);
+        schema_builder.add_u64_field("active", INDEXED | STORED);
+
+        // Content hash, so a persisted index knows which markets are unchanged
+        schema_builder.add_u64_field("doc_hash", STORED);
 
-        let schema = schema_builder.build();
+        schema_builder.build()
+    }
 
-        // Create in-memory index for now (fast, ephemeral)
-        // In production, you might want a directory.
-        let index = Index::create_in_ram(schema.clone());
+    fn build(
+        index: Index,
+        embeddings: EmbeddingStore,
+        multilingual_embeddings: EmbeddingStore,
+        cfg: &MarketIndexCfg,
+    ) -> Result<Self> {
+        let schema = index.schema();
+        let field = |name: &str| schema.get_field(name);
 
         // 50MB buffer for indexing
         let writer = index.writer(50_000_000)?;
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
 
-        // Initialize Embedding Model
-        let embedding_model = TextEmbedding::try_new(
-            InitOptions::new(EmbeddingModel::AllMiniLML6V2).with_show_download_progress(true),
-        )?;
+        // Initialize Embedding Models
+        let semantic = VectorSpace::new("english", EmbeddingModel::AllMiniLML6V2, &cfg.ann)?;
+        let multilingual = if cfg.multilingual {
+            Some(VectorSpace::new(
+                "multilingual",
+                EmbeddingModel::ParaphraseMLMiniLML12V2,
+                &cfg.ann,
+            )?)
+        } else {
+            None
+        };
 
-        Ok(Self {
+        let mut market_index = Self {
+            title: field("title")?,
+            description: field("description")?,
+            tags: field("tags")?,
+            id: field("id")?,
+            resolution_date: field("resolution_date")?,
+            event_id: field("event_id")?,
+            volume: field("volume")?,
+            liquidity: field("liquidity")?,
+            tag: field("tag")?,
+            active: field("active")?,
+            doc_hash: field("doc_hash")?,
+            docs: HashMap::new(),
             index,
             writer,
-            title,
-            description,
-            tags,
-            id,
-            resolution_date,
-            embedding_model,
-            vectors: HashMap::new(),
-        })
+            reader,
+            semantic,
+            multilingual,
+        };
+
+        market_index.docs = market_index.load_docs()?;
+        market_index.semantic.load(embeddings, &market_index.docs)?;
+        if let Some(space) = &mut market_index.multilingual {
+            space.load(multilingual_embeddings, &market_index.docs)?;
+        }
+
+        if !market_index.docs.is_empty() {
+            info!(
+                "Opened MarketIndex with {} documents and {} embeddings ({} in ANN graph)",
+                market_index.docs.len(),
+                market_index.semantic.embeddings.len(),
+                market_index.semantic.ann.len()
+            );
+        }
+        Ok(market_index)
+    }
+
+    fn load_docs(&self) -> Result<HashMap<String, IndexedDoc>> {
+        let searcher = self.reader.searcher();
+        let mut docs = HashMap::new();
+
+        for (segment_ord, segment) in searcher.segment_readers().iter().enumerate() {
+            let alive = segment.alive_bitset();
+            for doc_id in 0..segment.max_doc() {
+                if alive.is_some_and(|a| a.is_deleted(doc_id)) {
+                    continue;
+                }
+                let doc: TantivyDocument =
+                    searcher.doc(DocAddress::new(segment_ord as u32, doc_id))?;
+                if let Some(hash) = doc.get_first(self.doc_hash).and_then(|v| v.as_u64()) {
+                    let attrs = self.attrs_from_doc(&doc);
+                    docs.insert(self.extract_id(&doc), IndexedDoc { hash, attrs });
+                }
+            }
+        }
+        Ok(docs)
     }
 
+    #[allow(dead_code)]
     pub fn contains(&self, market_id: &str) -> bool {
-        self.vectors.contains_key(market_id)
+        self.docs.contains_key(market_id)
     }
 
-    pub fn delete_market(&mut self, market_id: &str) -> Result<()> {
-        let term = Term::from_field_text(self.id, market_id);
-        self.writer.delete_term(term);
-        self.writer.commit()?;
+    /// Delete markets with a single commit. Returns the number that were indexed.
+    pub fn delete_markets(&mut self, market_ids: &[String]) -> Result<usize> {
+        let mut removed = 0;
+        for id in market_ids {
+            self.writer.delete_term(Term::from_field_text(self.id, id));
+            if self.docs.remove(id).is_some() {
+                removed += 1;
+            }
 
-        // Remove from semantic cache
-        self.vectors.remove(market_id);
-        Ok(())
+            // Remove from semantic cache
+            self.semantic.remove(id)?;
+            if let Some(space) = &mut self.multilingual {
+                space.remove(id)?;
+            }
+        }
+        if removed > 0 {
+            self.commit()?;
+        }
+        Ok(removed)
     }
 
+    /// Delete every indexed market not in `keep` (e.g. markets that closed while
+    /// we were offline). Returns the number removed.
+    pub fn retain_markets(&mut self, keep: &HashSet<String>) -> Result<usize> {
+        let stale: Vec<String> = self
+            .docs
+            .keys()
+            .filter(|id| !keep.contains(*id))
+            .cloned()
+            .collect();
+        self.delete_markets(&stale)
+    }
+
+    /// Insert or replace a single market; see `add_markets`.
+    #[allow(dead_code)]
     pub fn add_market(
         &mut self,
         market_id: &str,
@@ -86,44 +487,162 @@ impl MarketIndex {
         tags_text: &str,
         res_date: Option<i64>,
     ) -> Result<()> {
-        // First delete to ensure update/replace
-        let term = Term::from_field_text(self.id, market_id);
-        self.writer.delete_term(term);
+        self.add_markets(&[MarketDoc {
+            id: market_id.to_string(),
+            title: title_text.to_string(),
+            description: desc_text.to_string(),
+            tags: tags_text.to_string(),
+            resolution_date: res_date,
+            active: true,
+            ..Default::default()
+        }])?;
+        Ok(())
+    }
 
-        let mut doc = TantivyDocument::default();
-        doc.add_text(self.title, title_text);
-        doc.add_text(self.description, desc_text);
-        doc.add_text(self.tags, tags_text);
-        doc.add_text(self.id, market_id);
+    /// Insert or replace markets in bulk: one tantivy commit and batched
+    /// embedding for the whole slice. Unchanged markets are a no-op, and an
+    /// embedding is only recomputed when the title or description changed.
+    /// Returns the number of markets that changed.
+    pub fn add_markets(&mut self, docs: &[MarketDoc]) -> Result<usize> {
+        let changed = self.upsert_docs(docs)?;
+        let mut jobs = self.embedding_jobs(docs);
+        for job in &mut jobs {
+            job.compute()?;
+        }
+        self.apply_embeddings(jobs)?;
+        Ok(changed)
+    }
+
+    /// The searchable documents of `add_markets`, without their embeddings.
+    pub fn upsert_docs(&mut self, docs: &[MarketDoc]) -> Result<usize> {
+        let mut changed = 0;
+
+        for market in docs {
+            let opt_text = |v: Option<String>| v.unwrap_or_default();
+            let doc_hash = text_hash(&[
+                &market.title,
+                &market.description,
+                &market.tags,
+                &opt_text(market.resolution_date.map(|d| d.to_string())),
+                &opt_text(market.event_id.clone()),
+                &opt_text(market.volume.map(|v| v.to_string())),
+                &opt_text(market.liquidity.map(|v| v.to_string())),
+                if market.active { "active" } else { "inactive" },
+            ]);
 
-        if let Some(d) = res_date {
-            doc.add_i64(self.resolution_date, d);
+            if self.docs.get(&market.id).map(|d| d.hash) != Some(doc_hash) {
+                // First delete to ensure update/replace
+                let term = Term::from_field_text(self.id, &market.id);
+                self.writer.delete_term(term);
+
+                let mut doc = TantivyDocument::default();
+                doc.add_text(self.title, &market.title);
+                doc.add_text(self.description, &market.description);
+                doc.add_text(self.tags, &market.tags);
+                doc.add_text(self.id, &market.id);
+                doc.add_u64(self.doc_hash, doc_hash);
+
+                if let Some(d) = market.resolution_date {
+                    doc.add_i64(self.resolution_date, d);
+                }
+                if let Some(event_id) = &market.event_id {
+                    doc.add_text(self.event_id, event_id);
+                }
+                if let Some(v) = market.volume {
+                    doc.add_f64(self.volume, v);
+                }
+                if let Some(l) = market.liquidity {
+                    doc.add_f64(self.liquidity, l);
+                }
+                for tag in market.tag_list() {
+                    doc.add_text(self.tag, &tag);
+                }
+                doc.add_u64(self.active, market.active as u64);
+
+                self.writer.add_document(doc)?;
+                self.docs.insert(
+                    market.id.clone(),
+                    IndexedDoc {
+                        hash: doc_hash,
+                        attrs: market.attrs(),
+                    },
+                );
+                changed += 1;
+            }
         }
 
-        self.writer.add_document(doc)?;
-        // In a real high-throughput system, you wouldn't commit every time.
-        // But for low-frequency market additions, it's fine.
-        self.writer.commit()?;
+        if changed > 0 {
+            self.commit()?;
+        }
+        Ok(changed)
+    }
 
-        // Generate and store embedding
-        // Combine title and description for better context
-        let text_to_embed = format!("{} {}", title_text, desc_text);
-        let embeddings = self.embedding_model.embed(vec![text_to_embed], None)?;
-        if let Some(embedding) = embeddings.first() {
-            self.vectors
-                .insert(market_id.to_string(), embedding.clone());
+    /// Embeddings `docs` need in each vector space; compute them without
+    /// holding the index.
+    pub fn embedding_jobs(&self, docs: &[MarketDoc]) -> Vec<EmbeddingJob> {
+        std::iter::once(&self.semantic)
+            .chain(&self.multilingual)
+            .map(|space| space.plan(docs))
+            .filter(|job| !job.items.is_empty())
+            .collect()
+    }
+
+    /// ANN graphs whose tombstones outnumber their live vectors.
+    pub fn ann_rebuilds(&self) -> Vec<AnnRebuild> {
+        std::iter::once(&self.semantic)
+            .chain(&self.multilingual)
+            .filter(|space| space.ann.needs_rebuild())
+            .map(|space| AnnRebuild {
+                space: space.name,
+                rebuild: space.ann.rebuild_plan(),
+            })
+            .collect()
+    }
+
+    /// Swap in built graphs; one whose space changed meanwhile is dropped and
+    /// planned again on a later pass.
+    pub fn apply_ann_rebuilds(&mut self, rebuilds: Vec<AnnRebuild>) {
+        for rebuild in rebuilds {
+            let space = match &mut self.multilingual {
+                Some(space) if space.name == rebuild.space => space,
+                _ => &mut self.semantic,
+            };
+            if !space.ann.replace_with(rebuild.rebuild) {
+                warn!(
+                    "MarketIndex: {} graph changed during its rebuild, retrying later",
+                    space.name
+                );
+            }
+        }
+    }
+
+    /// Store computed embeddings of markets that are still indexed.
+    pub fn apply_embeddings(&mut self, jobs: Vec<EmbeddingJob>) -> Result<()> {
+        for job in jobs {
+            let space = match &mut self.multilingual {
+                Some(space) if space.name == job.space => space,
+                _ => &mut self.semantic,
+            };
+            space.apply(job, &self.docs)?;
         }
+        Ok(())
+    }
 
+    /// Make pending writes visible to searches.
+    fn commit(&mut self) -> Result<()> {
+        self.writer.commit()?;
+        self.reader.reload()?;
         Ok(())
     }
 
+    /// BM25 search over title, description and tags, restricted by `filter`.
     pub fn search(
         &self,
         query_tokens: &[String],
+        filter: &MarketFilter,
         limit: usize,
     ) -> Result<Vec<crate::strategy::types::RawCandidate>> {
-        let reader = self.index.reader()?;
-        let searcher = reader.searcher();
+        let searcher = self.reader.searcher();
 
         // Construct a query from tokens
         // Simple approach: OR query of all tokens
@@ -132,7 +651,11 @@ impl MarketIndex {
         let mut query_parser =
             QueryParser::for_index(&self.index, vec![self.title, self.description, self.tags]);
         query_parser.set_conjunction_by_default();
-        let query = query_parser.parse_query(&query_str)?;
+        let text_query = query_parser.parse_query(&query_str)?;
+
+        let mut clauses = vec![(Occur::Must, text_query)];
+        clauses.extend(self.filter_clauses(filter));
+        let query = BooleanQuery::new(clauses);
 
         let collector = TopDocsStruct::with_limit(limit);
         let top_docs = searcher.search(&query, &collector)?;
@@ -140,57 +663,113 @@ impl MarketIndex {
         let mut results = Vec::new();
         for (score, doc_address) in top_docs {
             let retrieved_doc: TantivyDocument = searcher.doc(doc_address)?;
-            let id_val = self.extract_id(&retrieved_doc);
-            let (title_val, desc_val, tags_vec, res_date_val) =
-                self.extract_metadata(&retrieved_doc);
-
             results.push(crate::strategy::types::RawCandidate {
-                market_id: id_val,
                 bm25_score: score,
-                title: title_val,
-                description: desc_val,
-                tags: tags_vec,
-                resolution_date: res_date_val,
+                ..self.candidate_from_doc(&retrieved_doc)
             });
         }
 
         Ok(results)
     }
 
+    /// `filter` as tantivy clauses. Required clauses score 0 so they don't skew BM25.
+    fn filter_clauses(&self, filter: &MarketFilter) -> Vec<(Occur, Box<dyn Query>)> {
+        let term =
+            |t: Term| -> Box<dyn Query> { Box::new(TermQuery::new(t, IndexRecordOption::Basic)) };
+        let field_name = |f: Field| self.index.schema().get_field_name(f).to_string();
+        let min_bound = |min: Option<f64>| min.map_or(Bound::Unbounded, Bound::Included);
+        let mut must: Vec<Box<dyn Query>> = Vec::new();
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+
+        if !filter.include_tags.is_empty() {
+            let any_of = filter
+                .include_tags
+                .iter()
+                .map(|t| (Occur::Should, term(Term::from_field_text(self.tag, t))))
+                .collect();
+            must.push(Box::new(BooleanQuery::new(any_of)));
+        }
+        for t in &filter.exclude_tags {
+            clauses.push((Occur::MustNot, term(Term::from_field_text(self.tag, t))));
+        }
+        for id in &filter.exclude_market_ids {
+            clauses.push((Occur::MustNot, term(Term::from_field_text(self.id, id))));
+        }
+        if filter.min_liquidity.is_some() {
+            must.push(Box::new(RangeQuery::new_f64_bounds(
+                field_name(self.liquidity),
+                min_bound(filter.min_liquidity),
+                Bound::Unbounded,
+            )));
+        }
+        if filter.min_volume.is_some() {
+            must.push(Box::new(RangeQuery::new_f64_bounds(
+                field_name(self.volume),
+                min_bound(filter.min_volume),
+                Bound::Unbounded,
+            )));
+        }
+        if filter.resolves_after.is_some() || filter.resolves_before.is_some() {
+            must.push(Box::new(RangeQuery::new_i64_bounds(
+                field_name(self.resolution_date),
+                filter
+                    .resolves_after
+                    .map_or(Bound::Unbounded, Bound::Included),
+                filter
+                    .resolves_before
+                    .map_or(Bound::Unbounded, Bound::Included),
+            )));
+        }
+        if filter.active_only {
+            must.push(term(Term::from_field_u64(self.active, 1)));
+        }
+
+        clauses.extend(must.into_iter().map(|q| {
+            (
+                Occur::Must,
+                Box::new(ConstScoreQuery::new(q, 0.0)) as Box<dyn Query>,
+            )
+        }));
+        clauses
+    }
+
+    /// Nearest markets by embedding among those passing `filter`. The filter is
+    /// evaluated during the HNSW traversal, so `limit` results are returned
+    /// whenever enough markets match. Non-English text is embedded in the
+    /// multilingual space when it is enabled.
     pub fn search_semantic(
-        &mut self,
+        &self,
+        language: Language,
         query_text: &str,
+        filter: &MarketFilter,
         limit: usize,
     ) -> Result<Vec<crate::strategy::types::RawCandidate>> {
-        let embeddings = self.embedding_model.embed(vec![query_text], None)?;
+        let space = match &self.multilingual {
+            Some(space) if language != Language::English => space,
+            _ => &self.semantic,
+        };
+        metrics::counter!("market_index_semantic_searches_total", "space" => space.name)
+            .increment(1);
+        let embeddings = space.model.embed(vec![query_text.to_string()])?;
         let query_embedding = match embeddings.first() {
             Some(e) => e,
             None => return Ok(Vec::new()),
         };
 
-        // Brute-force Cosine Similarity (fast enough for <10k items)
-        let mut scored_markets: Vec<(String, f32)> = self
-            .vectors
-            .iter()
-            .map(|(id, vec)| {
-                let score = cosine_similarity(query_embedding, vec);
-                (id.clone(), score)
-            })
-            .collect();
-
-        // Sort by score descending
-        scored_markets.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
+        // Approximate nearest neighbours (HNSW) by cosine similarity, best first.
         // Filter by threshold (e.g., 0.35) to avoid irrelevant matches
         let threshold = 0.35;
-        let top_k = scored_markets
+        let docs = &self.docs;
+        let top_k = space
+            .ann
+            .search_filtered(query_embedding, limit, |id| {
+                filter.is_empty() || docs.get(id).is_some_and(|d| filter.matches(id, &d.attrs))
+            })
             .into_iter()
-            .filter(|(_, score)| *score >= threshold)
-            .take(limit);
+            .filter(|(_, score)| *score >= threshold);
 
         // Retrieve metadata from Tantivy (or we could store it in memory too, but Tantivy is fine)
-        let reader = self.index.reader()?;
-        let searcher = reader.searcher();
+        let searcher = self.reader.searcher();
         let term_query_parser = QueryParser::for_index(&self.index, vec![self.id]);
 
         let mut results = Vec::new();
@@ -203,16 +782,10 @@ impl MarketIndex {
 
             if let Some((_, doc_addr)) = top_docs.first() {
                 let retrieved_doc: TantivyDocument = searcher.doc(*doc_addr)?;
-                let (title_val, desc_val, tags_vec, res_date_val) =
-                    self.extract_metadata(&retrieved_doc);
-
                 results.push(crate::strategy::types::RawCandidate {
                     market_id: id,
-                    bm25_score: score, // Using cosine similarity as "score"
-                    title: title_val,
-                    description: desc_val,
-                    tags: tags_vec,
-                    resolution_date: res_date_val,
+                    semantic_score: score,
+                    ..self.candidate_from_doc(&retrieved_doc)
                 });
             }
         }
@@ -220,6 +793,47 @@ impl MarketIndex {
         Ok(results)
     }
 
+    /// Model for embedding news texts for story clustering: the multilingual
+    /// one when enabled, so stories match across languages. Usable without
+    /// holding the index.
+    pub fn news_embedder(&self) -> Embedder {
+        self.multilingual
+            .as_ref()
+            .unwrap_or(&self.semantic)
+            .model
+            .clone()
+    }
+
+    /// Run both searches with the query's filters and fuse them so that markets
+    /// found by both rank higher. Results carry the component scores and are
+    /// sorted by `fused_score`. A failing search degrades to the other one.
+    pub fn search_hybrid(
+        &self,
+        query: &MarketQuery,
+        cfg: &RetrievalCfg,
+    ) -> Result<Vec<RawCandidate>> {
+        let lexical = self.search(&query.tokens, &query.filter, cfg.limit);
+        let semantic = self.search_semantic(query.language, &query.text, &query.filter, cfg.limit);
+
+        let (lexical, semantic) = match (lexical, semantic) {
+            (Err(l), Err(s)) => {
+                return Err(l.context(format!("semantic search also failed: {:#}", s)));
+            }
+            (l, s) => (
+                l.unwrap_or_else(|e| {
+                    warn!("BM25 search failed: {:#}", e);
+                    Vec::new()
+                }),
+                s.unwrap_or_else(|e| {
+                    warn!("Semantic search failed: {:#}", e);
+                    Vec::new()
+                }),
+            ),
+        };
+
+        Ok(fuse(lexical, semantic, cfg))
+    }
+
     fn extract_id(&self, doc: &TantivyDocument) -> String {
         doc.get_first(self.id)
             .and_then(|v| v.as_str())
@@ -227,40 +841,96 @@ impl MarketIndex {
             .to_string()
     }
 
-    fn extract_metadata(
-        &self,
-        doc: &TantivyDocument,
-    ) -> (String, String, Vec<String>, Option<i64>) {
-        let title_val = doc
-            .get_first(self.title)
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let desc_val = doc
-            .get_first(self.description)
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let tags_val = doc
-            .get_first(self.tags)
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let res_date_val = doc.get_first(self.resolution_date).and_then(|v| v.as_i64());
-        let tags_vec: Vec<String> = tags_val.split(',').map(|s| s.trim().to_string()).collect();
-        (title_val, desc_val, tags_vec, res_date_val)
+    fn attrs_from_doc(&self, doc: &TantivyDocument) -> MarketAttrs {
+        MarketAttrs {
+            tags: doc
+                .get_all(self.tag)
+                .filter_map(|v| v.as_str())
+                .map(|t| t.to_string())
+                .collect(),
+            resolution_date: doc.get_first(self.resolution_date).and_then(|v| v.as_i64()),
+            volume: doc.get_first(self.volume).and_then(|v| v.as_f64()),
+            liquidity: doc.get_first(self.liquidity).and_then(|v| v.as_f64()),
+            active: doc.get_first(self.active).and_then(|v| v.as_u64()) == Some(1),
+        }
+    }
+
+    /// Stored fields of a document as an unscored candidate.
+    fn candidate_from_doc(&self, doc: &TantivyDocument) -> RawCandidate {
+        let text = |field: Field| {
+            doc.get_first(field)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        let event_id = text(self.event_id);
+        RawCandidate {
+            market_id: self.extract_id(doc),
+            title: text(self.title),
+            description: text(self.description),
+            tags: text(self.tags)
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            resolution_date: doc.get_first(self.resolution_date).and_then(|v| v.as_i64()),
+            event_id: (!event_id.is_empty()).then_some(event_id),
+            volume: doc.get_first(self.volume).and_then(|v| v.as_f64()),
+            liquidity: doc.get_first(self.liquidity).and_then(|v| v.as_f64()),
+            ..Default::default()
+        }
     }
 }
 
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    let dot_product: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-    if norm_a == 0.0 || norm_b == 0.0 {
-        0.0
-    } else {
-        dot_product / (norm_a * norm_b)
+/// Merge ranked lexical and semantic results (each sorted best-first) by market ID.
+fn fuse(
+    lexical: Vec<RawCandidate>,
+    semantic: Vec<RawCandidate>,
+    cfg: &RetrievalCfg,
+) -> Vec<RawCandidate> {
+    let max_bm25 = lexical.iter().map(|c| c.bm25_score).fold(0.0_f32, f32::max);
+
+    let mut merged: HashMap<String, RawCandidate> = HashMap::new();
+    let mut in_both = 0;
+
+    for (rank, mut c) in lexical.into_iter().enumerate() {
+        c.fused_score = match cfg.fusion {
+            FusionMethod::Rrf => cfg.bm25_weight / (cfg.rrf_k + rank as f32 + 1.0),
+            FusionMethod::Weighted if max_bm25 > 0.0 => cfg.bm25_weight * c.bm25_score / max_bm25,
+            FusionMethod::Weighted => 0.0,
+        };
+        merged.insert(c.market_id.clone(), c);
     }
+
+    for (rank, c) in semantic.into_iter().enumerate() {
+        let contribution = match cfg.fusion {
+            FusionMethod::Rrf => cfg.semantic_weight / (cfg.rrf_k + rank as f32 + 1.0),
+            FusionMethod::Weighted => cfg.semantic_weight * c.semantic_score.max(0.0),
+        };
+        match merged.get_mut(&c.market_id) {
+            Some(existing) => {
+                in_both += 1;
+                existing.semantic_score = c.semantic_score;
+                existing.fused_score += contribution;
+            }
+            None => {
+                let mut c = c;
+                c.fused_score = contribution;
+                merged.insert(c.market_id.clone(), c);
+            }
+        }
+    }
+
+    metrics::counter!("strategy_retrieval_overlap_total").increment(in_both as u64);
+
+    let mut fused: Vec<RawCandidate> = merged.into_values().collect();
+    fused.sort_by(|a, b| {
+        b.fused_score
+            .partial_cmp(&a.fused_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.market_id.cmp(&b.market_id))
+    });
+    fused
 }
 
 #[cfg(test)]
@@ -294,23 +964,59 @@ mod tests {
         )?;
 
         // Search for "Fed"
-        let results = index.search(&vec!["Fed".to_string()], 10)?;
+        let results = index.search(&vec!["Fed".to_string()], &MarketFilter::default(), 10)?;
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].market_id, "1");
 
         // Search for "rates"
-        let results = index.search(&vec!["rates".to_string()], 10)?;
+        let results = index.search(&vec!["rates".to_string()], &MarketFilter::default(), 10)?;
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].market_id, "1");
 
         // Search for "crypto"
-        let results = index.search(&vec!["crypto".to_string()], 10)?;
+        let results = index.search(&vec!["crypto".to_string()], &MarketFilter::default(), 10)?;
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].market_id, "2");
 
         Ok(())
     }
 
+    #[test]
+    fn test_market_metadata_roundtrip() -> Result<()> {
+        let market: PolyMarketMarket = serde_json::from_str(
+            r#"{
+                "id": "7",
+                "question": "Will the Fed cut rates in March?",
+                "endDate": "2026-03-18T18:00:00Z",
+                "volume": "250000.5",
+                "liquidity": 12000,
+                "category": "Economics",
+                "tags": [{"label": "Fed"}],
+                "eventId": "903"
+            }"#,
+        )?;
+        let mut index = MarketIndex::new()?;
+        index.add_markets(&[MarketDoc::from_market(&market)])?;
+
+        let results = index.search(&["fed".to_string()], &MarketFilter::default(), 10)?;
+        assert_eq!(results.len(), 1);
+        let c = &results[0];
+        assert_eq!(c.tags, vec!["economics", "fed"]);
+        assert_eq!(c.resolution_date, Some(1773856800));
+        assert_eq!(c.event_id.as_deref(), Some("903"));
+        assert_eq!(c.volume, Some(250000.5));
+        assert_eq!(c.liquidity, Some(12000.0));
+
+        // Tags are searchable too
+        assert_eq!(
+            index
+                .search(&["economics".to_string()], &MarketFilter::default(), 10)?
+                .len(),
+            1
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_semantic_search() -> Result<()> {
         let mut index: MarketIndex = MarketIndex::new()?;
@@ -331,15 +1037,165 @@ mod tests {
         )?;
 
         // Semantic search for "interest rate increase" (not in text, but semantically related to Fed rates)
-        let results = index.search_semantic("interest rate increase", 1)?;
+        let results = index.search_semantic(
+            Language::English,
+            "interest rate increase",
+            &MarketFilter::default(),
+            1,
+        )?;
         assert!(!results.is_empty());
         assert_eq!(results[0].market_id, "1");
 
         // Semantic search for "cryptocurrency surge"
-        let results = index.search_semantic("cryptocurrency surge", 1)?;
+        let results = index.search_semantic(
+            Language::English,
+            "cryptocurrency surge",
+            &MarketFilter::default(),
+            1,
+        )?;
         assert!(!results.is_empty());
         assert_eq!(results[0].market_id, "2");
 
         Ok(())
     }
+
+    fn lexical(id: &str, score: f32) -> RawCandidate {
+        RawCandidate {
+            market_id: id.to_string(),
+            bm25_score: score,
+            ..Default::default()
+        }
+    }
+
+    fn semantic(id: &str, score: f32) -> RawCandidate {
+        RawCandidate {
+            market_id: id.to_string(),
+            semantic_score: score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rrf_fusion_boosts_agreement() {
+        let cfg = RetrievalCfg::default();
+        // "b" is second in both lists, "a" and "c" top only one list each
+        let fused = fuse(
+            vec![lexical("a", 9.0), lexical("b", 5.0)],
+            vec![semantic("c", 0.8), semantic("b", 0.7)],
+            &cfg,
+        );
+
+        assert_eq!(fused.len(), 3);
+        assert_eq!(fused[0].market_id, "b");
+        assert_eq!(fused[0].bm25_score, 5.0);
+        assert_eq!(fused[0].semantic_score, 0.7);
+        assert!((fused[0].fused_score - 2.0 / 62.0).abs() < 1e-6);
+        // Ties broken by market ID
+        assert_eq!(fused[1].market_id, "a");
+        assert_eq!(fused[2].market_id, "c");
+    }
+
+    #[test]
+    fn test_weighted_fusion() {
+        let cfg = RetrievalCfg {
+            fusion: FusionMethod::Weighted,
+            bm25_weight: 0.5,
+            semantic_weight: 1.0,
+            ..Default::default()
+        };
+        let fused = fuse(
+            vec![lexical("a", 10.0), lexical("b", 5.0)],
+            vec![semantic("b", 0.9), semantic("c", 0.6)],
+            &cfg,
+        );
+
+        let score = |id: &str| {
+            fused
+                .iter()
+                .find(|c| c.market_id == id)
+                .unwrap()
+                .fused_score
+        };
+        assert!((score("a") - 0.5).abs() < 1e-6);
+        assert!((score("b") - (0.25 + 0.9)).abs() < 1e-6);
+        assert!((score("c") - 0.6).abs() < 1e-6);
+        assert_eq!(fused[0].market_id, "b");
+    }
+
+    #[test]
+    fn test_hybrid_search() -> Result<()> {
+        let mut index: MarketIndex = MarketIndex::new()?;
+        index.add_market(
+            "1",
+            "Fed rates decision",
+            "Will the Fed hike rates?",
+            "fed, rates, macro",
+            None,
+        )?;
+        index.add_market(
+            "2",
+            "Bitcoin price",
+            "Will BTC hit 100k?",
+            "crypto, btc",
+            None,
+        )?;
+
+        let results = index.search_hybrid(
+            &MarketQuery::new(&["fed".to_string()], "Fed signals interest rate hike"),
+            &RetrievalCfg::default(),
+        )?;
+        assert_eq!(results[0].market_id, "1");
+        assert!(results[0].bm25_score > 0.0);
+        assert!(results[0].semantic_score > 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_filtered_search() -> Result<()> {
+        let market =
+            |id: &str, tags: &str, liquidity: f64, resolution: i64, active: bool| MarketDoc {
+                id: id.to_string(),
+                title: "Will the Fed cut interest rates?".to_string(),
+                tags: tags.to_string(),
+                resolution_date: Some(resolution),
+                liquidity: Some(liquidity),
+                active,
+                ..Default::default()
+            };
+        let mut index = MarketIndex::new()?;
+        index.add_markets(&[
+            market("ok", "economics, fed", 5_000.0, 2_000, true),
+            market("illiquid", "economics, fed", 10.0, 2_000, true),
+            market("too_late", "economics, fed", 5_000.0, 9_000, true),
+            market("sports", "sports, fed", 5_000.0, 2_000, true),
+            market("closed", "economics, fed", 5_000.0, 2_000, false),
+            market("held", "economics, fed", 5_000.0, 2_000, true),
+            market("uncategorized", "fed", 5_000.0, 2_000, true),
+        ])?;
+
+        let query = MarketQuery::new(&["fed".to_string()], "Fed rate cut")
+            .include_tags(["Economics"])
+            .exclude_tags(["sports"])
+            .min_liquidity(1_000.0)
+            .resolves_between(Some(1_000), Some(5_000))
+            .active_only()
+            .exclude_markets(["held".to_string()]);
+
+        // Both retrievers apply the filter, so the only match fills each window
+        let lexical = index.search(&query.tokens, &query.filter, 1)?;
+        let semantic = index.search_semantic(query.language, &query.text, &query.filter, 1)?;
+        assert_eq!(lexical.len(), 1);
+        assert_eq!(lexical[0].market_id, "ok");
+        assert_eq!(semantic.len(), 1);
+        assert_eq!(semantic[0].market_id, "ok");
+
+        let hybrid = index.search_hybrid(&query, &RetrievalCfg::default())?;
+        let ids: Vec<_> = hybrid.iter().map(|c| c.market_id.as_str()).collect();
+        assert_eq!(ids, vec!["ok"]);
+
+        // Unfiltered, every market matches
+        let all = index.search(&query.tokens, &MarketFilter::default(), 10)?;
+        assert_eq!(all.len(), 7);
+        Ok(())
+    }
 }
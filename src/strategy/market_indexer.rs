@@ -0,0 +1,237 @@
+use crate::config::config::MarketIndexCfg;
+use crate::strategy::market_index::{MarketDoc, MarketIndex};
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+pub type SharedMarketIndex = Arc<Mutex<MarketIndex>>;
+
+#[derive(Debug, Clone)]
+pub enum IndexOp {
+    Upsert(MarketDoc),
+    Delete(String),
+    /// Drop every indexed market not in the set.
+    Retain(HashSet<String>),
+}
+
+/// Applies index updates in the background so the strategy loop never blocks
+/// on embedding or tantivy commits. Ops are coalesced into batches of up to
+/// `batchSize` (or whatever arrives within `flushInterval`) and applied on a
+/// blocking thread with one commit per batch. Searches only wait for the
+/// document writes of a batch, never for its embeddings.
+pub struct MarketIndexer {
+    index: SharedMarketIndex,
+    rx: mpsc::UnboundedReceiver<IndexOp>,
+    batch_size: usize,
+    flush_interval: Duration,
+    shutdown: CancellationToken,
+}
+
+impl MarketIndexer {
+    pub fn spawn(
+        index: SharedMarketIndex,
+        cfg: &MarketIndexCfg,
+        shutdown: CancellationToken,
+    ) -> mpsc::UnboundedSender<IndexOp> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let indexer = Self {
+            index,
+            rx,
+            batch_size: cfg.batch_size.max(1),
+            flush_interval: cfg.flush_interval,
+            shutdown,
+        };
+        tokio::spawn(indexer.run());
+        tx
+    }
+
+    async fn run(mut self) {
+        loop {
+            let first = tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                op = self.rx.recv() => match op {
+                    Some(op) => op,
+                    None => break,
+                },
+            };
+
+            let mut batch = vec![first];
+            let deadline = tokio::time::Instant::now() + self.flush_interval;
+            while batch.len() < self.batch_size {
+                match tokio::time::timeout_at(deadline, self.rx.recv()).await {
+                    Ok(Some(op)) => batch.push(op),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+
+            let start = std::time::Instant::now();
+            let ops = batch.len();
+            let index = self.index.clone();
+            match tokio::task::spawn_blocking(move || apply_ops(&index, batch)).await {
+                Ok(Ok(changed)) => {
+                    metrics::counter!("market_index_batches_total", "status" => "success")
+                        .increment(1);
+                    if changed > 0 {
+                        info!(
+                            "MarketIndex: applied {} ops ({} markets changed) in {:?}",
+                            ops,
+                            changed,
+                            start.elapsed()
+                        );
+                    }
+                }
+                Ok(Err(e)) => {
+                    metrics::counter!("market_index_batches_total", "status" => "error")
+                        .increment(1);
+                    error!("MarketIndex batch failed: {:#}", e);
+                }
+                Err(e) => {
+                    metrics::counter!("market_index_batches_total", "status" => "error")
+                        .increment(1);
+                    error!("MarketIndex batch task panicked: {}", e);
+                }
+            }
+            metrics::histogram!("market_index_batch_size").record(ops as f64);
+            metrics::histogram!("market_index_batch_duration_seconds")
+                .record(start.elapsed().as_secs_f64());
+        }
+        info!("MarketIndexer stopped");
+    }
+}
+
+/// Apply ops in order, grouping consecutive upserts and deletes into bulk calls.
+/// Returns the number of markets added, updated or removed.
+///
+/// Documents are written under the index lock, but embeddings are computed
+/// after releasing it and stored under a second, short lock, so searches are
+/// never blocked by the embedding model. ANN graphs left mostly tombstones
+/// are rebuilt the same way: planned and swapped in under the lock, built
+/// without it.
+fn apply_ops(index: &Mutex<MarketIndex>, ops: Vec<IndexOp>) -> Result<usize> {
+    let lock = || {
+        index
+            .lock()
+            .map_err(|_| anyhow::anyhow!("MarketIndex lock poisoned"))
+    };
+
+    let (changed, mut jobs) = {
+        let mut index = lock()?;
+        let mut changed = 0;
+        let mut upserted: Vec<MarketDoc> = Vec::new();
+        let mut upserts: Vec<MarketDoc> = Vec::new();
+        let mut deletes: Vec<String> = Vec::new();
+
+        for op in ops {
+            match op {
+                IndexOp::Upsert(doc) => {
+                    if !deletes.is_empty() {
+                        changed += index.delete_markets(&std::mem::take(&mut deletes))?;
+                    }
+                    upserts.push(doc);
+                }
+                IndexOp::Delete(id) => {
+                    if !upserts.is_empty() {
+                        changed += index.upsert_docs(&upserts)?;
+                        upserted.append(&mut upserts);
+                    }
+                    deletes.push(id);
+                }
+                IndexOp::Retain(keep) => {
+                    if !upserts.is_empty() {
+                        changed += index.upsert_docs(&upserts)?;
+                        upserted.append(&mut upserts);
+                    }
+                    if !deletes.is_empty() {
+                        changed += index.delete_markets(&std::mem::take(&mut deletes))?;
+                    }
+                    let removed = index.retain_markets(&keep)?;
+                    if removed > 0 {
+                        info!("MarketIndex: Removed {} inactive markets", removed);
+                    }
+                    changed += removed;
+                }
+            }
+        }
+
+        if !upserts.is_empty() {
+            changed += index.upsert_docs(&upserts)?;
+            upserted.append(&mut upserts);
+        }
+        if !deletes.is_empty() {
+            changed += index.delete_markets(&deletes)?;
+        }
+        (changed, index.embedding_jobs(&upserted))
+    };
+
+    for job in &mut jobs {
+        job.compute()?;
+    }
+    if !jobs.is_empty() {
+        lock()?.apply_embeddings(jobs)?;
+    }
+
+    let mut rebuilds = lock()?.ann_rebuilds();
+    if !rebuilds.is_empty() {
+        for rebuild in &mut rebuilds {
+            rebuild.build();
+        }
+        lock()?.apply_ann_rebuilds(rebuilds);
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(id: &str, title: &str) -> MarketDoc {
+        MarketDoc {
+            id: id.to_string(),
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_indexer_applies_ops_in_order() -> Result<()> {
+        let index: SharedMarketIndex = Arc::new(Mutex::new(MarketIndex::new()?));
+        let shutdown = CancellationToken::new();
+        let cfg = MarketIndexCfg {
+            batch_size: 2,
+            flush_interval: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let tx = MarketIndexer::spawn(index.clone(), &cfg, shutdown.clone());
+
+        tx.send(IndexOp::Upsert(doc("1", "Fed rates decision")))?;
+        tx.send(IndexOp::Upsert(doc("2", "Bitcoin price")))?;
+        tx.send(IndexOp::Delete("1".to_string()))?;
+        tx.send(IndexOp::Upsert(doc("3", "US Election")))?;
+        tx.send(IndexOp::Retain(["3".to_string()].into_iter().collect()))?;
+
+        // Wait for the background batches to land
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let idx = index.lock().unwrap();
+            if idx.contains("3") && !idx.contains("2") {
+                break;
+            }
+        }
+
+        let idx = index.lock().unwrap();
+        assert!(!idx.contains("1"));
+        assert!(!idx.contains("2"));
+        assert!(idx.contains("3"));
+        assert_eq!(
+            idx.search(&["election".to_string()], &Default::default(), 10)?
+                .len(),
+            1
+        );
+        shutdown.cancel();
+        Ok(())
+    }
+}
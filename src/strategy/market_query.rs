@@ -0,0 +1,242 @@
+use crate::strategy::language::Language;
+use std::collections::HashSet;
+
+/// Structured restrictions on which markets a search may return. They are
+/// pushed down into both the tantivy query and the HNSW traversal, so the
+/// retrieval window is spent only on tradeable markets.
+///
+/// Tags are compared lowercased. A market with an unknown liquidity, volume
+/// or resolution date fails any bound set on that attribute.
+#[derive(Debug, Clone, Default)]
+pub struct MarketFilter {
+    /// Keep markets with at least one of these tags (or categories).
+    pub include_tags: Vec<String>,
+    /// Drop markets with any of these tags (or categories).
+    pub exclude_tags: Vec<String>,
+    pub min_liquidity: Option<f64>,
+    pub min_volume: Option<f64>,
+    /// Inclusive bounds on the resolution timestamp (seconds).
+    pub resolves_after: Option<i64>,
+    pub resolves_before: Option<i64>,
+    pub active_only: bool,
+    pub exclude_market_ids: HashSet<String>,
+}
+
+/// The filterable attributes of an indexed market.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketAttrs {
+    pub tags: Vec<String>,
+    pub resolution_date: Option<i64>,
+    pub volume: Option<f64>,
+    pub liquidity: Option<f64>,
+    pub active: bool,
+}
+
+impl MarketFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include_tags.is_empty()
+            && self.exclude_tags.is_empty()
+            && self.min_liquidity.is_none()
+            && self.min_volume.is_none()
+            && self.resolves_after.is_none()
+            && self.resolves_before.is_none()
+            && !self.active_only
+            && self.exclude_market_ids.is_empty()
+    }
+
+    /// In-memory equivalent of the tantivy filter clauses built by `MarketIndex`.
+    pub fn matches(&self, market_id: &str, attrs: &MarketAttrs) -> bool {
+        let at_least = |value: Option<f64>, min: Option<f64>| match min {
+            Some(min) => value.is_some_and(|v| v >= min),
+            None => true,
+        };
+
+        if self.exclude_market_ids.contains(market_id) || (self.active_only && !attrs.active) {
+            return false;
+        }
+        if !self.include_tags.is_empty()
+            && !attrs.tags.iter().any(|t| self.include_tags.contains(t))
+        {
+            return false;
+        }
+        if attrs.tags.iter().any(|t| self.exclude_tags.contains(t)) {
+            return false;
+        }
+        if !at_least(attrs.liquidity, self.min_liquidity)
+            || !at_least(attrs.volume, self.min_volume)
+        {
+            return false;
+        }
+        if self.resolves_after.is_some() || self.resolves_before.is_some() {
+            let Some(ts) = attrs.resolution_date else {
+                return false;
+            };
+            if self.resolves_after.is_some_and(|after| ts < after)
+                || self.resolves_before.is_some_and(|before| ts > before)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Text plus structured filters for `MarketIndex::search_hybrid`, built by chaining.
+#[derive(Debug, Clone, Default)]
+pub struct MarketQuery {
+    pub tokens: Vec<String>,
+    pub text: String,
+    /// Language of `text`; non-English text is embedded with the multilingual model.
+    pub language: Language,
+    pub filter: MarketFilter,
+}
+
+impl MarketQuery {
+    pub fn new(tokens: &[String], text: &str) -> Self {
+        Self {
+            tokens: tokens.to_vec(),
+            text: text.to_string(),
+            language: Language::English,
+            filter: MarketFilter::default(),
+        }
+    }
+
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    pub fn include_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.filter.include_tags.extend(normalize_tags(tags));
+        self
+    }
+
+    pub fn exclude_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.filter.exclude_tags.extend(normalize_tags(tags));
+        self
+    }
+
+    pub fn min_liquidity(mut self, min: f64) -> Self {
+        self.filter.min_liquidity = Some(min);
+        self
+    }
+
+    pub fn min_volume(mut self, min: f64) -> Self {
+        self.filter.min_volume = Some(min);
+        self
+    }
+
+    pub fn resolves_between(mut self, after: Option<i64>, before: Option<i64>) -> Self {
+        self.filter.resolves_after = after;
+        self.filter.resolves_before = before;
+        self
+    }
+
+    pub fn active_only(mut self) -> Self {
+        self.filter.active_only = true;
+        self
+    }
+
+    pub fn exclude_markets<I: IntoIterator<Item = String>>(mut self, ids: I) -> Self {
+        self.filter.exclude_market_ids.extend(ids);
+        self
+    }
+}
+
+fn normalize_tags<I, S>(tags: I) -> impl Iterator<Item = String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    tags.into_iter()
+        .map(|t| t.as_ref().trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attrs() -> MarketAttrs {
+        MarketAttrs {
+            tags: vec!["economics".to_string(), "fed".to_string()],
+            resolution_date: Some(1_000),
+            volume: Some(50_000.0),
+            liquidity: Some(2_000.0),
+            active: true,
+        }
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let base = MarketQuery::new(&[], "");
+        assert!(base.filter.is_empty());
+        assert!(base.filter.matches("m", &attrs()));
+
+        let q = base
+            .clone()
+            .include_tags([" Fed ", "crypto"])
+            .min_liquidity(1_000.0)
+            .resolves_between(Some(500), Some(1_000))
+            .active_only();
+        assert!(q.filter.matches("m", &attrs()));
+        assert!(!q.filter.matches(
+            "m",
+            &MarketAttrs {
+                active: false,
+                ..attrs()
+            }
+        ));
+        assert!(!q.filter.matches(
+            "m",
+            &MarketAttrs {
+                resolution_date: Some(1_001),
+                ..attrs()
+            }
+        ));
+        // Unknown values fail a bound
+        assert!(!q.filter.matches(
+            "m",
+            &MarketAttrs {
+                liquidity: None,
+                ..attrs()
+            }
+        ));
+
+        assert!(
+            !base
+                .clone()
+                .exclude_tags(["ECONOMICS"])
+                .filter
+                .matches("m", &attrs())
+        );
+        assert!(
+            !base
+                .clone()
+                .include_tags(["sports"])
+                .filter
+                .matches("m", &attrs())
+        );
+        assert!(
+            !base
+                .clone()
+                .min_volume(60_000.0)
+                .filter
+                .matches("m", &attrs())
+        );
+        assert!(
+            !base
+                .exclude_markets(["m".to_string()])
+                .filter
+                .matches("m", &attrs())
+        );
+    }
+}
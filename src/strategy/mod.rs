@@ -1,11 +1,23 @@
 pub mod actor;
 pub mod analyst;
+mod embedding_store;
+mod entity_dictionary;
 mod event_features;
 pub mod exact_duplicate_detector;
 pub mod hard_filters;
+mod hnsw;
 pub mod kelly;
+pub mod language;
 mod market_index;
+mod market_indexer;
+mod market_query;
 pub mod normalizers;
+mod numeric_facts;
+pub mod reranker;
 mod sim_hash_cache;
+mod source_stats;
+mod staleness;
+mod story_clusterer;
+mod time_expressions;
 pub mod tokenization;
 pub mod types;
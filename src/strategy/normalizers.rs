@@ -27,6 +27,13 @@ pub fn normalize_news_item_dedup_stage(news: &RawNews) -> String {
 
 
 pub fn normalize_for_matching(news: &RawNews) -> String {
+    // deunicode (é -> e, ñ -> n, …)
+    deunicode(&normalize_unicode(news))
+}
+
+/// Same as `normalize_for_matching` but keeps the original script, so language
+/// detection and non-English stemmers see the accents / Cyrillic / Greek letters.
+pub fn normalize_unicode(news: &RawNews) -> String {
     lazy_static::lazy_static! {
         // Remove URLs
         static ref URL_RE: Regex = Regex::new(r"https?://\S+").unwrap();
@@ -42,15 +49,12 @@ pub fn normalize_for_matching(news: &RawNews) -> String {
     // 2) remove URLs (they are noise)
     let no_url = URL_RE.replace_all(&lower, "");
 
-    // 3) deunicode (é -> e, ñ -> n, …)
-    let ascii = deunicode(&no_url);
-
-    // 4) collapse multiple whitespace (spaces, tabs, newlines...)
-    let collapsed = ascii
+    // 3) collapse multiple whitespace (spaces, tabs, newlines...)
+    let collapsed = no_url
         .split_whitespace()
         .collect::<Vec<_>>()
         .join(" ");
 
-    // 5) trim edges
+    // 4) trim edges
     collapsed.trim().to_string()
 }
\ No newline at end of file
@@ -0,0 +1,549 @@
+//! numeric_facts.rs
+//!
+//! Numbers in news ("fed cuts rates by 25bps", "cpi rose 3.2%", "bitcoin fell to
+//! $98,500") and thresholds in market questions ("will cpi exceed 3%?",
+//! "btc above 100k?"), plus a deterministic comparator that settles a
+//! threshold from the facts when they unambiguously confirm or contradict it.
+//!
+//! All text is expected normalized: lowercase ASCII, punctuation kept.
+
+use std::ops::Range;
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+use crate::strategy::event_features::Entity;
+
+const NUMBER: &str = r"(?P<cur>\$|usd ?)?(?P<num>\d{1,3}(?:,\d{3})+(?:\.\d+)?|\d+(?:\.\d+)?)(?P<plus>\+)?(?: ?(?P<mag>k|mn|m|bn|b|tn|t|thousand|million|billion|trillion)\b)?(?: ?(?P<unit>%|percent\b|per cent\b|bps\b|bp\b|basis points?\b))?";
+const UP: &str = r"rises?|rose|risen|rising|up|climb(?:s|ed)?|jump(?:s|ed)?|surge[sd]?|gain(?:s|ed)?|increase[sd]?|hike[sd]?|raise[sd]?|soar(?:s|ed)?|rall(?:y|ies|ied)|accelerate[sd]?";
+const DOWN: &str = r"falls?|fell|fallen|falling|down|drop(?:s|ped)?|decline[sd]?|cuts?|lower(?:s|ed)?|slid(?:e|es)?|plunge[sd]?|tumble[sd]?|sinks?|sank|ease[sd]?|slow(?:s|ed)?|reduce[sd]?|slash(?:es|ed)?";
+
+lazy_static! {
+    static ref RE_NUMBER: Regex = Regex::new(&format!(r"(?:^|[^\w.,$])(?P<all>{NUMBER})")).unwrap();
+    // The last move verb shortly before a number: "cuts rates by ", "rose ", "fell to "
+    static ref RE_MOVE_BEFORE: Regex = Regex::new(&format!(
+        r"^.*\b(?:(?P<up>{UP})|(?P<down>{DOWN}))(?: [a-z']+){{0,3}}? (?:(?P<prep>by|to|at) )?$"
+    ))
+    .unwrap();
+    // "cut rates by 50 bps", "hike by at least 25bps"
+    static ref RE_CHANGE_THRESHOLD: Regex = Regex::new(&format!(
+        r"\b(?:(?P<up>hikes?|raises?|increases?)|(?P<down>cuts?|lowers?|reduces?))\b(?: [a-z']+){{0,3}}? by (?:(?P<cmp>at least|more than|over|at most|less than|under) )?{NUMBER}(?: ?(?P<post>or (?:more|higher|greater|above|less|lower|fewer|below)))?"
+    ))
+    .unwrap();
+    // "exceed 3%", "above 100k", "reach $120,000", "dip below 80k"
+    static ref RE_PREFIX_THRESHOLD: Regex = Regex::new(&format!(
+        r"\b(?P<cmp>exceeds?|above|over|more than|greater than|higher than|at least|reach(?:es)?|hits?|below|under|less than|lower than|at most|(?:dips?|falls?|drops?) (?:to|below))\s+{NUMBER}"
+    ))
+    .unwrap();
+    // "4.5% or higher", "3+%"; numbers without either are skipped
+    static ref RE_POSTFIX_THRESHOLD: Regex = Regex::new(&format!(
+        r"{NUMBER}(?: ?(?P<post>or (?:more|higher|greater|above|less|lower|fewer|below)))?"
+    ))
+    .unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericUnit {
+    Percent,
+    BasisPoints,
+    Usd,
+    /// No unit; comparable with USD amounts ("btc above 100k")
+    Plain,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// A number reported in the news.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericFact {
+    /// Magnitude applied: "98.5k" is 98500
+    pub value: f64,
+    pub unit: NumericUnit,
+    /// Set when reported as a move ("cut 25bps", "rose to 3.2%")
+    pub direction: Option<Direction>,
+    /// The size of a move ("by 25bps") rather than a level ("to 3.2%")
+    pub is_change: bool,
+    /// Label of the entity the number is about, if one is in the same sentence
+    pub subject: Option<String>,
+    /// Byte range in the normalized text
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+    Exactly,
+}
+
+/// A numeric condition in a market question.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Threshold {
+    pub comparison: Comparison,
+    pub value: f64,
+    pub unit: NumericUnit,
+    /// Set for questions about the size of a move ("cut rates by 50 bps?")
+    pub direction: Option<Direction>,
+    /// Met as soon as the level is touched ("reach", "hit", "dip to"): a fact can
+    /// confirm it but not rule it out
+    pub touch: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Confirms,
+    Contradicts,
+}
+
+impl Verdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Confirms => "confirms",
+            Verdict::Contradicts => "contradicts",
+        }
+    }
+}
+
+/// Numbers with a unit, a magnitude or a move verb. Bare numbers ("3 people",
+/// "in 2026") are skipped.
+pub fn extract_numeric_facts(text: &str) -> Vec<NumericFact> {
+    let mut facts = Vec::new();
+    for caps in RE_NUMBER.captures_iter(text) {
+        let Some((value, unit)) = parse_number(&caps) else {
+            continue;
+        };
+        let span = caps.name("all").unwrap().range();
+
+        let window_start = floor_char_boundary(text, span.start.saturating_sub(48));
+        let before = RE_MOVE_BEFORE.captures(&text[window_start..span.start]);
+        let direction = before.as_ref().and_then(|m| {
+            if m.name("up").is_some() {
+                Some(Direction::Up)
+            } else if m.name("down").is_some() {
+                Some(Direction::Down)
+            } else {
+                None
+            }
+        });
+        let prep = before
+            .as_ref()
+            .and_then(|m| m.name("prep"))
+            .map(|p| p.as_str());
+
+        if unit == NumericUnit::Plain && caps.name("mag").is_none() && direction.is_none() {
+            continue;
+        }
+        let is_change = direction.is_some()
+            && (prep == Some("by") || (prep.is_none() && unit == NumericUnit::BasisPoints));
+
+        facts.push(NumericFact {
+            value,
+            unit,
+            direction,
+            is_change,
+            subject: None,
+            span,
+        });
+    }
+    facts
+}
+
+/// Attach to each fact the entity mentioned closest before it in the same
+/// sentence, or else the first one after it.
+pub fn assign_subjects(text: &str, facts: &mut [NumericFact], entities: &[Entity]) {
+    let same_sentence = |a: usize, b: usize| {
+        let between = &text[a.min(b)..a.max(b)];
+        !between.contains(". ")
+            && !between.contains("? ")
+            && !between.contains("! ")
+            && !between.contains("; ")
+    };
+    for fact in facts.iter_mut() {
+        let before = entities
+            .iter()
+            .flat_map(|e| e.spans.iter().map(move |s| (e, s)))
+            .filter(|(_, s)| s.end <= fact.span.start && same_sentence(s.end, fact.span.start))
+            .max_by_key(|(_, s)| s.end);
+        let after = || {
+            entities
+                .iter()
+                .flat_map(|e| e.spans.iter().map(move |s| (e, s)))
+                .filter(|(_, s)| s.start >= fact.span.end && same_sentence(fact.span.end, s.start))
+                .min_by_key(|(_, s)| s.start)
+        };
+        fact.subject = before.or_else(after).map(|(e, _)| e.value.clone());
+    }
+}
+
+/// The first numeric condition in a market question.
+pub fn parse_threshold(question: &str) -> Option<Threshold> {
+    if let Some(caps) = RE_CHANGE_THRESHOLD.captures(question) {
+        let (value, unit) = parse_number(&caps)?;
+        let direction = if caps.name("up").is_some() {
+            Direction::Up
+        } else {
+            Direction::Down
+        };
+        let comparison = match caps.name("cmp").map(|c| c.as_str()) {
+            Some(cmp) => prefix_comparison(cmp)?.0,
+            None => postfix_comparison(&caps).unwrap_or(Comparison::Exactly),
+        };
+        return Some(Threshold {
+            comparison,
+            value,
+            unit,
+            direction: Some(direction),
+            touch: false,
+        });
+    }
+
+    let prefix = RE_PREFIX_THRESHOLD.captures(question);
+    let postfix = RE_POSTFIX_THRESHOLD
+        .captures_iter(question)
+        .find(|c| postfix_comparison(c).is_some());
+    let prefix_first = match (&prefix, &postfix) {
+        (Some(a), Some(b)) => a.get(0)?.start() <= b.get(0)?.start(),
+        (a, _) => a.is_some(),
+    };
+    let (caps, comparison, touch) = if prefix_first {
+        let caps = prefix?;
+        let (comparison, touch) = prefix_comparison(&caps["cmp"])?;
+        (caps, comparison, touch)
+    } else {
+        let caps = postfix?;
+        let comparison = postfix_comparison(&caps)?;
+        (caps, comparison, false)
+    };
+    let (value, unit) = parse_number(&caps)?;
+    Some(Threshold {
+        comparison,
+        value,
+        unit,
+        direction: None,
+        touch,
+    })
+}
+
+/// Settle `threshold` from the facts about one of `subjects` (entity labels
+/// in the market question). Facts about other subjects, or in another unit,
+/// are ignored; if the relevant facts disagree, nothing is decided.
+pub fn settle(threshold: &Threshold, subjects: &[&str], facts: &[NumericFact]) -> Option<Verdict> {
+    let mut verdict = None;
+    for fact in facts {
+        if !fact
+            .subject
+            .as_deref()
+            .is_some_and(|s| subjects.contains(&s))
+        {
+            continue;
+        }
+        let Some(v) = compare(threshold, fact) else {
+            continue;
+        };
+        match verdict {
+            None => verdict = Some(v),
+            Some(prev) if prev != v => return None,
+            Some(_) => {}
+        }
+    }
+    verdict
+}
+
+/// Whether one fact meets the threshold, if they are comparable.
+pub fn compare(threshold: &Threshold, fact: &NumericFact) -> Option<Verdict> {
+    let fact_value = comparable_value(fact.value, fact.unit, threshold.unit)?;
+
+    match threshold.direction {
+        // A question about a move needs a reported move
+        Some(direction) => {
+            if !fact.is_change {
+                return None;
+            }
+            if fact.direction? != direction {
+                return Some(Verdict::Contradicts);
+            }
+        }
+        None => {
+            if fact.is_change {
+                return None;
+            }
+        }
+    }
+
+    let met = match threshold.comparison {
+        Comparison::Above => fact_value > threshold.value,
+        Comparison::AtLeast => fact_value >= threshold.value - tolerance(threshold.value),
+        Comparison::Below => fact_value < threshold.value,
+        Comparison::AtMost => fact_value <= threshold.value + tolerance(threshold.value),
+        Comparison::Exactly => (fact_value - threshold.value).abs() <= tolerance(threshold.value),
+    };
+    if met {
+        Some(Verdict::Confirms)
+    } else if threshold.touch {
+        None
+    } else {
+        Some(Verdict::Contradicts)
+    }
+}
+
+/// `value` expressed in `target`'s unit: percent and basis points convert,
+/// plain numbers compare with USD amounts.
+fn comparable_value(value: f64, unit: NumericUnit, target: NumericUnit) -> Option<f64> {
+    use NumericUnit::*;
+    match (unit, target) {
+        (a, b) if a == b => Some(value),
+        (Percent, BasisPoints) => Some(value * 100.0),
+        (BasisPoints, Percent) => Some(value / 100.0),
+        (Usd, Plain) | (Plain, Usd) => Some(value),
+        _ => None,
+    }
+}
+
+fn tolerance(value: f64) -> f64 {
+    value.abs() * 1e-9
+}
+
+fn prefix_comparison(cmp: &str) -> Option<(Comparison, bool)> {
+    let first = cmp.split(' ').next()?;
+    let touch = matches!(
+        first,
+        "reach" | "reaches" | "hit" | "hits" | "dip" | "dips" | "fall" | "falls" | "drop" | "drops"
+    );
+    let comparison = match first {
+        "exceed" | "exceeds" | "above" | "over" | "more" | "greater" | "higher" => {
+            Comparison::Above
+        }
+        "reach" | "reaches" | "hit" | "hits" => Comparison::AtLeast,
+        "below" | "under" | "less" | "lower" => Comparison::Below,
+        "at" if cmp == "at least" => Comparison::AtLeast,
+        "at" => Comparison::AtMost,
+        _ if cmp.ends_with(" below") => Comparison::Below,
+        _ => Comparison::AtMost,
+    };
+    Some((comparison, touch))
+}
+
+/// "25+ bps", "4.5% or higher", "3% or less".
+fn postfix_comparison(caps: &Captures) -> Option<Comparison> {
+    if caps.name("plus").is_some() {
+        return Some(Comparison::AtLeast);
+    }
+    match caps.name("post")?.as_str().rsplit(' ').next()? {
+        "more" | "higher" | "greater" | "above" => Some(Comparison::AtLeast),
+        "less" | "lower" | "fewer" | "below" => Some(Comparison::AtMost),
+        _ => None,
+    }
+}
+
+fn parse_number(caps: &Captures) -> Option<(f64, NumericUnit)> {
+    let raw = &caps["num"];
+    let mut value: f64 = raw.replace(',', "").parse().ok()?;
+    let mag = caps.name("mag").map(|m| m.as_str());
+    value *= match mag {
+        Some("k" | "thousand") => 1e3,
+        Some("m" | "mn" | "million") => 1e6,
+        Some("b" | "bn" | "billion") => 1e9,
+        Some("t" | "tn" | "trillion") => 1e12,
+        _ => 1.0,
+    };
+    let unit = match caps.name("unit").map(|u| u.as_str()) {
+        Some("%" | "percent" | "per cent") => NumericUnit::Percent,
+        Some(_) => NumericUnit::BasisPoints,
+        None if caps.name("cur").is_some() => NumericUnit::Usd,
+        None => NumericUnit::Plain,
+    };
+    // A bare year is not a quantity
+    if unit == NumericUnit::Plain
+        && mag.is_none()
+        && !raw.contains([',', '.'])
+        && (1900.0..=2100.0).contains(&value)
+    {
+        return None;
+    }
+    Some((value, unit))
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fact(
+        value: f64,
+        unit: NumericUnit,
+        direction: Option<Direction>,
+        is_change: bool,
+    ) -> NumericFact {
+        NumericFact {
+            value,
+            unit,
+            direction,
+            is_change,
+            subject: Some("CPI".to_string()),
+            span: 0..0,
+        }
+    }
+
+    #[test]
+    fn test_extract_numeric_facts() {
+        let facts = extract_numeric_facts(
+            "fed cuts rates by 25bps; cpi rose 3.2% in march. bitcoin fell to $98,500, 3 people hurt in 2026",
+        );
+        let summary: Vec<_> = facts
+            .iter()
+            .map(|f| (f.value, f.unit, f.direction, f.is_change))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (25.0, NumericUnit::BasisPoints, Some(Direction::Down), true),
+                (3.2, NumericUnit::Percent, Some(Direction::Up), false),
+                (98_500.0, NumericUnit::Usd, Some(Direction::Down), false),
+            ]
+        );
+
+        let facts = extract_numeric_facts("tesla market cap tops $1.2 trillion, btc at 98.5k");
+        assert_eq!(facts[0].value, 1.2e12);
+        assert_eq!(facts[0].unit, NumericUnit::Usd);
+        assert_eq!(facts[1].value, 98_500.0);
+        assert_eq!(facts[1].direction, None);
+    }
+
+    #[test]
+    fn test_assign_subjects() {
+        let text = "cpi rose 3.2%. bitcoin fell to $98,500";
+        let mut facts = extract_numeric_facts(text);
+        let entity = |value: &str, span: Range<usize>| Entity {
+            value: value.to_string(),
+            spans: vec![span],
+            ..Default::default()
+        };
+        assign_subjects(
+            text,
+            &mut facts,
+            &[entity("CPI", 0..3), entity("BTC", 15..22)],
+        );
+        assert_eq!(facts[0].subject.as_deref(), Some("CPI"));
+        assert_eq!(facts[1].subject.as_deref(), Some("BTC"));
+    }
+
+    #[test]
+    fn test_parse_threshold() {
+        let t = parse_threshold("will cpi exceed 3% in march?").unwrap();
+        assert_eq!(
+            (t.comparison, t.value, t.unit),
+            (Comparison::Above, 3.0, NumericUnit::Percent)
+        );
+
+        let t = parse_threshold("btc above 100k on december 31?").unwrap();
+        assert_eq!(
+            (t.comparison, t.value, t.unit),
+            (Comparison::Above, 100_000.0, NumericUnit::Plain)
+        );
+
+        let t = parse_threshold("will bitcoin reach $120,000 by june?").unwrap();
+        assert_eq!((t.comparison, t.touch), (Comparison::AtLeast, true));
+
+        let t =
+            parse_threshold("will the fed cut rates by 50 bps after the march meeting?").unwrap();
+        assert_eq!(t.direction, Some(Direction::Down));
+        assert_eq!(
+            (t.comparison, t.value, t.unit),
+            (Comparison::Exactly, 50.0, NumericUnit::BasisPoints)
+        );
+
+        let t = parse_threshold("fed hikes by 25+ bps?").unwrap();
+        assert_eq!(
+            (t.comparison, t.direction),
+            (Comparison::AtLeast, Some(Direction::Up))
+        );
+        assert_eq!(t.unit, NumericUnit::BasisPoints);
+
+        let t = parse_threshold("us unemployment 4.5% or higher in may?").unwrap();
+        assert_eq!((t.comparison, t.value), (Comparison::AtLeast, 4.5));
+
+        assert!(parse_threshold("will trump win the 2028 election?").is_none());
+    }
+
+    #[test]
+    fn test_compare() {
+        use NumericUnit::*;
+        let cpi_above_3 = parse_threshold("will cpi exceed 3%?").unwrap();
+        assert_eq!(
+            compare(
+                &cpi_above_3,
+                &fact(3.2, Percent, Some(Direction::Up), false)
+            ),
+            Some(Verdict::Confirms)
+        );
+        assert_eq!(
+            compare(&cpi_above_3, &fact(2.9, Percent, None, false)),
+            Some(Verdict::Contradicts)
+        );
+        // A move is not a level, and units must match
+        assert_eq!(
+            compare(&cpi_above_3, &fact(0.3, Percent, Some(Direction::Up), true)),
+            None
+        );
+        assert_eq!(compare(&cpi_above_3, &fact(3.2, Usd, None, false)), None);
+
+        let cut_50 = parse_threshold("will the fed cut by 50 bps?").unwrap();
+        assert_eq!(
+            compare(&cut_50, &fact(0.5, Percent, Some(Direction::Down), true)),
+            Some(Verdict::Confirms)
+        );
+        assert_eq!(
+            compare(
+                &cut_50,
+                &fact(25.0, BasisPoints, Some(Direction::Down), true)
+            ),
+            Some(Verdict::Contradicts)
+        );
+        assert_eq!(
+            compare(&cut_50, &fact(50.0, BasisPoints, Some(Direction::Up), true)),
+            Some(Verdict::Contradicts)
+        );
+
+        // Touch thresholds can only be confirmed
+        let reach = parse_threshold("will bitcoin reach 120k?").unwrap();
+        assert_eq!(
+            compare(&reach, &fact(121_000.0, Usd, Some(Direction::Up), false)),
+            Some(Verdict::Confirms)
+        );
+        assert_eq!(
+            compare(&reach, &fact(98_500.0, Usd, Some(Direction::Down), false)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_settle() {
+        let threshold = parse_threshold("will cpi exceed 3%?").unwrap();
+        let facts = extract_numeric_facts("cpi rose 3.2%, core cpi 2.8%");
+        let mut with_subject = facts.clone();
+        for f in &mut with_subject {
+            f.subject = Some("CPI".to_string());
+        }
+        // Facts about another subject are ignored
+        assert_eq!(settle(&threshold, &["CPI"], &facts), None);
+        assert_eq!(
+            settle(&threshold, &["CPI"], &with_subject[..1]),
+            Some(Verdict::Confirms)
+        );
+        // Conflicting facts decide nothing
+        assert_eq!(settle(&threshold, &["CPI"], &with_subject), None);
+        assert_eq!(settle(&threshold, &["GDP"], &with_subject), None);
+    }
+}
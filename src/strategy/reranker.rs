@@ -0,0 +1,211 @@
+use crate::config::config::{RerankCfg, RerankModel};
+use crate::strategy::types::RawCandidate;
+use anyhow::Result;
+use fastembed::{RerankInitOptions, RerankerModel, TextRerank};
+use std::sync::{Arc, Mutex};
+use tracing::{error, info};
+
+/// Scores (news, market) pairs with a local cross-encoder, orders candidates by
+/// relevance and drops the ones below `minRelevance` before any LLM call.
+///
+/// If the model is disabled or fails to load, candidates pass through in
+/// retrieval order without a relevance gate. Scoring runs on a blocking thread.
+pub struct Reranker {
+    model: Option<Arc<Mutex<TextRerank>>>,
+    min_relevance: f32,
+    max_candidates: usize,
+}
+
+impl Reranker {
+    pub fn new(cfg: &RerankCfg) -> Self {
+        let model = if cfg.enabled {
+            match TextRerank::try_new(
+                RerankInitOptions::new(to_fastembed(cfg.model)).with_show_download_progress(true),
+            ) {
+                Ok(m) => {
+                    info!("Loaded reranker model {:?}", cfg.model);
+                    Some(Arc::new(Mutex::new(m)))
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to load reranker model {:?}, reranking disabled: {:#}",
+                        cfg.model, e
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Self {
+            model,
+            min_relevance: cfg.min_relevance,
+            max_candidates: cfg.max_candidates.max(1),
+        }
+    }
+
+    pub async fn rerank(
+        &self,
+        news_text: &str,
+        candidates: Vec<RawCandidate>,
+    ) -> Vec<RawCandidate> {
+        let Some(model) = self.model.clone() else {
+            return candidates;
+        };
+        if candidates.is_empty() {
+            return candidates;
+        }
+
+        let mut candidates = candidates;
+        candidates.truncate(self.max_candidates);
+
+        let query = news_text.to_string();
+        let documents = documents(&candidates);
+        let result = tokio::task::spawn_blocking(move || {
+            let mut model = model
+                .lock()
+                .map_err(|_| anyhow::anyhow!("reranker model lock poisoned"))?;
+            score_pairs(&mut model, query, documents)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(scores)) => order_by_relevance(candidates, &scores, self.min_relevance),
+            Ok(Err(e)) => {
+                error!("Reranking failed, keeping retrieval order: {:#}", e);
+                metrics::counter!("strategy_rerank_errors_total").increment(1);
+                candidates
+            }
+            Err(e) => {
+                error!("Reranking task panicked, keeping retrieval order: {}", e);
+                metrics::counter!("strategy_rerank_errors_total").increment(1);
+                candidates
+            }
+        }
+    }
+}
+
+fn to_fastembed(model: RerankModel) -> RerankerModel {
+    match model {
+        RerankModel::BgeRerankerBase => RerankerModel::BGERerankerBase,
+        RerankModel::BgeRerankerV2M3 => RerankerModel::BGERerankerV2M3,
+        RerankModel::JinaRerankerV1TurboEn => RerankerModel::JINARerankerV1TurboEn,
+        RerankModel::JinaRerankerV2BaseMultilingual => RerankerModel::JINARerankerV2BaseMultiligual,
+    }
+}
+
+/// Text scored against the news, one per candidate.
+fn documents(candidates: &[RawCandidate]) -> Vec<String> {
+    candidates
+        .iter()
+        .map(|c| format!("{} {}", c.title, c.description))
+        .collect()
+}
+
+/// Relevance in [0, 1] per document, aligned with `documents`.
+fn score_pairs(
+    model: &mut TextRerank,
+    news_text: String,
+    documents: Vec<String>,
+) -> Result<Vec<f32>> {
+    let count = documents.len();
+    let results = model.rerank(news_text, documents, false, None)?;
+
+    // Results come back sorted by score; map them back to input positions
+    let mut scores = vec![0.0; count];
+    for r in results {
+        if let Some(slot) = scores.get_mut(r.index) {
+            *slot = sigmoid(r.score);
+        }
+    }
+    Ok(scores)
+}
+
+/// Cross-encoders emit logits; squash them so `minRelevance` is model-independent.
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn order_by_relevance(
+    candidates: Vec<RawCandidate>,
+    scores: &[f32],
+    min_relevance: f32,
+) -> Vec<RawCandidate> {
+    let total = candidates.len();
+    let mut ranked: Vec<RawCandidate> = candidates
+        .into_iter()
+        .zip(scores.iter().copied())
+        .filter(|(_, score)| *score >= min_relevance)
+        .map(|(mut c, score)| {
+            c.relevance = Some(score);
+            c
+        })
+        .collect();
+
+    metrics::counter!("strategy_rerank_dropped_total").increment((total - ranked.len()) as u64);
+    for c in &ranked {
+        metrics::histogram!("strategy_rerank_relevance").record(c.relevance.unwrap_or(0.0) as f64);
+    }
+
+    // Stable sort keeps retrieval order for ties
+    ranked.sort_by(|a, b| {
+        b.relevance
+            .partial_cmp(&a.relevance)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str, title: &str) -> RawCandidate {
+        RawCandidate {
+            market_id: id.to_string(),
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_order_by_relevance() {
+        let candidates = vec![candidate("a", ""), candidate("b", ""), candidate("c", "")];
+        let ranked = order_by_relevance(candidates, &[0.4, 0.1, 0.9], 0.3);
+
+        let ids: Vec<_> = ranked.iter().map(|c| c.market_id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "a"]);
+        assert_eq!(ranked[0].relevance, Some(0.9));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_passthrough() {
+        let reranker = Reranker::new(&RerankCfg {
+            enabled: false,
+            ..Default::default()
+        });
+        let candidates = vec![candidate("a", "x"), candidate("b", "y")];
+        let out = reranker.rerank("news", candidates).await;
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].market_id, "a");
+        assert!(out[0].relevance.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cross_encoder_ranking() {
+        let reranker = Reranker::new(&RerankCfg {
+            min_relevance: 0.0,
+            ..Default::default()
+        });
+        let candidates = vec![
+            candidate("btc", "Will Bitcoin hit $100k in 2025?"),
+            candidate("fed", "Will the Fed cut interest rates in March?"),
+        ];
+        let out = reranker
+            .rerank("Federal Reserve announces rate cut of 25bps", candidates)
+            .await;
+        assert_eq!(out[0].market_id, "fed");
+        assert!(out[0].relevance.unwrap() > out[1].relevance.unwrap());
+    }
+}
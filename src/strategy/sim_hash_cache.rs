@@ -1,16 +1,21 @@
-use ahash::AHasher;
-use chrono::Utc;
+use crate::strategy::embedding_store::text_hash;
 use lru::LruCache;
-use std::hash::{Hash, Hasher};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::num::{NonZero, NonZeroUsize};
-use crate::strategy::tokenization::TokenizationConfig;
+use tracing::warn;
 
-/// Configuration for the exact duplication pipeline
+/// Configuration for the SimHash index used by story clustering
 #[derive(Debug, Clone)]
 pub struct SimHashCacheConfig {
     pub capacity: NonZeroUsize,
     pub ttl_hours: i64,
     pub hamming_threshold: u32,
+    /// SimHash width (1..=64)
+    pub bits: u32,
+    /// Number of bands the hash is split into for the LSH lookup. Any hash
+    /// within `hamming_threshold` is found as long as `bands > hamming_threshold`.
+    pub bands: u32,
 }
 
 impl Default for SimHashCacheConfig {
@@ -19,86 +24,161 @@ impl Default for SimHashCacheConfig {
             capacity: NonZero::new(10_000).expect(" SimHashCache: lru cache can't be of size 0"),
             ttl_hours: 48,
             hamming_threshold: 3,
+            bits: 64,
+            bands: 4,
         }
     }
 }
 
+/// Recent SimHashes indexed by band (banded LSH): two hashes within distance k
+/// agree exactly on at least one of k + 1 bands, so a lookup only compares the
+/// hashes sharing a band with the query instead of scanning the whole cache.
 pub struct SimHashCache {
-    cache: LruCache<u64, i64>, // simhash -> timestamp
+    cache: LruCache<u64, SimHashEntry>,
+    /// One table per band: band value -> hashes with that value
+    tables: Vec<HashMap<u64, Vec<u64>>>,
+    /// (shift, mask) of each band
+    bands: Vec<(u32, u64)>,
+    /// Insertion times, oldest first; stale entries are skipped when popped
+    expiry: BinaryHeap<Reverse<(i64, u64)>>,
     ttl_hours: i64,
     hamming_threshold: u32,
+    bits: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SimHashEntry {
+    /// Unix timestamp (seconds) the hash was seen
+    seen: i64,
+    /// Story the hashed item was assigned to
+    cluster_id: i64,
 }
 
 impl SimHashCache {
     pub fn new(config: SimHashCacheConfig) -> Self {
+        let bits = config.bits.clamp(1, 64);
+        let band_count = config.bands.clamp(1, bits);
+        if band_count <= config.hamming_threshold {
+            warn!(
+                "SimHashCache: {} bands for distance {}: lookups may miss near-duplicates",
+                band_count, config.hamming_threshold
+            );
+        }
+
+        // Split the bits as evenly as possible; the first bands take the remainder
+        let mut bands = Vec::with_capacity(band_count as usize);
+        let mut shift = 0;
+        for i in 0..band_count {
+            let width = bits / band_count + u32::from(i < bits % band_count);
+            bands.push((shift, mask(width)));
+            shift += width;
+        }
+
         Self {
             cache: LruCache::new(config.capacity),
+            tables: vec![HashMap::new(); bands.len()],
+            bands,
+            expiry: BinaryHeap::new(),
             ttl_hours: config.ttl_hours,
             hamming_threshold: config.hamming_threshold,
+            bits,
         }
     }
 
-    /// Returns true if `hash` is considered a near-duplicate of any recent hash
-    /// currently in the cache (within TTL and Hamming distance threshold).
-    ///
-    /// Each time this is called, we eject expired key, we shouldn't rely on LRU memory policy
-    /// Expired entries must not participate in the similarity check.
-    /// Note: This DOES NOT insert the hash. Call `insert` separately if you
-    /// decide to treat this as a new event.
-    pub fn is_near_duplicate(&mut self, hash: u64) -> bool {
-        let now = Utc::now().timestamp();
-        let ttl_secs = self.ttl_hours * 3600;
+    /// SimHash width in bits.
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
 
-        // We'll collect keys to remove lazily to avoid borrowing issues.
-        let mut expired_keys = Vec::new();
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
 
-        for (existing_hash, &ts) in self.cache.iter() {
-            // Mark expired entries for later removal.
-            if now - ts > ttl_secs {
-                expired_keys.push(*existing_hash);
+    /// Returns the stories of recent hashes within the Hamming distance
+    /// threshold of `hash`, with the smallest distance per story, closest first.
+    ///
+    /// Expired entries are evicted first and never participate in the check;
+    /// we don't rely on the LRU memory policy for the TTL.
+    /// Note: This DOES NOT insert the hash. Call `insert` separately once the
+    /// item has been assigned to a story.
+    pub fn matches(&mut self, hash: u64, now: i64) -> Vec<(i64, u32)> {
+        self.expire(now);
+        let hash = hash & mask(self.bits);
+
+        let mut seen = HashSet::new();
+        let mut closest: HashMap<i64, u32> = HashMap::new();
+        for (table, &(shift, band_mask)) in self.tables.iter().zip(&self.bands) {
+            let Some(bucket) = table.get(&((hash >> shift) & band_mask)) else {
                 continue;
+            };
+            for &candidate in bucket {
+                if !seen.insert(candidate) {
+                    continue;
+                }
+                let dist = hamming_distance(hash, candidate);
+                if dist > self.hamming_threshold {
+                    continue;
+                }
+                if let Some(entry) = self.cache.peek(&candidate) {
+                    let best = closest.entry(entry.cluster_id).or_insert(dist);
+                    *best = (*best).min(dist);
+                }
             }
+        }
+
+        let mut matches: Vec<(i64, u32)> = closest.into_iter().collect();
+        matches.sort_by_key(|&(cluster_id, dist)| (dist, cluster_id));
+        matches
+    }
 
-            // Within TTL: check Hamming distance.
-            let dist = hamming_distance(hash, *existing_hash);
-            if dist <= self.hamming_threshold {
-                return true;
+    pub fn insert(&mut self, hash: u64, cluster_id: i64, seen: i64) {
+        let hash = hash & mask(self.bits);
+        let entry = SimHashEntry { seen, cluster_id };
+        match self.cache.push(hash, entry) {
+            // Re-inserted: already in the band tables
+            Some((old, _)) if old == hash => {}
+            // Evicted the least recently used hash
+            Some((evicted, _)) => {
+                self.unindex(evicted);
+                self.index(hash);
             }
+            None => self.index(hash),
         }
+        self.expiry.push(Reverse((seen, hash)));
+    }
 
-        // Remove expired entries.
-        for key in expired_keys {
-            self.cache.pop(&key);
+    /// Drop hashes older than the TTL.
+    fn expire(&mut self, now: i64) {
+        let ttl_secs = self.ttl_hours * 3600;
+        while let Some(&Reverse((seen, hash))) = self.expiry.peek() {
+            if now - seen <= ttl_secs {
+                break;
+            }
+            self.expiry.pop();
+            // Skip if since evicted or re-inserted with a newer time
+            if self.cache.peek(&hash).is_some_and(|e| e.seen == seen) {
+                self.cache.pop(&hash);
+                self.unindex(hash);
+            }
         }
-
-        false
     }
 
-    pub fn insert(&mut self, hash: u64) {
-        let now = Utc::now().timestamp();
-        self.cache.put(hash, now);
+    fn index(&mut self, hash: u64) {
+        for (table, &(shift, band_mask)) in self.tables.iter_mut().zip(&self.bands) {
+            table.entry((hash >> shift) & band_mask).or_default().push(hash);
+        }
     }
 
-    pub fn hydrate(&mut self, items: Vec<(String, String)>, cfg: &TokenizationConfig) {
-        use crate::core::types::RawNews;
-        use crate::strategy::tokenization::TokenizedNews;
-
-        let now = Utc::now().timestamp();
-
-        for (title, description) in items {
-            let news = RawNews {
-                title,
-                description,
-                url: "".to_string(),
-                feed: "".to_string(),
-                published: None,
-                labels: vec![],
-            };
-
-            let tokenized = TokenizedNews::from_raw(news, &cfg);
-            // using the same tokens logic as handle_news_event:
-            let h = self.sim_hash(&tokenized.tokens);
-            self.cache.put(h, now);
+    fn unindex(&mut self, hash: u64) {
+        for (table, &(shift, band_mask)) in self.tables.iter_mut().zip(&self.bands) {
+            let key = (hash >> shift) & band_mask;
+            if let Some(bucket) = table.get_mut(&key) {
+                bucket.retain(|h| *h != hash);
+                if bucket.is_empty() {
+                    table.remove(&key);
+                }
+            }
         }
     }
 
@@ -121,6 +201,8 @@ impl SimHashCache {
     ///    (here weight = 1.0 for all tokens)
     /// 3) Final bit i of SimHash is 1 if V[i] > 0, else 0.
     ///
+    /// Only the low `bits` bits are kept.
+    ///
     /// TODO: upgrade to TF-IDF sim hashing
     pub fn sim_hash(&self, tokens: &[String]) -> u64 {
         if tokens.is_empty() {
@@ -152,22 +234,25 @@ impl SimHashCache {
             }
         }
 
-        result
+        result & mask(self.bits)
     }
 }
 
-/// Hash a single token to a stable u64.
-/// For SimHash, we just need a fast non-crypto hash.
+/// Hash a single token to a u64 that is stable across restarts, since
+/// SimHashes are persisted with their events.
 fn hash_token(token: &str) -> u64 {
-    let mut hasher = AHasher::default();
-    token.hash(&mut hasher);
-    hasher.finish()
+    text_hash(&[token])
 }
 
 fn hamming_distance(a: u64, b: u64) -> u32 {
     (a ^ b).count_ones()
 }
 
+/// The low `width` bits set.
+fn mask(width: u32) -> u64 {
+    if width >= 64 { u64::MAX } else { (1u64 << width) - 1 }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,20 +287,158 @@ mod tests {
     fn test_near_duplicate_detection() {
         let config = SimHashCacheConfig::default();
         let mut cache = SimHashCache::new(config);
+        let now = 1_000_000;
 
         let hash = 0b0000_1111; // simple pattern
-        cache.insert(hash);
+        cache.insert(hash, 7, now);
 
         // Exact match
-        assert!(cache.is_near_duplicate(hash));
+        assert_eq!(cache.matches(hash, now), vec![(7, 0)]);
 
         // 1 bit difference (dist=1, threshold=3) -> True
-        assert!(cache.is_near_duplicate(0b0000_1110));
+        assert_eq!(cache.matches(0b0000_1110, now), vec![(7, 1)]);
 
         // 3 bits difference (dist=3, threshold=3) -> True
-        assert!(cache.is_near_duplicate(0b0000_1000));
+        assert_eq!(cache.matches(0b0000_1000, now), vec![(7, 3)]);
 
         // 4 bits difference (dist=4, threshold=3) -> False
-        assert!(!cache.is_near_duplicate(0b1111_0000));
+        assert!(cache.matches(0b1111_0000, now).is_empty());
+
+        // Closest story first
+        cache.insert(0b0000_1110, 9, now);
+        assert_eq!(cache.matches(0b0000_1100, now), vec![(9, 1), (7, 2)]);
+
+        // Expired entries don't match
+        assert!(cache.matches(hash, now + 49 * 3600).is_empty());
+    }
+
+    /// xorshift, so tests and the benchmark don't need a rand dependency
+    fn random_hashes(n: usize, mut state: u64) -> Vec<u64> {
+        (0..n)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state
+            })
+            .collect()
+    }
+
+    /// Flip `k` distinct bits of `hash`, chosen from `seed`.
+    fn flip_bits(hash: u64, k: u32, seed: u64) -> u64 {
+        let mut out = hash;
+        let mut bit = seed % 64;
+        for _ in 0..k {
+            out ^= 1 << bit;
+            bit = (bit + 17) % 64;
+        }
+        out
+    }
+
+    #[test]
+    fn test_banded_lookup_matches_linear_scan() {
+        let mut cache = SimHashCache::new(SimHashCacheConfig::default());
+        let now = 1_000_000;
+        let hashes = random_hashes(5_000, 42);
+        for (i, h) in hashes.iter().enumerate() {
+            cache.insert(*h, i as i64, now);
+        }
+
+        for (i, h) in hashes.iter().enumerate().step_by(50) {
+            for k in 0..=4 {
+                let query = flip_bits(*h, k, i as u64);
+                let expected: Vec<(i64, u32)> = {
+                    let mut v: Vec<(i64, u32)> = hashes
+                        .iter()
+                        .enumerate()
+                        .map(|(j, other)| (j as i64, hamming_distance(query, *other)))
+                        .filter(|(_, d)| *d <= 3)
+                        .collect();
+                    v.sort_by_key(|&(id, d)| (d, id));
+                    v
+                };
+                assert_eq!(cache.matches(query, now), expected, "hash {} flipped {}", i, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bits_bands_and_capacity() {
+        let mut cache = SimHashCache::new(SimHashCacheConfig {
+            capacity: NonZero::new(2).unwrap(),
+            bits: 16,
+            bands: 5,
+            ..Default::default()
+        });
+        assert_eq!(cache.bits(), 16);
+        let tokens = vec!["fed".to_string(), "cuts".to_string()];
+        assert!(cache.sim_hash(&tokens) <= u16::MAX as u64);
+
+        // Bits above the width are ignored
+        cache.insert(0xFFFF_0000_0000_0001, 1, 0);
+        assert_eq!(cache.matches(0x1, 0), vec![(1, 0)]);
+
+        // LRU eviction removes the hash from the band tables
+        cache.insert(0x00F0, 2, 0);
+        cache.insert(0x0F00, 3, 0);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.matches(0x1, 0).is_empty());
+        assert_eq!(cache.matches(0x0F01, 0), vec![(3, 1)]);
+
+        // Re-inserting refreshes the TTL
+        cache.insert(0x0F00, 3, 40 * 3600);
+        assert_eq!(cache.matches(0x0F00, 50 * 3600), vec![(3, 0)]);
+        assert!(cache.matches(0x00F0, 50 * 3600).is_empty());
+        assert_eq!(cache.len(), 1);
+    }
+
+    /// Lookup throughput with 100k+ cached hashes, banded index vs a full scan.
+    /// Run with `cargo test --release bench_sim_hash_lookup -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_sim_hash_lookup() {
+        let n = 200_000;
+        let queries = 20_000;
+        let mut cache = SimHashCache::new(SimHashCacheConfig {
+            capacity: NonZero::new(n).unwrap(),
+            ..Default::default()
+        });
+        let hashes = random_hashes(n, 7);
+        let now = 1_000_000;
+        let start = std::time::Instant::now();
+        for (i, h) in hashes.iter().enumerate() {
+            cache.insert(*h, i as i64, now);
+        }
+        let insert = start.elapsed();
+
+        let probes: Vec<u64> = (0..queries)
+            .map(|i| flip_bits(hashes[(i * 7919) % n], (i % 4) as u32, i as u64))
+            .collect();
+
+        let start = std::time::Instant::now();
+        let mut found = 0;
+        for q in &probes {
+            found += cache.matches(*q, now).len();
+        }
+        let banded = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let mut scanned = 0;
+        for q in probes.iter().take(queries / 20) {
+            scanned += hashes.iter().filter(|h| hamming_distance(*q, **h) <= 3).count();
+        }
+        let linear = start.elapsed() * 20;
+
+        println!(
+            "{} hashes: insert {:.0}/s, banded lookup {:.0}/s ({} matches), linear scan {:.0}/s ({} matches in 1/20 of the queries)",
+            n,
+            n as f64 / insert.as_secs_f64(),
+            queries as f64 / banded.as_secs_f64(),
+            found,
+            queries as f64 / linear.as_secs_f64(),
+            scanned
+        );
+        assert!(found >= queries);
+        assert!(banded < linear);
     }
 }
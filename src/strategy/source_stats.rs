@@ -0,0 +1,386 @@
+//! Per-source reliability and latency.
+//!
+//! Every non-repeat report updates its source's share of first reports and
+//! its lag behind the story's first report. Signals whose markets resolved
+//! give the source's calibration (Brier score) and profitability per trade.
+//! Each statistic is pulled towards neutral by `priorCount` pseudo-observations
+//! and combined into a weight around 1.0 that the analyst applies to the
+//! signals derived from the source.
+
+use crate::config::config::SourceStatsCfg;
+use crate::persistence::database::{ResolvedSignal, SourceReport};
+use crate::strategy::story_clusterer::{StoryAssignment, StoryStatus};
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{HashMap, VecDeque};
+
+/// Lags kept per source for the median.
+const MAX_LAGS: usize = 500;
+
+/// Settlement price at which an outcome counts as the winner.
+const RESOLVED_PRICE: f64 = 0.99;
+
+#[derive(Debug, Default, Clone)]
+struct SourceStats {
+    reports: usize,
+    first_reports: usize,
+    lags: VecDeque<f64>,
+    resolved: usize,
+    brier_sum: f64,
+    trades: usize,
+    pnl_sum: f64,
+}
+
+impl SourceStats {
+    fn first_rate(&self) -> Option<f64> {
+        (self.reports > 0).then(|| self.first_reports as f64 / self.reports as f64)
+    }
+
+    fn median_lag(&self) -> Option<f64> {
+        if self.lags.is_empty() {
+            return None;
+        }
+        let mut lags: Vec<f64> = self.lags.iter().copied().collect();
+        lags.sort_by(f64::total_cmp);
+        let mid = lags.len() / 2;
+        Some(if lags.len().is_multiple_of(2) {
+            (lags[mid - 1] + lags[mid]) / 2.0
+        } else {
+            lags[mid]
+        })
+    }
+
+    fn brier(&self) -> Option<f64> {
+        (self.resolved > 0).then(|| self.brier_sum / self.resolved as f64)
+    }
+
+    fn pnl_per_trade(&self) -> Option<f64> {
+        (self.trades > 0).then(|| self.pnl_sum / self.trades as f64)
+    }
+}
+
+/// What the analyst gets to know about a source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceProfile {
+    /// Multiplier for the confidence of signals from this source; 1.0 is neutral.
+    pub weight: f64,
+    /// Short description for the analyst prompt.
+    pub summary: String,
+}
+
+pub struct SourceTracker {
+    cfg: SourceStatsCfg,
+    sources: HashMap<String, SourceStats>,
+}
+
+impl SourceTracker {
+    pub fn new(cfg: &SourceStatsCfg) -> Self {
+        Self {
+            cfg: cfg.clone(),
+            sources: HashMap::new(),
+        }
+    }
+
+    /// History the statistics are built from.
+    pub fn window(&self) -> std::time::Duration {
+        self.cfg.window
+    }
+
+    pub fn refresh_interval(&self) -> std::time::Duration {
+        self.cfg.refresh_interval
+    }
+
+    /// Count a clustered report. Repeats say nothing about the source's speed.
+    pub fn record_report(&mut self, source: &str, story: &StoryAssignment) {
+        if story.status == StoryStatus::Repeat {
+            return;
+        }
+        let lag = story.lag.num_milliseconds().max(0) as f64 / 1000.0;
+        self.add_report(source, story.status == StoryStatus::FirstReport, lag);
+        metrics::counter!("strategy_source_reports_total", "source" => source.to_string(), "status" => story.status.as_str())
+            .increment(1);
+        metrics::histogram!("strategy_source_lag_seconds", "source" => source.to_string())
+            .record(lag);
+        self.publish(source);
+    }
+
+    /// Replay persisted reports (startup hydration).
+    pub fn hydrate_reports(&mut self, reports: impl IntoIterator<Item = SourceReport>) {
+        for report in reports {
+            if report.status == StoryStatus::Repeat.as_str() {
+                continue;
+            }
+            let first = report.status == StoryStatus::FirstReport.as_str();
+            self.add_report(&report.source, first, report.lag_secs.max(0.0));
+        }
+        self.publish_all();
+    }
+
+    /// Replace the outcome statistics with the signals resolved so far.
+    pub fn set_outcomes(&mut self, signals: impl IntoIterator<Item = ResolvedSignal>) {
+        for stats in self.sources.values_mut() {
+            stats.resolved = 0;
+            stats.brier_sum = 0.0;
+            stats.trades = 0;
+            stats.pnl_sum = 0.0;
+        }
+        for signal in signals {
+            let Some(won) = signal_won(&signal) else {
+                continue;
+            };
+            let pnl = trade_pnl(&signal);
+            let stats = self.sources.entry(signal.source).or_default();
+            let outcome = if won { 1.0 } else { 0.0 };
+            // Same mapping as the analyst: confidence 0..1 -> probability 0.5..1
+            let probability = 0.5 + signal.confidence.clamp(0.0, 1.0) * 0.5;
+            stats.resolved += 1;
+            stats.brier_sum += (probability - outcome).powi(2);
+            if let Some(pnl) = pnl {
+                stats.trades += 1;
+                stats.pnl_sum += pnl;
+            }
+        }
+        self.publish_all();
+    }
+
+    /// Weight in `[minWeight, maxWeight]`; unknown sources get 1.0.
+    pub fn weight(&self, source: &str) -> f64 {
+        let Some(stats) = self.sources.get(source) else {
+            return 1.0;
+        };
+        let k = self.cfg.prior_count;
+        // Pull each score in [0, 1] towards the neutral 0.5
+        let shrink = |score: Option<f64>, n: usize| match score {
+            Some(s) if n as f64 + k > 0.0 => {
+                (s.clamp(0.0, 1.0) * n as f64 + 0.5 * k) / (n as f64 + k)
+            }
+            _ => 0.5,
+        };
+
+        let half_life = self.cfg.lag_half_life.as_secs_f64().max(1.0);
+        let timeliness = stats
+            .first_rate()
+            .zip(stats.median_lag())
+            .map(|(rate, lag)| 0.5 * rate + 0.5 * 0.5f64.powf(lag / half_life));
+        // A coin flip (Brier 0.25) is neutral, a perfect forecast scores 1
+        let calibration = stats.brier().map(|b| 1.0 - 2.0 * b);
+        let profitability = stats.pnl_per_trade().map(|pnl| (1.0 + pnl) / 2.0);
+
+        let score = (shrink(timeliness, stats.reports)
+            + shrink(calibration, stats.resolved)
+            + shrink(profitability, stats.trades))
+            / 3.0;
+        (2.0 * score).clamp(self.cfg.min_weight, self.cfg.max_weight)
+    }
+
+    pub fn profile(&self, source: &str) -> SourceProfile {
+        let weight = self.weight(source);
+        let empty = SourceStats::default();
+        let stats = self.sources.get(source).unwrap_or(&empty);
+        let mut parts = Vec::new();
+        if let (Some(rate), Some(lag)) = (stats.first_rate(), stats.median_lag()) {
+            parts.push(format!(
+                "first to {:.0}% of {} stories, median lag {:.0}s",
+                rate * 100.0,
+                stats.reports,
+                lag
+            ));
+        }
+        if let Some(brier) = stats.brier() {
+            parts.push(format!(
+                "{} resolved signals with Brier score {:.2}",
+                stats.resolved, brier
+            ));
+        }
+        if let Some(pnl) = stats.pnl_per_trade() {
+            parts.push(format!(
+                "{:+.2} per share over {} trades",
+                pnl, stats.trades
+            ));
+        }
+        let summary = if parts.is_empty() {
+            format!("{} (no track record, weight {:.2})", source, weight)
+        } else {
+            format!("{} (weight {:.2}: {})", source, weight, parts.join("; "))
+        };
+        SourceProfile { weight, summary }
+    }
+
+    fn add_report(&mut self, source: &str, first: bool, lag: f64) {
+        let stats = self.sources.entry(source.to_string()).or_default();
+        stats.reports += 1;
+        if first {
+            stats.first_reports += 1;
+        }
+        if stats.lags.len() == MAX_LAGS {
+            stats.lags.pop_front();
+        }
+        stats.lags.push_back(lag);
+    }
+
+    fn publish_all(&self) {
+        for source in self.sources.keys() {
+            self.publish(source);
+        }
+    }
+
+    fn publish(&self, source: &str) {
+        let Some(stats) = self.sources.get(source) else {
+            return;
+        };
+        let label = source.to_string();
+        if let Some(rate) = stats.first_rate() {
+            metrics::gauge!("strategy_source_first_report_ratio", "source" => label.clone())
+                .set(rate);
+        }
+        if let Some(lag) = stats.median_lag() {
+            metrics::gauge!("strategy_source_median_lag_seconds", "source" => label.clone())
+                .set(lag);
+        }
+        if let Some(brier) = stats.brier() {
+            metrics::gauge!("strategy_source_brier_score", "source" => label.clone()).set(brier);
+        }
+        if let Some(pnl) = stats.pnl_per_trade() {
+            metrics::gauge!("strategy_source_pnl_per_trade", "source" => label.clone()).set(pnl);
+        }
+        metrics::gauge!("strategy_source_weight", "source" => label).set(self.weight(source));
+    }
+}
+
+/// Outcome the settled market paid out on.
+fn winning_outcome(signal: &ResolvedSignal) -> Option<&str> {
+    signal
+        .tokens
+        .iter()
+        .find(|t| t.price.to_f64().is_some_and(|p| p >= RESOLVED_PRICE))
+        .map(|t| t.outcome.as_str())
+}
+
+/// Whether the signal's outcome won, once its market has settled.
+fn signal_won(signal: &ResolvedSignal) -> Option<bool> {
+    Some(winning_outcome(signal)?.eq_ignore_ascii_case(&signal.sentiment))
+}
+
+/// Profit per share of the token the decision bought. The entry price is
+/// quoted for the signal's outcome; buying the other side costs its complement.
+fn trade_pnl(signal: &ResolvedSignal) -> Option<f64> {
+    let side = signal.side.as_deref()?;
+    let price = signal.entry_price?.to_f64()?;
+    let cost = if side.eq_ignore_ascii_case(&signal.sentiment) {
+        price
+    } else {
+        1.0 - price
+    };
+    let payout = if winning_outcome(signal)?.eq_ignore_ascii_case(side) {
+        1.0
+    } else {
+        0.0
+    };
+    Some(payout - cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::MarketToken;
+    use rust_decimal::Decimal;
+
+    fn signal(source: &str, confidence: f64, won: bool, entry: Option<Decimal>) -> ResolvedSignal {
+        let token = |outcome: &str, price: i64| MarketToken {
+            token_id: outcome.to_lowercase(),
+            outcome: outcome.to_string(),
+            price: Decimal::new(price, 0),
+        };
+        ResolvedSignal {
+            source: source.to_string(),
+            sentiment: "Yes".to_string(),
+            confidence,
+            tokens: vec![token("Yes", won as i64), token("No", !won as i64)],
+            side: entry.map(|_| "Yes".to_string()),
+            entry_price: entry,
+        }
+    }
+
+    fn report(source: &str, status: StoryStatus, lag_secs: f64) -> SourceReport {
+        SourceReport {
+            source: source.to_string(),
+            status: status.as_str().to_string(),
+            lag_secs,
+        }
+    }
+
+    #[test]
+    fn test_fast_accurate_source_outweighs_aggregator() {
+        let mut tracker = SourceTracker::new(&SourceStatsCfg::default());
+        assert_eq!(tracker.weight("unknown"), 1.0);
+
+        let mut reports = Vec::new();
+        for _ in 0..50 {
+            reports.push(report("FinancialJuice", StoryStatus::FirstReport, 0.0));
+            reports.push(report("google-news", StoryStatus::Corroboration, 1800.0));
+            reports.push(report("google-news", StoryStatus::Repeat, 3600.0));
+        }
+        tracker.hydrate_reports(reports);
+
+        let mut signals = Vec::new();
+        for i in 0..40 {
+            let entry = Some(Decimal::new(60, 2));
+            signals.push(signal("FinancialJuice", 0.8, i % 10 != 0, entry));
+            signals.push(signal("google-news", 0.8, i % 2 == 0, entry));
+        }
+        tracker.set_outcomes(signals);
+
+        let fj = tracker.weight("FinancialJuice");
+        let gn = tracker.weight("google-news");
+        assert!(fj > 1.0 && gn < 1.0, "fj {fj}, gn {gn}");
+        assert!(fj <= 1.5 && gn >= 0.5);
+
+        let profile = tracker.profile("google-news");
+        assert!(
+            profile
+                .summary
+                .contains("first to 0% of 50 stories, median lag 1800s")
+        );
+        assert!(profile.summary.contains("40 resolved signals"));
+
+        // Outcomes are replaced, not accumulated
+        tracker.set_outcomes(Vec::new());
+        assert!(!tracker.profile("google-news").summary.contains("resolved"));
+    }
+
+    #[test]
+    fn test_unsettled_markets_are_ignored() {
+        let mut tracker = SourceTracker::new(&SourceStatsCfg::default());
+        let mut open = signal("reuters", 0.9, true, None);
+        for t in &mut open.tokens {
+            t.price = Decimal::new(5, 1);
+        }
+        tracker.set_outcomes(vec![open, signal("reuters", 0.9, false, None)]);
+        let summary = tracker.profile("reuters").summary;
+        // Confidence 0.9 -> probability 0.95 on a loss
+        assert!(
+            summary.contains("1 resolved signals with Brier score 0.90"),
+            "{summary}"
+        );
+        assert!(!summary.contains("trades"));
+    }
+
+    #[test]
+    fn test_pnl_of_the_side_bought() {
+        let mut tracker = SourceTracker::new(&SourceStatsCfg::default());
+        // Bought Yes at 0.60 and won; bought No (at 0.40) against a Yes
+        // signal and lost; bought No (at 0.30) and won
+        let yes_won = signal("reuters", 0.8, true, Some(Decimal::new(60, 2)));
+        let mut no_lost = signal("reuters", 0.8, true, Some(Decimal::new(60, 2)));
+        no_lost.side = Some("No".to_string());
+        let mut no_won = signal("reuters", 0.8, false, Some(Decimal::new(70, 2)));
+        no_won.side = Some("No".to_string());
+        tracker.set_outcomes(vec![yes_won, no_lost, no_won]);
+
+        // (0.40 - 0.40 + 0.70) / 3
+        let summary = tracker.profile("reuters").summary;
+        assert!(
+            summary.contains("+0.23 per share over 3 trades"),
+            "{summary}"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Rejects news too old to trade on, using the source's `published` time.
+//!
+//! An item is stale when it is older than its source's `maxAge`, published
+//! further in the future than `clockSkew` allows (usually a timezone bug), or
+//! published more than `watermarkGrace` before the newest item already seen
+//! from the same source. The watermarks are restored from the events table,
+//! so a restart does not replay a feed's backlog.
+
+use crate::config::config::StalenessCfg;
+use crate::core::types::RawNews;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Staleness {
+    TooOld,
+    Future,
+    BeforeWatermark,
+    Unpublished,
+}
+
+impl Staleness {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Staleness::TooOld => "too_old",
+            Staleness::Future => "future",
+            Staleness::BeforeWatermark => "before_watermark",
+            Staleness::Unpublished => "unpublished",
+        }
+    }
+}
+
+pub struct StalenessGate {
+    cfg: StalenessCfg,
+    /// Newest `published` time accepted per source
+    watermarks: HashMap<String, DateTime<Utc>>,
+}
+
+impl StalenessGate {
+    pub fn new(cfg: &StalenessCfg) -> Self {
+        Self {
+            cfg: cfg.clone(),
+            watermarks: HashMap::new(),
+        }
+    }
+
+    /// Restore per-source watermarks (startup hydration).
+    pub fn hydrate(&mut self, watermarks: impl IntoIterator<Item = (String, DateTime<Utc>)>) {
+        for (source, published) in watermarks {
+            self.advance(&source, published);
+        }
+    }
+
+    /// Accept or reject `news` at `now`, recording the ingest latency and
+    /// advancing the source's watermark when accepted.
+    pub fn check(&mut self, news: &RawNews, now: DateTime<Utc>) -> Result<(), Staleness> {
+        let Some(published) = news.published else {
+            return if self.cfg.enabled && self.cfg.require_published {
+                Err(Staleness::Unpublished)
+            } else {
+                Ok(())
+            };
+        };
+
+        let latency = (now - published).num_milliseconds() as f64 / 1000.0;
+        metrics::histogram!("strategy_news_ingest_latency_seconds", "source" => news.feed.clone())
+            .record(latency.max(0.0));
+        if !self.cfg.enabled {
+            return Ok(());
+        }
+
+        if published > now + duration(self.cfg.clock_skew) {
+            return Err(Staleness::Future);
+        }
+        if now - published > duration(self.max_age(&news.feed)) {
+            return Err(Staleness::TooOld);
+        }
+        if let Some(&watermark) = self.watermarks.get(&news.feed)
+            && published < watermark - duration(self.cfg.watermark_grace)
+        {
+            return Err(Staleness::BeforeWatermark);
+        }
+        self.advance(&news.feed, published);
+        Ok(())
+    }
+
+    fn max_age(&self, source: &str) -> std::time::Duration {
+        self.cfg
+            .by_source
+            .get(source)
+            .map(|s| s.max_age)
+            .unwrap_or(self.cfg.max_age)
+    }
+
+    fn advance(&mut self, source: &str, published: DateTime<Utc>) {
+        let watermark = self
+            .watermarks
+            .entry(source.to_string())
+            .or_insert(published);
+        *watermark = (*watermark).max(published);
+    }
+}
+
+fn duration(d: std::time::Duration) -> Duration {
+    Duration::from_std(d).unwrap_or(Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config::SourceStalenessCfg;
+
+    fn news(feed: &str, published: Option<DateTime<Utc>>) -> RawNews {
+        RawNews {
+            url: "http://example.com".to_string(),
+            title: "Fed cuts rates".to_string(),
+            description: String::new(),
+            feed: feed.to_string(),
+            published,
+            labels: vec![],
+            guid: None,
+            author: None,
+            updated: None,
+            body: None,
+        }
+    }
+
+    #[test]
+    fn test_staleness_gate() {
+        let mut cfg = StalenessCfg::default();
+        cfg.by_source.insert(
+            "FinancialJuice".to_string(),
+            SourceStalenessCfg {
+                max_age: std::time::Duration::from_secs(60),
+            },
+        );
+        let mut gate = StalenessGate::new(&cfg);
+        let now = Utc::now();
+        let ago = |m: i64| Some(now - Duration::minutes(m));
+
+        assert_eq!(gate.check(&news("reuters", ago(5)), now), Ok(()));
+        assert_eq!(
+            gate.check(&news("reuters", ago(16)), now),
+            Err(Staleness::TooOld)
+        );
+        // Per-source max age
+        assert_eq!(
+            gate.check(&news("FinancialJuice", ago(5)), now),
+            Err(Staleness::TooOld)
+        );
+        // Within the clock skew, then beyond it
+        assert_eq!(gate.check(&news("reuters", ago(-1)), now), Ok(()));
+        assert_eq!(
+            gate.check(&news("reuters", ago(-5)), now),
+            Err(Staleness::Future)
+        );
+        // No published time passes unless required
+        assert_eq!(gate.check(&news("reuters", None), now), Ok(()));
+    }
+
+    #[test]
+    fn test_watermark() {
+        let mut gate = StalenessGate::new(&StalenessCfg::default());
+        let now = Utc::now();
+        gate.hydrate([("reuters".to_string(), now - Duration::minutes(1))]);
+
+        // Out of order within the grace period
+        let older = news("reuters", Some(now - Duration::minutes(8)));
+        assert_eq!(gate.check(&older, now), Ok(()));
+        // Well behind the newest item from the same source
+        let late = news("reuters", Some(now - Duration::minutes(12)));
+        assert_eq!(gate.check(&late, now), Err(Staleness::BeforeWatermark));
+        // Other sources have their own watermark
+        let other = news("bloomberg", Some(now - Duration::minutes(12)));
+        assert_eq!(gate.check(&other, now), Ok(()));
+    }
+}
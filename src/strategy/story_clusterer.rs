@@ -0,0 +1,413 @@
+//! Groups news into stories so follow-ups are not mistaken for new events.
+//!
+//! An item joins the most similar live story when the combined score clears
+//! `minSimilarity`: SimHash closeness and embedding cosine, weighted, then
+//! decayed by the time since the story was last reported. Near-identical
+//! hashes (within `hammingThreshold`) join directly through the SimHash index.
+//! Within a story an item is a material update when enough of its tokens are
+//! new, a corroboration when it comes from a source the story has not seen,
+//! and a repeat otherwise.
+
+use crate::config::config::StoryCfg;
+use crate::strategy::sim_hash_cache::{SimHashCache, SimHashCacheConfig};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+
+/// Hashes kept per story for the SimHash component of the score.
+const MAX_STORY_HASHES: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoryStatus {
+    /// Starts a new story.
+    FirstReport,
+    /// Same story, reported by another source with nothing new.
+    Corroboration,
+    /// Same story with enough new content to be re-analysed.
+    MaterialUpdate,
+    /// Same story, same source, nothing new.
+    Repeat,
+}
+
+impl StoryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StoryStatus::FirstReport => "first_report",
+            StoryStatus::Corroboration => "corroboration",
+            StoryStatus::MaterialUpdate => "material_update",
+            StoryStatus::Repeat => "repeat",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoryAssignment {
+    pub cluster_id: i64,
+    pub status: StoryStatus,
+    /// Decayed similarity to the story joined; 1.0 for a first report.
+    pub similarity: f32,
+    /// Distinct sources that reported the story, including this item.
+    pub sources: usize,
+    /// Items in the story, including this one.
+    pub reports: usize,
+    /// Time since the story's first report; zero for a first report.
+    pub lag: chrono::Duration,
+}
+
+impl StoryAssignment {
+    /// Short description for the analyst prompt.
+    pub fn describe(&self) -> String {
+        match self.status {
+            StoryStatus::FirstReport => "first report of this story".to_string(),
+            StoryStatus::Corroboration => format!(
+                "corroborated: reported by {} independent sources",
+                self.sources
+            ),
+            StoryStatus::MaterialUpdate => format!(
+                "update to a developing story ({} earlier reports from {} sources)",
+                self.reports - 1,
+                self.sources
+            ),
+            StoryStatus::Repeat => "repeat of an earlier report".to_string(),
+        }
+    }
+}
+
+struct Story {
+    hashes: Vec<u64>,
+    /// Running mean of member embeddings
+    centroid: Option<Vec<f32>>,
+    embedded: usize,
+    reports: usize,
+    sources: HashSet<String>,
+    tokens: HashSet<String>,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+}
+
+impl Story {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            hashes: Vec::new(),
+            centroid: None,
+            embedded: 0,
+            reports: 0,
+            sources: HashSet::new(),
+            tokens: HashSet::new(),
+            first_seen: now,
+            last_seen: now,
+        }
+    }
+
+    fn add(
+        &mut self,
+        source: &str,
+        hash: u64,
+        embedding: Option<&[f32]>,
+        tokens: &[String],
+        now: DateTime<Utc>,
+    ) {
+        if self.hashes.len() == MAX_STORY_HASHES {
+            self.hashes.remove(0);
+        }
+        self.hashes.push(hash);
+        if let Some(e) = embedding {
+            let n = self.embedded as f32;
+            match &mut self.centroid {
+                Some(c) if c.len() == e.len() => {
+                    for (c, x) in c.iter_mut().zip(e) {
+                        *c = (*c * n + x) / (n + 1.0);
+                    }
+                }
+                _ => self.centroid = Some(e.to_vec()),
+            }
+            self.embedded += 1;
+        }
+        self.reports += 1;
+        self.sources.insert(source.to_string());
+        self.tokens.extend(tokens.iter().cloned());
+        self.first_seen = self.first_seen.min(now);
+        self.last_seen = self.last_seen.max(now);
+    }
+
+    /// Fraction of `tokens` the story has not seen yet. A story restored from
+    /// the database has no tokens, so novelty is unknown and its first
+    /// follow-up counts as an update.
+    fn novelty(&self, tokens: &[String]) -> f32 {
+        if tokens.is_empty() {
+            return 0.0;
+        }
+        if self.tokens.is_empty() {
+            return 1.0;
+        }
+        let new = tokens.iter().filter(|t| !self.tokens.contains(*t)).count();
+        new as f32 / tokens.len() as f32
+    }
+}
+
+pub struct StoryClusterer {
+    cfg: StoryCfg,
+    index: SimHashCache,
+    stories: HashMap<i64, Story>,
+    last_id: i64,
+}
+
+impl StoryClusterer {
+    pub fn new(cfg: &StoryCfg) -> Self {
+        let index = SimHashCache::new(SimHashCacheConfig {
+            capacity: NonZeroUsize::new(cfg.capacity.max(1)).unwrap_or(NonZeroUsize::MIN),
+            ttl_hours: cfg.ttl.as_secs().div_ceil(3600).max(1) as i64,
+            hamming_threshold: cfg.hamming_threshold,
+            bits: cfg.simhash_bits,
+            bands: cfg.simhash_bands,
+        });
+        Self {
+            cfg: cfg.clone(),
+            index,
+            stories: HashMap::new(),
+            last_id: 0,
+        }
+    }
+
+    pub fn ttl(&self) -> std::time::Duration {
+        self.cfg.ttl
+    }
+
+    /// Number of stories still within the TTL.
+    pub fn len(&self) -> usize {
+        self.stories.len()
+    }
+
+    /// SimHash of an item's tokens, as stored by `assign` and `restore`.
+    pub fn sim_hash(&self, tokens: &[String]) -> u64 {
+        self.index.sim_hash(tokens)
+    }
+
+    /// Assign an item to a story (a new one if nothing is similar enough).
+    /// `tokens` are compared for novelty; `embedding` is optional.
+    pub fn assign(
+        &mut self,
+        source: &str,
+        hash: u64,
+        embedding: Option<&[f32]>,
+        tokens: &[String],
+        now: DateTime<Utc>,
+    ) -> StoryAssignment {
+        self.expire(now);
+
+        let best = self.closest(hash, embedding, now);
+        let (cluster_id, status, similarity) = match best {
+            Some((cluster_id, similarity)) => {
+                let story = &self.stories[&cluster_id];
+                let status = if story.novelty(tokens) >= self.cfg.min_novelty {
+                    StoryStatus::MaterialUpdate
+                } else if !story.sources.contains(source) {
+                    StoryStatus::Corroboration
+                } else {
+                    StoryStatus::Repeat
+                };
+                (cluster_id, status, similarity)
+            }
+            None => {
+                let cluster_id = self.next_id(now);
+                self.stories.insert(cluster_id, Story::new(now));
+                (cluster_id, StoryStatus::FirstReport, 1.0)
+            }
+        };
+
+        let story = self.stories.get_mut(&cluster_id).expect("story exists");
+        story.add(source, hash, embedding, tokens, now);
+        self.index.insert(hash, cluster_id, now.timestamp());
+
+        StoryAssignment {
+            cluster_id,
+            status,
+            similarity,
+            sources: story.sources.len(),
+            reports: story.reports,
+            lag: (now - story.first_seen).max(chrono::Duration::zero()),
+        }
+    }
+
+    /// Re-add a persisted item to its story (startup hydration), oldest first.
+    pub fn restore(&mut self, cluster_id: i64, source: &str, hash: u64, seen: DateTime<Utc>) {
+        self.last_id = self.last_id.max(cluster_id);
+        self.stories
+            .entry(cluster_id)
+            .or_insert_with(|| Story::new(seen))
+            .add(source, hash, None, &[], seen);
+        self.index.insert(hash, cluster_id, seen.timestamp());
+    }
+
+    /// Best live story for the item and its decayed score, if above `minSimilarity`.
+    fn closest(
+        &mut self,
+        hash: u64,
+        embedding: Option<&[f32]>,
+        now: DateTime<Utc>,
+    ) -> Option<(i64, f32)> {
+        // Near-identical text: same story regardless of the other signals
+        if let Some(&(cluster_id, dist)) = self
+            .index
+            .matches(hash, now.timestamp())
+            .iter()
+            .find(|(id, _)| self.stories.contains_key(id))
+        {
+            return Some((cluster_id, 1.0 - dist as f32 / self.index.bits() as f32));
+        }
+
+        let bits = self.index.bits();
+        let weights = self.cfg.simhash_weight + self.cfg.embedding_weight;
+        let half_life = self.cfg.half_life.as_secs_f32().max(1.0);
+        self.stories
+            .iter()
+            .map(|(&id, story)| {
+                let dist = story
+                    .hashes
+                    .iter()
+                    .map(|h| (h ^ hash).count_ones())
+                    .min()
+                    .unwrap_or(bits);
+                let hash_sim = 1.0 - dist as f32 / bits as f32;
+                let cosine = embedding
+                    .zip(story.centroid.as_deref())
+                    .map(|(a, b)| cosine(a, b));
+                let similarity = match cosine {
+                    Some(cos) if weights > 0.0 => {
+                        (self.cfg.simhash_weight * hash_sim + self.cfg.embedding_weight * cos)
+                            / weights
+                    }
+                    _ => hash_sim,
+                };
+                let age = (now - story.last_seen).num_seconds().max(0) as f32;
+                (id, similarity * 0.5f32.powf(age / half_life))
+            })
+            .filter(|(_, score)| *score >= self.cfg.min_similarity)
+            .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+    }
+
+    fn expire(&mut self, now: DateTime<Utc>) {
+        let ttl = self.cfg.ttl.as_secs() as i64;
+        self.stories
+            .retain(|_, story| (now - story.last_seen).num_seconds() <= ttl);
+    }
+
+    /// Cluster IDs are microsecond timestamps, bumped to stay unique.
+    fn next_id(&mut self, now: DateTime<Utc>) -> i64 {
+        self.last_id = now.timestamp_micros().max(self.last_id + 1);
+        self.last_id
+    }
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom > 0.0 { dot / denom } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn tokens(text: &str) -> Vec<String> {
+        text.split_whitespace().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_story_statuses() {
+        let mut clusterer = StoryClusterer::new(&StoryCfg::default());
+        let now = Utc::now();
+        let first = tokens("fed cuts rates by 25bps powell signals more");
+        let h = clusterer.sim_hash(&first);
+        let e = [1.0, 0.0, 0.0];
+
+        let a = clusterer.assign("reuters", h, Some(&e), &first, now);
+        assert_eq!(a.status, StoryStatus::FirstReport);
+
+        // Same text from the same source
+        let b = clusterer.assign("reuters", h, Some(&e), &first, now);
+        assert_eq!(b.status, StoryStatus::Repeat);
+        assert_eq!(b.cluster_id, a.cluster_id);
+
+        // Another source, close wording and embedding
+        let other = tokens("fed cuts rates by 25bps powell signals further cuts");
+        let h2 = clusterer.sim_hash(&other);
+        let c = clusterer.assign(
+            "bloomberg",
+            h2,
+            Some(&[0.98, 0.1, 0.0]),
+            &other,
+            now + Duration::seconds(90),
+        );
+        assert_eq!(c.cluster_id, a.cluster_id);
+        assert_eq!(c.status, StoryStatus::Corroboration);
+        assert_eq!(c.sources, 2);
+        assert_eq!(c.lag, Duration::seconds(90));
+        assert_eq!(a.lag, Duration::zero());
+
+        // Same story with new facts
+        let update = tokens("fed cuts rates dissent from three members markets slide yields jump");
+        let h3 = clusterer.sim_hash(&update);
+        let d = clusterer.assign("reuters", h3, Some(&[0.95, 0.2, 0.0]), &update, now);
+        assert_eq!(d.cluster_id, a.cluster_id);
+        assert_eq!(d.status, StoryStatus::MaterialUpdate);
+
+        // Unrelated story
+        let unrelated = tokens("bitcoin etf approved sec filing");
+        let h4 = clusterer.sim_hash(&unrelated);
+        let f = clusterer.assign("reuters", h4, Some(&[0.0, 0.0, 1.0]), &unrelated, now);
+        assert_eq!(f.status, StoryStatus::FirstReport);
+        assert_ne!(f.cluster_id, a.cluster_id);
+        assert_eq!(clusterer.len(), 2);
+    }
+
+    #[test]
+    fn test_restored_story_novelty() {
+        let mut clusterer = StoryClusterer::new(&StoryCfg::default());
+        let now = Utc::now();
+        let text = tokens("fed cuts rates by 25bps powell signals more");
+        let h = clusterer.sim_hash(&text);
+        clusterer.restore(7, "reuters", h, now - Duration::minutes(5));
+
+        // The restored story's text is unknown: not dropped as a repeat
+        let a = clusterer.assign("reuters", h, None, &text, now);
+        assert_eq!(a.cluster_id, 7);
+        assert_eq!(a.status, StoryStatus::MaterialUpdate);
+
+        // From then on the story's tokens are known
+        let b = clusterer.assign("reuters", h, None, &text, now);
+        assert_eq!(b.status, StoryStatus::Repeat);
+    }
+
+    #[test]
+    fn test_story_time_decay() {
+        let mut clusterer = StoryClusterer::new(&StoryCfg::default());
+        let now = Utc::now();
+        let t = tokens("ecb holds rates lagarde warns on inflation");
+        let h = clusterer.sim_hash(&t);
+        let a = clusterer.assign("ft", h, Some(&[1.0, 0.0]), &t, now);
+
+        // Similar embedding but slightly different wording: decays out of the story
+        let t2 = tokens("ecb holds rates lagarde cautious on inflation outlook");
+        let h2 = clusterer.sim_hash(&t2);
+        let later = now + Duration::hours(40);
+        let b = clusterer.assign("wsj", h2, Some(&[0.9, 0.3]), &t2, later);
+        assert_ne!(b.cluster_id, a.cluster_id);
+        assert_eq!(b.status, StoryStatus::FirstReport);
+
+        // Restored stories keep their IDs and new IDs stay unique
+        let mut restored = StoryClusterer::new(&StoryCfg::default());
+        restored.restore(a.cluster_id, "ft", h, now);
+        let c = restored.assign("ft", h, None, &t, now);
+        assert_eq!(c.cluster_id, a.cluster_id);
+        assert_eq!(c.status, StoryStatus::MaterialUpdate);
+        let fresh = tokens("opec agrees output cut");
+        let hf = restored.sim_hash(&fresh);
+        assert!(restored.assign("ft", hf, None, &fresh, now).cluster_id > a.cluster_id);
+    }
+}
@@ -0,0 +1,637 @@
+//! time_expressions.rs
+//!
+//! Turns time expressions in normalized news text into UTC windows, with day,
+//! week and month boundaries taken in the configured timezone:
+//!   - explicit dates ("march 15", "15 march 2026", "15/03/2026", "2026-03-15")
+//!   - months ("in march", "march 2026") and years ("in 2027")
+//!   - weekdays ("by friday", "next tuesday") and "today" / "tomorrow"
+//!   - relative spans ("within 48 hours", "in two weeks", "the next 3 days")
+//!   - named events from config ("fomc meeting")
+//!   - coarse phrases ("year-end", "next week", "q4")
+//!
+//! "by" / "before" / "until" turn a window into a deadline starting now.
+
+use std::cmp::Reverse;
+use std::ops::Range;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use deunicode::deunicode;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+use crate::config::config::TimeExprCfg;
+use crate::strategy::event_features::TimeWindow;
+
+const RECENT_PAST_DAYS: i64 = 30;
+
+const DEADLINE: &str = r"(?:(?P<by>by|before|until|till|no later than)\s+)?";
+const MONTH: &str = r"(?P<month>jan(?:uary)?|feb(?:ruary)?|mar(?:ch)?|apr(?:il)?|may|june?|july?|aug(?:ust)?|sept?(?:ember)?|oct(?:ober)?|nov(?:ember)?|dec(?:ember)?)\b\.?";
+
+lazy_static! {
+    static ref RE_ISO_DATE: Regex =
+        Regex::new(&format!(r"\b{DEADLINE}(?P<y>\d{{4}})-(?P<m>\d{{1,2}})-(?P<d>\d{{1,2}})\b"))
+            .unwrap();
+    static ref RE_NUMERIC_DATE: Regex = Regex::new(&format!(
+        r"\b{DEADLINE}(?P<a>\d{{1,2}})(?P<sep>[/.-])(?P<b>\d{{1,2}})[/.-](?P<y>\d{{4}}|\d{{2}})\b"
+    ))
+    .unwrap();
+    static ref RE_MONTH_DAY: Regex = Regex::new(&format!(
+        r"\b{DEADLINE}{MONTH}\s+(?P<d>\d{{1,2}})(?:st|nd|rd|th)?\b(?:,?\s+(?P<y>\d{{4}})\b)?"
+    ))
+    .unwrap();
+    static ref RE_DAY_MONTH: Regex = Regex::new(&format!(
+        r"\b{DEADLINE}(?P<d>\d{{1,2}})(?:st|nd|rd|th)?\s+(?:of\s+)?{MONTH}(?:,?\s+(?P<y>\d{{4}})\b)?"
+    ))
+    .unwrap();
+    // A bare month name needs a year or a preposition: "may" and "march" are also words
+    static ref RE_MONTH_YEAR: Regex = Regex::new(&format!(
+        r"\b{DEADLINE}(?:(?:in|during|through|end of|early|late|mid)(?:\s+|-))?{MONTH}\s+(?P<y>\d{{4}})\b"
+    ))
+    .unwrap();
+    static ref RE_MONTH: Regex = Regex::new(&format!(
+        r"\b(?:(?P<by>by|before|until|till)|in|during|through|end of|early|late|mid)(?:\s+|-){MONTH}"
+    ))
+    .unwrap();
+    static ref RE_WEEKDAY: Regex = Regex::new(&format!(
+        r"\b{DEADLINE}(?:(?P<rel>this|next|last|coming|on)\s+)?(?P<wd>mon|tues|wednes|thurs|fri|satur|sun)day\b"
+    ))
+    .unwrap();
+    static ref RE_RELATIVE_DAY: Regex =
+        Regex::new(&format!(r"\b{DEADLINE}(?P<day>today|tonight|tomorrow)\b")).unwrap();
+    static ref RE_RELATIVE: Regex = Regex::new(
+        r"\b(?P<prep>within|in|(?:over |in |within |during )?the (?:next|coming)|next)\s+(?P<n>\d+|an?|one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve|a few|a couple of|couple of|several)\s+(?P<unit>hour|day|week|month|year)s?\b"
+    )
+    .unwrap();
+    static ref RE_PHRASE: Regex = Regex::new(&format!(
+        r"\b{DEADLINE}(?:(?P<year_end>year[- ]end|end of (?:the )?year)|(?P<phrase>this week|next week|this month|next month|this year|next year)|(?P<q>q[1-4])(?:\s+(?P<y>\d{{4}}))?)\b"
+    ))
+    .unwrap();
+    static ref RE_YEAR: Regex = Regex::new(&format!(
+        r"\b{DEADLINE}(?:(?:in|during|for|through|end of)\s+)?(?P<y>20\d{{2}})\b"
+    ))
+    .unwrap();
+    static ref RE_DEADLINE_BEFORE: Regex =
+        Regex::new(r"\b(?:by|before|until|till|ahead of)\s+(?:the\s+)?$").unwrap();
+}
+
+/// Moves a timestamp by a number of units.
+type Shift = fn(DateTime<Utc>, i64) -> Option<DateTime<Utc>>;
+
+/// A time expression found in the text and the window it stands for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeMention {
+    /// Byte range in the normalized text
+    pub span: Range<usize>,
+    pub window: TimeWindow,
+}
+
+/// A scheduled event referred to by name, e.g. "FOMC meeting".
+#[derive(Debug, Clone)]
+struct NamedEvent {
+    /// Lowercased, ASCII-folded
+    aliases: Vec<String>,
+    /// Sorted
+    dates: Vec<NaiveDate>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimeExpressionParser {
+    tz: Tz,
+    /// Read ambiguous numeric dates ("03/04/2026") as day/month
+    day_first: bool,
+    named_events: Vec<NamedEvent>,
+}
+
+impl Default for TimeExpressionParser {
+    fn default() -> Self {
+        Self {
+            tz: Tz::UTC,
+            day_first: false,
+            named_events: Vec::new(),
+        }
+    }
+}
+
+impl TimeExpressionParser {
+    pub fn new(cfg: &TimeExprCfg) -> Result<Self> {
+        let tz: Tz = cfg
+            .timezone
+            .parse()
+            .map_err(|e| anyhow::anyhow!("{}", e))
+            .with_context(|| format!("invalid timezone '{}'", cfg.timezone))?;
+
+        let named_events = cfg
+            .named_events
+            .iter()
+            .map(|event| {
+                let mut dates = event.dates.clone();
+                dates.sort();
+                NamedEvent {
+                    aliases: std::iter::once(&event.name)
+                        .chain(&event.aliases)
+                        .map(|a| deunicode(a.trim()).to_lowercase())
+                        .filter(|a| !a.is_empty())
+                        .collect(),
+                    dates,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            tz,
+            day_first: cfg.day_first,
+            named_events,
+        })
+    }
+
+    /// The window of the leftmost time expression that is not entirely in the past.
+    pub fn window(&self, text: &str, now: DateTime<Utc>) -> Option<TimeWindow> {
+        self.parse(text, now).into_iter().next().map(|m| m.window)
+    }
+
+    /// All time expressions in `text` (lowercase, ASCII), in order. Overlaps are
+    /// resolved leftmost-longest, so the "2026" of "march 15, 2026" is not
+    /// reported on its own. Windows that ended before `now` are dropped: a past
+    /// reference ("on monday", "in 2020") says nothing about what resolves next.
+    pub fn parse(&self, text: &str, now: DateTime<Utc>) -> Vec<TimeMention> {
+        let today = now.with_timezone(&self.tz).date_naive();
+        let mut found: Vec<TimeMention> = Vec::new();
+
+        let mut scan = |re: &Regex, f: &dyn Fn(&Captures) -> Option<TimeWindow>| {
+            for caps in re.captures_iter(text) {
+                if let Some(window) = f(&caps) {
+                    let m = caps.get(0).unwrap();
+                    let window = if caps.name("by").is_some() {
+                        deadline(now, window)
+                    } else {
+                        window
+                    };
+                    found.push(TimeMention {
+                        span: m.start()..m.end(),
+                        window,
+                    });
+                }
+            }
+        };
+
+        scan(&RE_ISO_DATE, &|c| {
+            let date = NaiveDate::from_ymd_opt(num(c, "y")?, num(c, "m")?, num(c, "d")?)?;
+            Some(self.days(date, date))
+        });
+        scan(&RE_NUMERIC_DATE, &|c| {
+            let (a, b): (u32, u32) = (num(c, "a")?, num(c, "b")?);
+            let y: i32 = num(c, "y")?;
+            let y = match c.name("y")?.as_str().len() {
+                4 => y,
+                // Two-digit years only with slashes: "3-2-24" is more likely a score
+                _ if &c["sep"] == "/" => 2000 + y,
+                _ => return None,
+            };
+            let (day, month) = if a > 12 || (b <= 12 && self.day_first) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            let date = NaiveDate::from_ymd_opt(y, month, day)?;
+            Some(self.days(date, date))
+        });
+        let month_day = |c: &Captures| {
+            let month = month_number(&c["month"])?;
+            let day: u32 = num(c, "d")?;
+            let (first, last) = match c.name("y") {
+                Some(y) => {
+                    let date = NaiveDate::from_ymd_opt(y.as_str().parse().ok()?, month, day)?;
+                    (date, date)
+                }
+                None => upcoming(today, |year| {
+                    NaiveDate::from_ymd_opt(year, month, day).map(|d| (d, d))
+                })?,
+            };
+            Some(self.days(first, last))
+        };
+        scan(&RE_MONTH_DAY, &month_day);
+        scan(&RE_DAY_MONTH, &month_day);
+        scan(&RE_MONTH_YEAR, &|c| {
+            let (first, last) = month_bounds(num(c, "y")?, month_number(&c["month"])?)?;
+            Some(self.days(first, last))
+        });
+        scan(&RE_MONTH, &|c| {
+            let month = month_number(&c["month"])?;
+            let (first, last) = upcoming(today, |year| month_bounds(year, month))?;
+            Some(self.days(first, last))
+        });
+        scan(&RE_WEEKDAY, &|c| {
+            let target = weekday_index(&c["wd"])?;
+            let forward = (target + 7 - today.weekday().num_days_from_monday() as i64) % 7;
+            let offset = match c.name("rel").map(|r| r.as_str()) {
+                // The one in the following calendar week
+                Some("next") => 7 - today.weekday().num_days_from_monday() as i64 + target,
+                Some("last") => forward - 7,
+                Some("this" | "coming") => forward,
+                _ if c.name("by").is_some() => forward,
+                // "on friday": the closest one, which in news is often a past day
+                _ if forward <= 3 => forward,
+                _ => forward - 7,
+            };
+            let date = today + Duration::days(offset);
+            Some(self.days(date, date))
+        });
+        scan(&RE_RELATIVE_DAY, &|c| {
+            let date = match &c["day"] {
+                "tomorrow" => today.succ_opt()?,
+                _ => today,
+            };
+            Some(self.days(date, date))
+        });
+        scan(&RE_RELATIVE, &|c| {
+            let n = word_number(&c["n"])?;
+            // Absurd counts ("within 100000000 days") overflow to None
+            let (span, unit): (i64, Shift) = match &c["unit"] {
+                "hour" => (n, |t, k| t.checked_add_signed(Duration::try_hours(k)?)),
+                "day" => (n, |t, k| t.checked_add_signed(Duration::try_days(k)?)),
+                "week" => (n, |t, k| t.checked_add_signed(Duration::try_weeks(k)?)),
+                "month" => (n, add_months),
+                _ => (n.checked_mul(12)?, add_months),
+            };
+            if c["prep"].trim() == "in" {
+                // "in two weeks": around that point, give or take one unit
+                let step = if &c["unit"] == "year" { 12 } else { 1 };
+                Some(TimeWindow {
+                    start: unit(now, span.checked_sub(step)?)?.max(now),
+                    end: unit(now, span.checked_add(step)?)?,
+                })
+            } else {
+                Some(TimeWindow {
+                    start: now,
+                    end: unit(now, span)?,
+                })
+            }
+        });
+        scan(&RE_PHRASE, &|c| {
+            if c.name("year_end").is_some() {
+                let (_, last) = month_bounds(today.year(), 12)?;
+                return Some(TimeWindow {
+                    start: now,
+                    end: self.days(last, last).end,
+                });
+            }
+            if let Some(q) = c.name("q") {
+                let q: u32 = q.as_str()[1..].parse().ok()?;
+                let quarter = |year| {
+                    let (first, _) = month_bounds(year, q * 3 - 2)?;
+                    let (_, last) = month_bounds(year, q * 3)?;
+                    Some((first, last))
+                };
+                let (first, last) = match c.name("y") {
+                    Some(y) => quarter(y.as_str().parse().ok()?)?,
+                    None => upcoming(today, quarter)?,
+                };
+                return Some(self.days(first, last));
+            }
+            let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            let (first, last) = match &c["phrase"] {
+                "this week" => (monday, monday + Duration::days(6)),
+                "next week" => (monday + Duration::days(7), monday + Duration::days(13)),
+                "this month" => month_bounds(today.year(), today.month())?,
+                "next month" => {
+                    let next = today.with_day(1)?.checked_add_months(Months::new(1))?;
+                    month_bounds(next.year(), next.month())?
+                }
+                "this year" => year_bounds(today.year())?,
+                _ => year_bounds(today.year() + 1)?,
+            };
+            Some(self.days(first, last))
+        });
+        scan(&RE_YEAR, &|c| {
+            let (first, last) = year_bounds(num(c, "y")?)?;
+            Some(self.days(first, last))
+        });
+
+        for event in &self.named_events {
+            let Some(date) = event.dates.iter().find(|d| **d >= today).copied() else {
+                continue;
+            };
+            for alias in &event.aliases {
+                for (start, _) in text.match_indices(alias.as_str()) {
+                    let end = start + alias.len();
+                    if !on_word_boundaries(text, start, end) {
+                        continue;
+                    }
+                    let window = self.days(date, date);
+                    let (start, window) = match RE_DEADLINE_BEFORE.find(&text[..start]) {
+                        Some(prep) => (prep.start(), deadline(now, window)),
+                        None => (start, window),
+                    };
+                    found.push(TimeMention {
+                        span: start..end,
+                        window,
+                    });
+                }
+            }
+        }
+
+        found.sort_by_key(|m| (m.span.start, Reverse(m.span.end)));
+        let mut mentions: Vec<TimeMention> = Vec::new();
+        let mut covered_to = 0;
+        for mention in found {
+            if mention.span.start < covered_to {
+                continue;
+            }
+            covered_to = mention.span.end;
+            mentions.push(mention);
+        }
+        mentions.retain(|m| m.window.end >= now);
+        mentions
+    }
+
+    /// From the start of `first` to the end of `last`, local time.
+    fn days(&self, first: NaiveDate, last: NaiveDate) -> TimeWindow {
+        let next = last.succ_opt().unwrap_or(last);
+        TimeWindow {
+            start: self.local_midnight(first),
+            end: self.local_midnight(next) - Duration::seconds(1),
+        }
+    }
+
+    fn local_midnight(&self, date: NaiveDate) -> DateTime<Utc> {
+        let naive = date.and_time(NaiveTime::MIN);
+        self.tz
+            .from_local_datetime(&naive)
+            .earliest()
+            // Midnight skipped by a DST change: the first instant of that day
+            .or_else(|| {
+                self.tz
+                    .from_local_datetime(&(naive + Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&naive))
+    }
+}
+
+/// "by friday": anything from now until the end of the window.
+fn deadline(now: DateTime<Utc>, window: TimeWindow) -> TimeWindow {
+    TimeWindow {
+        start: now.min(window.end),
+        end: window.end,
+    }
+}
+
+/// For a date without a year: the earliest occurrence that ended at most
+/// `RECENT_PAST_DAYS` ago. "march 2" in a March 10 article is a past
+/// reference, "jan 20" most likely next year's.
+fn upcoming(
+    today: NaiveDate,
+    range_in: impl Fn(i32) -> Option<(NaiveDate, NaiveDate)>,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let cutoff = today - Duration::days(RECENT_PAST_DAYS);
+    (today.year() - 1..=today.year() + 1)
+        .filter_map(range_in)
+        .find(|(_, last)| *last >= cutoff)
+}
+
+fn month_bounds(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+    Some((first, last))
+}
+
+fn year_bounds(year: i32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((
+        NaiveDate::from_ymd_opt(year, 1, 1)?,
+        NaiveDate::from_ymd_opt(year, 12, 31)?,
+    ))
+}
+
+fn add_months(t: DateTime<Utc>, months: i64) -> Option<DateTime<Utc>> {
+    let count = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months >= 0 {
+        t.checked_add_months(count)
+    } else {
+        t.checked_sub_months(count)
+    }
+}
+
+fn num<T: std::str::FromStr>(c: &Captures, name: &str) -> Option<T> {
+    c.name(name)?.as_str().parse().ok()
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    let month = match name.get(..3)? {
+        "jan" => 1,
+        "feb" => 2,
+        "mar" => 3,
+        "apr" => 4,
+        "may" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "aug" => 8,
+        "sep" => 9,
+        "oct" => 10,
+        "nov" => 11,
+        "dec" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+/// Days from Monday.
+fn weekday_index(prefix: &str) -> Option<i64> {
+    ["mon", "tues", "wednes", "thurs", "fri", "satur", "sun"]
+        .iter()
+        .position(|d| *d == prefix)
+        .map(|i| i as i64)
+}
+
+fn word_number(word: &str) -> Option<i64> {
+    let n = match word {
+        "a" | "an" | "one" => 1,
+        "two" | "a couple of" | "couple of" => 2,
+        "three" | "a few" | "several" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        "eleven" => 11,
+        "twelve" => 12,
+        digits => digits.parse().ok()?,
+    };
+    Some(n)
+}
+
+fn on_word_boundaries(text: &str, start: usize, end: usize) -> bool {
+    let is_word = |c: char| c.is_alphanumeric();
+    !text[..start].ends_with(is_word) && !text[end..].starts_with(is_word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config::NamedEventCfg;
+
+    // Wednesday 2026-03-11, 15:00 UTC
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 11, 15, 0, 0).unwrap()
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, s).unwrap()
+    }
+
+    fn day(y: i32, m: u32, d: u32) -> TimeWindow {
+        TimeWindow {
+            start: utc(y, m, d, 0, 0, 0),
+            end: utc(y, m, d, 23, 59, 59),
+        }
+    }
+
+    fn window(parser: &TimeExpressionParser, text: &str) -> Option<TimeWindow> {
+        parser.window(text, now())
+    }
+
+    #[test]
+    fn test_explicit_dates() {
+        let parser = TimeExpressionParser::default();
+        assert_eq!(window(&parser, "vote on march 15"), Some(day(2026, 3, 15)));
+        assert_eq!(
+            window(&parser, "15th of march, 2027"),
+            Some(day(2027, 3, 15))
+        );
+        assert_eq!(
+            window(&parser, "deadline 15/03/2026"),
+            Some(day(2026, 3, 15))
+        );
+        assert_eq!(
+            window(&parser, "report due 2026-04-02"),
+            Some(day(2026, 4, 2))
+        );
+        // Ambiguous numeric dates follow dayFirst (month first by default)
+        assert_eq!(window(&parser, "on 04/05/2026"), Some(day(2026, 4, 5)));
+        let day_first = TimeExpressionParser {
+            day_first: true,
+            ..Default::default()
+        };
+        assert_eq!(window(&day_first, "on 04/05/2026"), Some(day(2026, 5, 4)));
+        // Without a year: recent dates are past references, older ones roll over
+        assert_eq!(window(&parser, "talks on march 2"), None);
+        assert_eq!(window(&parser, "jan 20 summit"), Some(day(2027, 1, 20)));
+        // Scores and versions are not dates
+        assert_eq!(window(&parser, "won 3-2-24 on penalties"), None);
+    }
+
+    #[test]
+    fn test_months_years_and_phrases() {
+        let parser = TimeExpressionParser::default();
+        let april = window(&parser, "rate cut expected in april").unwrap();
+        assert_eq!(april.start, utc(2026, 4, 1, 0, 0, 0));
+        assert_eq!(april.end, utc(2026, 4, 30, 23, 59, 59));
+        // "may" alone is a verb
+        assert_eq!(window(&parser, "the fed may cut"), None);
+        assert_eq!(
+            window(&parser, "may 2027 election").unwrap().start,
+            utc(2027, 5, 1, 0, 0, 0)
+        );
+
+        let by_2027 = window(&parser, "recession by 2027").unwrap();
+        assert_eq!(by_2027.start, now());
+        assert_eq!(by_2027.end, utc(2027, 12, 31, 23, 59, 59));
+        // Past references are ignored
+        assert_eq!(window(&parser, "as in 2020, markets fell"), None);
+
+        let q2 = window(&parser, "earnings in q2").unwrap();
+        assert_eq!(q2.start, utc(2026, 4, 1, 0, 0, 0));
+        assert_eq!(q2.end, utc(2026, 6, 30, 23, 59, 59));
+        let next_week = window(&parser, "outlook for next week").unwrap();
+        assert_eq!(next_week.start, utc(2026, 3, 16, 0, 0, 0));
+        assert_eq!(next_week.end, utc(2026, 3, 22, 23, 59, 59));
+        let year_end = window(&parser, "target by year-end").unwrap();
+        assert_eq!(year_end.end, utc(2026, 12, 31, 23, 59, 59));
+    }
+
+    #[test]
+    fn test_weekdays_and_relative() {
+        let parser = TimeExpressionParser::default();
+        let by_friday = window(&parser, "deal by friday").unwrap();
+        assert_eq!(by_friday.start, now());
+        assert_eq!(by_friday.end, utc(2026, 3, 13, 23, 59, 59));
+        assert_eq!(window(&parser, "next tuesday"), Some(day(2026, 3, 17)));
+        assert_eq!(window(&parser, "tomorrow"), Some(day(2026, 3, 12)));
+        // "said on monday" is two days ago
+        assert_eq!(window(&parser, "powell said on monday"), None);
+
+        let within = window(&parser, "ceasefire within 48 hours").unwrap();
+        assert_eq!(within.start, now());
+        assert_eq!(within.end, now() + Duration::hours(48));
+        let in_two_weeks = window(&parser, "vote in two weeks").unwrap();
+        assert_eq!(in_two_weeks.start, now() + Duration::weeks(1));
+        assert_eq!(in_two_weeks.end, now() + Duration::weeks(3));
+        let next_months = window(&parser, "over the next three months").unwrap();
+        assert_eq!(next_months.end, utc(2026, 6, 11, 15, 0, 0));
+
+        // Counts past chrono's range give no window instead of panicking
+        for text in [
+            "within 100000000 days",
+            "in 99999999999 hours",
+            "over the next 9000000000000000000 weeks",
+            "within 5000000000 months",
+            "in 800000000000000000 years",
+            "within 99999999999999999999999 days",
+        ] {
+            assert_eq!(window(&parser, text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_named_events_and_timezone() {
+        let cfg = TimeExprCfg {
+            timezone: "America/New_York".to_string(),
+            named_events: vec![NamedEventCfg {
+                name: "FOMC meeting".to_string(),
+                aliases: vec!["fed meeting".to_string()],
+                dates: vec![
+                    NaiveDate::from_ymd_opt(2026, 4, 29).unwrap(),
+                    NaiveDate::from_ymd_opt(2026, 1, 28).unwrap(),
+                    NaiveDate::from_ymd_opt(2026, 3, 18).unwrap(),
+                ],
+            }],
+            ..Default::default()
+        };
+        let parser = TimeExpressionParser::new(&cfg).unwrap();
+
+        // The next scheduled date, as a New York day (EDT, UTC-4)
+        let fomc = window(&parser, "traders eye the fomc meeting").unwrap();
+        assert_eq!(fomc.start, utc(2026, 3, 18, 4, 0, 0));
+        assert_eq!(fomc.end, utc(2026, 3, 19, 3, 59, 59));
+        let before = window(&parser, "no cut before the fed meeting").unwrap();
+        assert_eq!(before.start, now());
+        assert_eq!(before.end, fomc.end);
+
+        // 01:00 UTC on Mar 12 is still Mar 11 in New York
+        let late = utc(2026, 3, 12, 1, 0, 0);
+        assert_eq!(
+            parser.window("tomorrow", late).unwrap().start,
+            utc(2026, 3, 12, 4, 0, 0)
+        );
+
+        let bad = TimeExprCfg {
+            timezone: "Mars/Olympus".to_string(),
+            ..Default::default()
+        };
+        assert!(TimeExpressionParser::new(&bad).is_err());
+    }
+
+    #[test]
+    fn test_leftmost_mention_wins() {
+        let parser = TimeExpressionParser::default();
+        let mentions = parser.parse("by march 20, 2026 or in q3", now());
+        assert_eq!(mentions.len(), 2);
+        assert_eq!(mentions[0].span, 0..17);
+        assert_eq!(mentions[0].window.end, utc(2026, 3, 20, 23, 59, 59));
+        assert_eq!(mentions[1].window.start, utc(2026, 7, 1, 0, 0, 0));
+    }
+}
@@ -1,20 +1,26 @@
 //! Provides:
 //!   - TokenizationConfig: enables/disables features
 //!   - TokenizedNews: wrapper with normalized text, tokens, stems, n-grams
-//!   - normalize_for_matching
+//!   - normalize_for_matching / normalize_unicode
 //!   - tokenize_basic (internal)
+//!   - tokenize_unicode (internal, non-English text)
 
 use crate::core::types::RawNews;
-use crate::strategy::normalizers::normalize_for_matching;
+use crate::strategy::language::Language;
+use crate::strategy::normalizers::{normalize_for_matching, normalize_unicode};
+use deunicode::deunicode;
 use lazy_static::lazy_static;
 use regex::Regex;
-use rust_stemmers::{Algorithm, Stemmer};
+use rust_stemmers::Stemmer;
 use std::collections::HashSet;
 
 /// Configuration for the tokenization pipeline.
 #[derive(Debug, Clone)]
 pub struct TokenizationConfig {
-    /// Whether to apply stemming (Porter) on tokens.
+    /// Whether to detect the language and tokenize/stem non-English text with
+    /// that language's rules. Off means everything is treated as English.
+    pub detect_language: bool,
+    /// Whether to apply stemming (Snowball, per language) on tokens.
     pub use_stemming: bool,
     /// Whether to generate bigrams from tokens.
     pub generate_bigrams: bool,
@@ -29,6 +35,7 @@ pub struct TokenizationConfig {
 impl Default for TokenizationConfig {
     fn default() -> Self {
         Self {
+            detect_language: true,
             use_stemming: true,
             generate_bigrams: true,
             generate_trigrams: true,
@@ -59,21 +66,41 @@ pub struct TokenizedNews {
     /// Trigrams (space-joined, e.g. "ecb cuts rates").
     #[allow(dead_code)]
     pub trigrams: Vec<String>,
+    /// Detected language (English when detection is disabled).
+    pub language: Language,
 }
 
 impl TokenizedNews {
     /// Build a TokenizedNews from a RawNews using the given configuration.
     pub fn from_raw(raw: RawNews, cfg: &TokenizationConfig) -> Self {
-        // 1) Normalize
+        // 1) Normalize + detect language on the original script
+        let unicode = normalize_unicode(&raw);
         let normalized = normalize_for_matching(&raw);
+        let language = if cfg.detect_language {
+            Language::detect(&unicode)
+        } else {
+            Language::English
+        };
 
         // 2) Base tokens
-        let mut tokens = tokenize_basic(&normalized, cfg);
+        let mut tokens = if language == Language::English {
+            tokenize_basic(&normalized, cfg)
+        } else {
+            tokenize_unicode(&unicode, language, cfg)
+        };
 
         // 3) Stemming (optional)
         let mut stemmed_tokens = Vec::new();
         if cfg.use_stemming {
-            stemmed_tokens = stem_tokens(&tokens);
+            stemmed_tokens = stem_tokens(&tokens, language);
+        }
+
+        // Latin-script stems are folded to ASCII afterwards so they line up with
+        // the deunicoded entity dictionaries and market text.
+        if language != Language::English && language.is_latin_script() {
+            for t in tokens.iter_mut().chain(stemmed_tokens.iter_mut()) {
+                *t = deunicode(t);
+            }
         }
 
         // Decide which tokens to use for n-grams:
@@ -108,6 +135,7 @@ impl TokenizedNews {
             stemmed_tokens,
             bigrams,
             trigrams,
+            language,
         }
     }
 }
@@ -224,9 +252,28 @@ fn tokenize_basic(text: &str, cfg: &TokenizationConfig) -> Vec<String> {
     tokens
 }
 
-/// Stem tokens using the Porter stemmer (English).
-fn stem_tokens(tokens: &[String]) -> Vec<String> {
-    let stemmer = Stemmer::create(Algorithm::English);
+/// Tokenizer for non-English text: any-script words plus the same number tokens,
+/// filtered by the language's stopwords (and the configured extras).
+fn tokenize_unicode(text: &str, language: Language, cfg: &TokenizationConfig) -> Vec<String> {
+    lazy_static! {
+        static ref TOKEN_RE: Regex = Regex::new(r"\p{L}+|\d+(?:\.\d+)?(?:%|bps|bp)?").unwrap();
+    }
+
+    let stopwords = language.stopwords();
+    TOKEN_RE
+        .find_iter(text)
+        .map(|m| m.as_str().to_lowercase())
+        .filter(|t| t.chars().count() > 1)
+        .filter(|t| {
+            !cfg.remove_stopwords
+                || !(stopwords.contains(&t.as_str()) || cfg.extra_stopwords.contains(t))
+        })
+        .collect()
+}
+
+/// Stem tokens using the Snowball stemmer for `language`.
+fn stem_tokens(tokens: &[String], language: Language) -> Vec<String> {
+    let stemmer = Stemmer::create(language.algorithm());
     tokens.iter().map(|t| stemmer.stem(t).to_string()).collect()
 }
 
@@ -262,6 +309,10 @@ mod tests {
             feed: "test".to_string(),
             published: None,
             labels: vec![],
+            guid: None,
+            author: None,
+            updated: None,
+            body: None,
         }
     }
 
@@ -321,6 +372,32 @@ mod tests {
         assert!(trigrams.contains(&"central bank digit".to_string()));
     }
 
+    #[test]
+    fn test_non_english_tokenization() {
+        let cfg = TokenizationConfig::default();
+
+        let tokenized = TokenizedNews::from_raw(
+            make_raw("Die Europäische Zentralbank senkt die Zinsen"),
+            &cfg,
+        );
+        assert_eq!(tokenized.language, Language::German);
+        assert!(!tokenized.tokens.contains(&"die".to_string()));
+        assert!(tokenized.tokens.contains(&"europaische".to_string()));
+        assert!(tokenized.stemmed_tokens.contains(&"zins".to_string()));
+
+        let tokenized =
+            TokenizedNews::from_raw(make_raw("ЦБ России повысил ключевую ставку"), &cfg);
+        assert_eq!(tokenized.language, Language::Russian);
+        assert!(tokenized.stemmed_tokens.contains(&"ставк".to_string()));
+
+        let cfg = TokenizationConfig {
+            detect_language: false,
+            ..TokenizationConfig::default()
+        };
+        let tokenized = TokenizedNews::from_raw(make_raw("La BCE baisse ses taux"), &cfg);
+        assert_eq!(tokenized.language, Language::English);
+    }
+
     #[test]
     fn test_config_flags() {
         let mut cfg = TokenizationConfig::default();
@@ -17,12 +17,43 @@ use rust_decimal::Decimal;
 #[derive(Debug, Clone, Default)]
 pub struct RawCandidate {
     pub market_id: String,
-    #[allow(dead_code)]
+    /// BM25 score from the lexical search, 0.0 if only found semantically.
     pub bm25_score: f32,
+    /// Cosine similarity from the semantic search, 0.0 if only found lexically.
+    pub semantic_score: f32,
+    /// Hybrid score combining both searches (see `RetrievalCfg`).
+    pub fused_score: f32,
     pub title: String,
     pub description: String,
     pub tags: Vec<String>,
     pub resolution_date: Option<i64>, // timestamp in seconds
+    /// Parent Gamma event; sibling markets share it.
+    pub event_id: Option<String>,
+    pub volume: Option<f64>,
+    pub liquidity: Option<f64>,
+    /// Cross-encoder relevance to the news (0..1); None until reranked.
+    pub relevance: Option<f32>,
+}
+
+impl RawCandidate {
+    /// Market ID plus Gamma metadata, for logs.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(event_id) = &self.event_id {
+            parts.push(format!("event {}", event_id));
+        }
+        if let Some(v) = self.volume {
+            parts.push(format!("vol {:.0}", v));
+        }
+        if let Some(l) = self.liquidity {
+            parts.push(format!("liq {:.0}", l));
+        }
+        if parts.is_empty() {
+            self.market_id.clone()
+        } else {
+            format!("{} ({})", self.market_id, parts.join(", "))
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]